@@ -0,0 +1,206 @@
+use num::complex::{Complex, Complex64};
+
+use crate::error::FftError;
+use crate::fft::{fft, fft_complex, ifft, ifft_complex};
+use crate::filter::OverlapSave;
+use crate::utils::mul_vv_complex;
+
+// Adaptation selects the step-size rule of the frequency-domain adaptive
+// filter. Lms uses a fixed step for every bin, Nlms divides the step by a
+// running estimate of the input power in each bin, which makes convergence
+// independent of the input spectrum.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Adaptation {
+    Lms,
+    Nlms,
+}
+
+// Fdaf is a block frequency-domain adaptive filter (constrained overlap-save
+// FDAF). Every block of block_len input samples is filtered with a
+// block_len-tap FIR filter whose weights are adapted in the frequency domain
+// so that the output follows a desired signal. Typical uses are acoustic echo
+// cancellation, where the desired signal is the microphone and the error is
+// the echo-free residual, and channel equalization.
+#[derive(Debug, Clone)]
+pub struct Fdaf {
+    block_len: usize,
+    mu: f64,
+    adaptation: Adaptation,
+    weights: Vec<Complex64>,
+    power: Vec<f64>,
+    input: OverlapSave,
+}
+
+// Smoothing factor of the per-bin power estimate used by Nlms.
+const POWER_SMOOTHING: f64 = 0.9;
+// Regularization added to the power estimate to avoid division by zero.
+const POWER_FLOOR: f64 = 1e-8;
+
+impl Fdaf {
+    // new returns a filter with block_len taps, initialized to zero. block_len
+    // must be a power of two.
+    pub fn new(block_len: usize, mu: f64, adaptation: Adaptation) -> Result<Self, FftError> {
+        if !block_len.is_power_of_two() {
+            return Err(FftError::NotAPowerOfTwo(block_len));
+        }
+        if mu <= 0_f64 {
+            return Err(FftError::InvalidParameter("step size must be positive"));
+        }
+        Ok(Fdaf {
+            block_len,
+            mu,
+            adaptation,
+            weights: vec![Complex64::default(); 2 * block_len],
+            power: vec![0_f64; 2 * block_len],
+            input: OverlapSave::new(block_len),
+        })
+    }
+
+    // process filters x block by block, adapting towards d, and returns the
+    // filter output and the error d - y. Both inputs must have the same length,
+    // a multiple of the block length.
+    pub fn process(&mut self, x: &[f64], d: &[f64]) -> Result<(Vec<f64>, Vec<f64>), FftError> {
+        if x.len() != d.len() {
+            return Err(FftError::LengthMismatch(x.len(), d.len()));
+        }
+        if !x.len().is_multiple_of(self.block_len) {
+            return Err(FftError::LengthMismatch(
+                x.len().div_ceil(self.block_len) * self.block_len,
+                x.len(),
+            ));
+        }
+
+        let m = self.block_len;
+        let mut y = Vec::with_capacity(x.len());
+        let mut e = Vec::with_capacity(x.len());
+        for (xb, db) in x.chunks(m).zip(d.chunks(m)) {
+            let x_freq = fft(self.input.frame(xb))?;
+
+            // Only the last block_len samples of the circular convolution are
+            // a valid linear convolution.
+            let y_time = ifft(&mul_vv_complex(&x_freq, &self.weights)?)?;
+            let yb = &y_time[m..];
+            let eb: Vec<f64> = db.iter().zip(yb.iter()).map(|(d, y)| d - y).collect();
+
+            let mut e_padded = vec![0_f64; m];
+            e_padded.extend_from_slice(&eb);
+            let e_freq = fft(&e_padded)?;
+
+            let step: Vec<Complex64> = match self.adaptation {
+                Adaptation::Lms => vec![Complex::new(self.mu, 0_f64); 2 * m],
+                Adaptation::Nlms => self
+                    .power
+                    .iter_mut()
+                    .zip(x_freq.iter())
+                    .map(|(p, xf)| {
+                        *p = POWER_SMOOTHING * *p + (1_f64 - POWER_SMOOTHING) * xf.norm_sqr();
+                        Complex::new(self.mu / (*p + POWER_FLOOR), 0_f64)
+                    })
+                    .collect(),
+            };
+
+            // Gradient constraint: the correlation is truncated to block_len
+            // lags so the weights keep describing a causal block_len-tap filter.
+            let corr: Vec<Complex64> = x_freq
+                .iter()
+                .zip(e_freq.iter())
+                .zip(step.iter())
+                .map(|((xf, ef), s)| xf.conj() * ef * s)
+                .collect();
+            let mut grad = ifft_complex(&corr)?;
+            grad[m..].iter_mut().for_each(|g| *g = Complex64::default());
+            let grad_freq = fft_complex(&grad)?;
+            for (w, g) in self.weights.iter_mut().zip(grad_freq.iter()) {
+                *w += g;
+            }
+
+            y.extend_from_slice(yb);
+            e.extend(eb);
+        }
+        Ok((y, e))
+    }
+
+    // taps returns the current time-domain impulse response of the filter
+    pub fn taps(&self) -> Result<Vec<f64>, FftError> {
+        let mut taps = ifft(&self.weights)?;
+        taps.truncate(self.block_len);
+        Ok(taps)
+    }
+
+    // reset clears the weights, the power estimate and the input history
+    pub fn reset(&mut self) {
+        self.weights
+            .iter_mut()
+            .for_each(|w| *w = Complex64::default());
+        self.power.iter_mut().for_each(|p| *p = 0_f64);
+        self.input.reset();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    fn fir(h: &[f64], x: &[f64]) -> Vec<f64> {
+        (0..x.len())
+            .map(|n| {
+                (0..h.len())
+                    .filter(|&j| j <= n)
+                    .map(|j| h[j] * x[n - j])
+                    .sum()
+            })
+            .collect()
+    }
+
+    fn identify(adaptation: Adaptation, mu: f64) {
+        let mut rng = StdRng::seed_from_u64(3);
+        let h = [0.5, -0.3, 0.2, 0.1, 0.0, -0.05, 0.02, 0.01];
+        let x: Vec<f64> = (0..16384).map(|_| rng.random::<f64>() - 0.5).collect();
+        let d = fir(&h, &x);
+
+        let mut filter = Fdaf::new(16, mu, adaptation).unwrap();
+        let (_, e) = filter.process(&x, &d).unwrap();
+
+        let taps = filter.taps().unwrap();
+        for (i, t) in taps.iter().enumerate() {
+            let expected = h.get(i).copied().unwrap_or(0_f64);
+            assert!(
+                (t - expected).abs() < 1e-3,
+                "tap {}: {} vs {}",
+                i,
+                t,
+                expected
+            );
+        }
+        let tail: f64 = e[e.len() - 1024..].iter().map(|v| v * v).sum();
+        assert!(tail < 1e-6, "residual energy {}", tail);
+    }
+
+    #[test]
+    fn test_fdaf_nlms_identifies_system() {
+        identify(Adaptation::Nlms, 0.5);
+    }
+
+    #[test]
+    fn test_fdaf_lms_identifies_system() {
+        identify(Adaptation::Lms, 0.05);
+    }
+
+    #[test]
+    fn test_fdaf_invalid_input() {
+        assert!(matches!(
+            Fdaf::new(12, 0.1, Adaptation::Lms),
+            Err(FftError::NotAPowerOfTwo(12))
+        ));
+        let mut filter = Fdaf::new(8, 0.1, Adaptation::Lms).unwrap();
+        assert!(matches!(
+            filter.process(&[0.0; 8], &[0.0; 4]),
+            Err(FftError::LengthMismatch(8, 4))
+        ));
+        assert!(matches!(
+            filter.process(&[0.0; 10], &[0.0; 10]),
+            Err(FftError::LengthMismatch(16, 10))
+        ));
+    }
+}
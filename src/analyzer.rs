@@ -0,0 +1,143 @@
+use crate::error::FftError;
+
+// Averaging selects how SpectrumAnalyzer combines successive frames.
+// Exponential weights the newest frame by alpha and the running average by
+// 1 - alpha, so old frames fade out; Linear is the plain mean of every frame
+// since the last reset.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Averaging {
+    Exponential { alpha: f64 },
+    Linear,
+}
+
+// SpectrumAnalyzer keeps the running average, max-hold and min-hold of the
+// spectra of a live stream, e.g. power spectra of successive FFT frames.
+// All readouts are None until the first frame arrives.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpectrumAnalyzer {
+    bins: usize,
+    averaging: Averaging,
+    frames: usize,
+    average: Vec<f64>,
+    max_hold: Vec<f64>,
+    min_hold: Vec<f64>,
+}
+
+impl SpectrumAnalyzer {
+    pub fn new(bins: usize, averaging: Averaging) -> Result<Self, FftError> {
+        if bins == 0 {
+            return Err(FftError::InvalidParameter(
+                "number of bins must be greater than zero",
+            ));
+        }
+        if let Averaging::Exponential { alpha } = averaging {
+            if alpha.is_nan() || alpha <= 0_f64 || alpha > 1_f64 {
+                return Err(FftError::InvalidParameter("alpha must lie in (0, 1]"));
+            }
+        }
+        Ok(SpectrumAnalyzer {
+            bins,
+            averaging,
+            frames: 0,
+            average: Vec::with_capacity(bins),
+            max_hold: Vec::with_capacity(bins),
+            min_hold: Vec::with_capacity(bins),
+        })
+    }
+
+    pub fn bins(&self) -> usize {
+        self.bins
+    }
+
+    pub fn averaging(&self) -> Averaging {
+        self.averaging
+    }
+
+    // frames returns the number of frames pushed since the last reset
+    pub fn frames(&self) -> usize {
+        self.frames
+    }
+
+    // push folds one frame of bins values into all readouts
+    pub fn push(&mut self, frame: &[f64]) -> Result<(), FftError> {
+        if frame.len() != self.bins {
+            return Err(FftError::LengthMismatch(self.bins, frame.len()));
+        }
+        self.frames += 1;
+        if self.frames == 1 {
+            self.average.extend_from_slice(frame);
+            self.max_hold.extend_from_slice(frame);
+            self.min_hold.extend_from_slice(frame);
+            return Ok(());
+        }
+        let weight = match self.averaging {
+            Averaging::Exponential { alpha } => alpha,
+            Averaging::Linear => 1_f64 / self.frames as f64,
+        };
+        for (k, &v) in frame.iter().enumerate() {
+            self.average[k] += weight * (v - self.average[k]);
+            self.max_hold[k] = self.max_hold[k].max(v);
+            self.min_hold[k] = self.min_hold[k].min(v);
+        }
+        Ok(())
+    }
+
+    // reset forgets every frame, keeping the configuration
+    pub fn reset(&mut self) {
+        self.frames = 0;
+        self.average.clear();
+        self.max_hold.clear();
+        self.min_hold.clear();
+    }
+
+    pub fn average(&self) -> Option<&[f64]> {
+        self.readout(&self.average)
+    }
+
+    pub fn max_hold(&self) -> Option<&[f64]> {
+        self.readout(&self.max_hold)
+    }
+
+    pub fn min_hold(&self) -> Option<&[f64]> {
+        self.readout(&self.min_hold)
+    }
+
+    fn readout<'a>(&self, values: &'a [f64]) -> Option<&'a [f64]> {
+        (self.frames > 0).then_some(values)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_analyzer_averages_and_holds() {
+        let mut linear = SpectrumAnalyzer::new(2, Averaging::Linear).unwrap();
+        let mut exponential =
+            SpectrumAnalyzer::new(2, Averaging::Exponential { alpha: 0.5 }).unwrap();
+        assert_eq!(linear.average(), None);
+        for frame in [[1_f64, 6_f64], [2_f64, 4_f64], [6_f64, 2_f64]] {
+            linear.push(&frame).unwrap();
+            exponential.push(&frame).unwrap();
+        }
+        assert_eq!(linear.frames(), 3);
+        assert_eq!(linear.average(), Some(&[3_f64, 4_f64][..]));
+        assert_eq!(exponential.average(), Some(&[3.75_f64, 3.5_f64][..]));
+        assert_eq!(linear.max_hold(), Some(&[6_f64, 6_f64][..]));
+        assert_eq!(linear.min_hold(), Some(&[1_f64, 2_f64][..]));
+
+        linear.reset();
+        assert_eq!(linear.frames(), 0);
+        assert_eq!(linear.max_hold(), None);
+        linear.push(&[5_f64, 5_f64]).unwrap();
+        assert_eq!(linear.min_hold(), Some(&[5_f64, 5_f64][..]));
+
+        assert!(matches!(
+            linear.push(&[1_f64]),
+            Err(FftError::LengthMismatch(2, 1))
+        ));
+        assert!(SpectrumAnalyzer::new(4, Averaging::Exponential { alpha: 0_f64 }).is_err());
+        assert!(SpectrumAnalyzer::new(0, Averaging::Linear).is_err());
+    }
+}
@@ -0,0 +1,327 @@
+use num::complex::Complex64;
+
+use crate::cast::{as_bytes, from_bytes};
+use crate::error::FftError;
+use crate::utils::checked_len;
+
+// An archive is a flat buffer holding a spectrogram (or a single spectrum as
+// one frame) that can be written to disk once and later viewed in place, e.g.
+// from a memory-mapped file, without parsing or copying the bins.
+//
+// Layout: a 32-byte header holding the magic FFTSPEC1 and three u64 values,
+// the frame count, the bin count and a byte-order tag, followed by frames * bins Complex64
+// values in native byte order. The header keeps the bins 16-byte aligned
+// relative to the start of the buffer; archives read on a machine of the
+// opposite byte order are rejected by the tag. With the rkyv feature,
+// SpectrogramRecord stores spectrograms in rkyv's portable format instead.
+const MAGIC: &[u8; 8] = b"FFTSPEC1";
+const BYTE_ORDER: u64 = 0x0102_0304_0506_0708;
+const HEADER_LEN: usize = 32;
+
+fn header(frames: usize, bins: usize) -> [u8; HEADER_LEN] {
+    let mut out = [0_u8; HEADER_LEN];
+    out[..8].copy_from_slice(MAGIC);
+    for (i, v) in [frames as u64, bins as u64, BYTE_ORDER].iter().enumerate() {
+        out[8 * i + 8..8 * i + 16].copy_from_slice(&v.to_ne_bytes());
+    }
+    out
+}
+
+fn read_u64(bytes: &[u8], i: usize) -> u64 {
+    let mut v = [0_u8; 8];
+    v.copy_from_slice(&bytes[8 * i..8 * i + 8]);
+    u64::from_ne_bytes(v)
+}
+
+// archive_spectrogram serializes equally long frames, such as the output of
+// stft, into an archive
+pub fn archive_spectrogram(frames: &[Vec<Complex64>]) -> Result<Vec<u8>, FftError> {
+    let bins = frames.first().map_or(0, |f| f.len());
+    let mut out = Vec::with_capacity(HEADER_LEN + 16 * bins * frames.len());
+    out.extend_from_slice(&header(frames.len(), bins));
+    for frame in frames {
+        if frame.len() != bins {
+            return Err(FftError::LengthMismatch(bins, frame.len()));
+        }
+        out.extend_from_slice(as_bytes(frame));
+    }
+    Ok(out)
+}
+
+// archive_spectrum serializes a single spectrum as a one-frame archive
+pub fn archive_spectrum(spectrum: &[Complex64]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(HEADER_LEN + 16 * spectrum.len());
+    out.extend_from_slice(&header(1, spectrum.len()));
+    out.extend_from_slice(as_bytes(spectrum));
+    out
+}
+
+// ArchivedSpectrogram is a borrowed, zero-copy view of an archive.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ArchivedSpectrogram<'a> {
+    frames: usize,
+    bins: usize,
+    data: &'a [Complex64],
+}
+
+impl<'a> ArchivedSpectrogram<'a> {
+    // from_bytes validates the header of an archive and views its bins in
+    // place; bytes must be 8-byte aligned, which page-aligned memory maps are
+    pub fn from_bytes(bytes: &'a [u8]) -> Result<Self, FftError> {
+        if bytes.len() < HEADER_LEN || &bytes[..8] != MAGIC {
+            return Err(FftError::InvalidParameter("not a spectrum archive"));
+        }
+        if read_u64(bytes, 3) != BYTE_ORDER {
+            return Err(FftError::InvalidParameter(
+                "archive was written with a different byte order",
+            ));
+        }
+        let frames = read_u64(bytes, 1) as usize;
+        let bins = read_u64(bytes, 2) as usize;
+        let data = from_bytes(&bytes[HEADER_LEN..])?;
+        let expected = checked_len(&[frames, bins])?;
+        if data.len() != expected {
+            return Err(FftError::LengthMismatch(expected, data.len()));
+        }
+        Ok(ArchivedSpectrogram { frames, bins, data })
+    }
+
+    pub fn frames(&self) -> usize {
+        self.frames
+    }
+
+    pub fn bins(&self) -> usize {
+        self.bins
+    }
+
+    // frame returns the bins of frame i, or None past the last frame
+    pub fn frame(&self, i: usize) -> Option<&'a [Complex64]> {
+        if i >= self.frames {
+            return None;
+        }
+        Some(&self.data[i * self.bins..(i + 1) * self.bins])
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &'a [Complex64]> + '_ {
+        (0..self.frames).filter_map(move |i| self.frame(i))
+    }
+
+    // as_slice returns all bins, frame after frame
+    pub fn as_slice(&self) -> &'a [Complex64] {
+        self.data
+    }
+
+    // to_spectrogram copies the archive back into owned frames
+    pub fn to_spectrogram(&self) -> Vec<Vec<Complex64>> {
+        self.iter().map(|f| f.to_vec()).collect()
+    }
+}
+
+#[cfg(feature = "rkyv")]
+mod record {
+    use num::complex::Complex64;
+    use rkyv::rancor;
+    use rkyv::util::AlignedVec;
+
+    use crate::error::FftError;
+
+    // SpectrogramRecord is a spectrogram in the form rkyv archives: bins
+    // values per frame, each an [re, im] pair, frame after frame. Its
+    // archived form ArchivedSpectrogramRecord is read in place from the bytes
+    // of to_bytes, e.g. a memory-mapped file, after access has validated
+    // them.
+    #[derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize, Debug, Clone, PartialEq)]
+    pub struct SpectrogramRecord {
+        pub bins: u64,
+        pub data: Vec<[f64; 2]>,
+    }
+
+    impl SpectrogramRecord {
+        // from_frames copies equally long frames, such as the output of stft
+        pub fn from_frames(frames: &[Vec<Complex64>]) -> Result<Self, FftError> {
+            let bins = frames.first().map_or(0, |f| f.len());
+            let mut data = Vec::with_capacity(bins * frames.len());
+            for frame in frames {
+                if frame.len() != bins {
+                    return Err(FftError::LengthMismatch(bins, frame.len()));
+                }
+                data.extend(frame.iter().map(|c| [c.re, c.im]));
+            }
+            Ok(SpectrogramRecord {
+                bins: bins as u64,
+                data,
+            })
+        }
+
+        // from_spectrum records a single spectrum as one frame
+        pub fn from_spectrum(spectrum: &[Complex64]) -> Self {
+            SpectrogramRecord {
+                bins: spectrum.len() as u64,
+                data: spectrum.iter().map(|c| [c.re, c.im]).collect(),
+            }
+        }
+
+        // to_bytes archives the record
+        pub fn to_bytes(&self) -> Result<AlignedVec, FftError> {
+            rkyv::to_bytes::<rancor::Error>(self)
+                .map_err(|_| FftError::InvalidParameter("spectrogram could not be archived"))
+        }
+
+        // access validates the bytes of an archived record and views them in
+        // place; bytes must be 8-byte aligned, as AlignedVec and page-aligned
+        // memory maps are
+        pub fn access(bytes: &[u8]) -> Result<&ArchivedSpectrogramRecord, FftError> {
+            let archived = rkyv::access::<ArchivedSpectrogramRecord, rancor::Error>(bytes)
+                .map_err(|_| FftError::InvalidParameter("not an archived spectrogram"))?;
+            let bins = archived.bins.to_native() as usize;
+            if bins == 0 && !archived.data.is_empty()
+                || bins != 0 && archived.data.len() % bins != 0
+            {
+                return Err(FftError::InvalidParameter(
+                    "archived spectrogram has a partial frame",
+                ));
+            }
+            Ok(archived)
+        }
+    }
+
+    impl ArchivedSpectrogramRecord {
+        pub fn bins(&self) -> usize {
+            self.bins.to_native() as usize
+        }
+
+        pub fn frames(&self) -> usize {
+            match self.bins() {
+                0 => 0,
+                bins => self.data.len() / bins,
+            }
+        }
+
+        // frame returns the bins of frame i, converted one at a time, or None
+        // past the last frame
+        pub fn frame(&self, i: usize) -> Option<impl Iterator<Item = Complex64> + '_> {
+            if i >= self.frames() {
+                return None;
+            }
+            let bins = self.bins();
+            Some(
+                self.data[i * bins..(i + 1) * bins]
+                    .iter()
+                    .map(|[re, im]| Complex64::new(re.to_native(), im.to_native())),
+            )
+        }
+
+        // to_spectrogram copies the record back into owned frames
+        pub fn to_spectrogram(&self) -> Vec<Vec<Complex64>> {
+            (0..self.frames())
+                .filter_map(|i| self.frame(i))
+                .map(|f| f.collect())
+                .collect()
+        }
+    }
+}
+
+#[cfg(feature = "rkyv")]
+pub use self::record::{ArchivedSpectrogramRecord, SpectrogramRecord};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cast::as_bytes_mut;
+
+    // aligned copies bytes into 16-byte aligned storage, standing in for a
+    // memory-mapped file
+    fn aligned(bytes: &[u8]) -> Vec<Complex64> {
+        let mut storage = vec![Complex64::default(); bytes.len() / 16];
+        as_bytes_mut(&mut storage).copy_from_slice(bytes);
+        storage
+    }
+
+    #[cfg(feature = "window")]
+    #[test]
+    fn test_archive_round_trip() {
+        let x = crate::utils::generate_random_values();
+        let window = crate::window::hann(64);
+        let spectrogram = crate::stft::stft(&x, 64, 32, &window).unwrap();
+        let storage = aligned(&archive_spectrogram(&spectrogram).unwrap());
+        let archived = ArchivedSpectrogram::from_bytes(as_bytes(&storage)).unwrap();
+        assert_eq!(archived.frames(), spectrogram.len());
+        assert_eq!(archived.bins(), 64);
+        assert_eq!(archived.frame(3).unwrap(), &spectrogram[3][..]);
+        assert_eq!(archived.frame(archived.frames()), None);
+        assert_eq!(archived.to_spectrogram(), spectrogram);
+    }
+
+    #[test]
+    fn test_archive_spectrum_is_zero_copy() {
+        let spectrum = crate::fft::fft(&[1.0, 2.0, 3.0, 4.0]).unwrap();
+        let storage = aligned(&archive_spectrum(&spectrum));
+        let archived = ArchivedSpectrogram::from_bytes(as_bytes(&storage)).unwrap();
+        assert_eq!(archived.as_slice(), &spectrum[..]);
+        assert_eq!(archived.as_slice().as_ptr(), storage[2..].as_ptr());
+    }
+
+    #[cfg(feature = "rkyv")]
+    #[test]
+    fn test_rkyv_round_trip() {
+        let x = crate::utils::generate_random_values();
+        let frames: Vec<Vec<Complex64>> =
+            x.chunks(64).map(|c| crate::fft::fft(c).unwrap()).collect();
+        let record = SpectrogramRecord::from_frames(&frames).unwrap();
+        let bytes = record.to_bytes().unwrap();
+        let archived = SpectrogramRecord::access(&bytes).unwrap();
+        assert_eq!((archived.frames(), archived.bins()), (16, 64));
+        assert!(archived.frame(2).unwrap().eq(frames[2].iter().copied()));
+        assert!(archived.frame(16).is_none());
+        assert_eq!(archived.to_spectrogram(), frames);
+        let owned: SpectrogramRecord =
+            rkyv::deserialize::<_, rkyv::rancor::Error>(archived).unwrap();
+        assert_eq!(owned, record);
+        // The bins are read from the buffer itself.
+        let first = archived.data.as_ptr() as usize;
+        assert!((bytes.as_ptr() as usize..bytes.as_ptr() as usize + bytes.len()).contains(&first));
+
+        let spectrum = SpectrogramRecord::from_spectrum(&frames[0]);
+        let bytes = spectrum.to_bytes().unwrap();
+        let archived = SpectrogramRecord::access(&bytes).unwrap();
+        assert_eq!(archived.to_spectrogram(), vec![frames[0].clone()]);
+
+        assert!(matches!(
+            SpectrogramRecord::from_frames(&[frames[0].clone(), frames[1][..3].to_vec()]),
+            Err(FftError::LengthMismatch(64, 3))
+        ));
+        assert!(matches!(
+            SpectrogramRecord::access(&bytes[..bytes.len() - 8]),
+            Err(FftError::InvalidParameter(_))
+        ));
+        let partial = SpectrogramRecord {
+            bins: 3,
+            data: vec![[0_f64; 2]; 4],
+        };
+        assert!(matches!(
+            SpectrogramRecord::access(&partial.to_bytes().unwrap()),
+            Err(FftError::InvalidParameter(_))
+        ));
+    }
+
+    #[test]
+    fn test_archive_rejects_bad_input() {
+        let ragged = vec![vec![Complex64::default(); 4], vec![Complex64::default(); 3]];
+        assert!(matches!(
+            archive_spectrogram(&ragged),
+            Err(FftError::LengthMismatch(4, 3))
+        ));
+
+        let mut storage = aligned(&archive_spectrum(&[Complex64::default(); 4]));
+        let truncated = &as_bytes(&storage)[..HEADER_LEN + 32];
+        assert!(matches!(
+            ArchivedSpectrogram::from_bytes(truncated),
+            Err(FftError::LengthMismatch(4, 2))
+        ));
+        storage[0] = Complex64::default();
+        assert!(matches!(
+            ArchivedSpectrogram::from_bytes(as_bytes(&storage)),
+            Err(FftError::InvalidParameter(_))
+        ));
+    }
+}
@@ -0,0 +1,108 @@
+use arrow_array::{Array, FixedSizeListArray, Float64Array};
+use arrow_schema::{DataType, Field};
+use num::complex::Complex64;
+use std::sync::Arc;
+
+use crate::cast::{from_interleaved, into_interleaved};
+use crate::error::FftError;
+
+// Conversions between signals or spectra and arrow-rs arrays, so transforms
+// can run on the columns of Polars or DataFusion pipelines. Real data is a
+// Float64Array; complex data is a FixedSizeList<Float64, 2> whose child holds
+// interleaved re, im values. Neither direction copies the values, and slices
+// of arrays are honoured.
+
+// real_array hands a real signal over as a Float64Array
+pub fn real_array(x: Vec<f64>) -> Float64Array {
+    Float64Array::from(x)
+}
+
+// complex_array hands complex samples over as a FixedSizeList<Float64, 2>
+// array
+pub fn complex_array(x: Vec<Complex64>) -> FixedSizeListArray {
+    let item = Arc::new(Field::new("item", DataType::Float64, false));
+    let values = Float64Array::from(into_interleaved(x));
+    FixedSizeListArray::new(item, 2, Arc::new(values), None)
+}
+
+// real_values views the values of a Float64 array without nulls
+pub fn real_values(array: &dyn Array) -> Result<&[f64], FftError> {
+    let array = array
+        .as_any()
+        .downcast_ref::<Float64Array>()
+        .ok_or(FftError::InvalidParameter("expected a Float64 array"))?;
+    if array.null_count() != 0 {
+        return Err(FftError::InvalidParameter("null values are not supported"));
+    }
+    Ok(array.values())
+}
+
+// complex_values views the values of a FixedSizeList<Float64, 2> array
+// without nulls as complex samples
+pub fn complex_values(array: &dyn Array) -> Result<&[Complex64], FftError> {
+    let array = array
+        .as_any()
+        .downcast_ref::<FixedSizeListArray>()
+        .filter(|a| a.value_length() == 2)
+        .ok_or(FftError::InvalidParameter(
+            "expected a FixedSizeList<Float64, 2> array",
+        ))?;
+    if array.null_count() != 0 {
+        return Err(FftError::InvalidParameter("null values are not supported"));
+    }
+    from_interleaved(real_values(array.values().as_ref())?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow_array::Int32Array;
+
+    #[test]
+    fn test_arrow_real_round_trip() {
+        let x = crate::utils::generate_random_values();
+        let expected = x.clone();
+        let ptr = x.as_ptr();
+        let array = real_array(x);
+        assert_eq!(real_values(&array).unwrap(), &expected[..]);
+        assert_eq!(real_values(&array).unwrap().as_ptr(), ptr);
+        assert!(complex_values(&array).is_err());
+        let sliced = array.slice(10, 20);
+        assert_eq!(real_values(&sliced).unwrap(), &expected[10..30]);
+        assert!(matches!(
+            real_values(&Int32Array::from(vec![1, 2])),
+            Err(FftError::InvalidParameter(_))
+        ));
+    }
+
+    #[test]
+    fn test_arrow_complex_round_trip() {
+        let spectrum = crate::fft::fft(crate::utils::generate_random_values()).unwrap();
+        let owned = spectrum.clone();
+        let ptr = owned.as_ptr() as *const f64;
+        let array = complex_array(owned);
+        assert_eq!(array.len(), 1024);
+        assert_eq!(array.values().len(), 2048);
+        assert_eq!(real_values(array.values().as_ref()).unwrap().as_ptr(), ptr);
+        assert_eq!(complex_values(&array).unwrap(), &spectrum[..]);
+        // Slicing on the producer side is honoured.
+        let sliced = array.slice(10, 20);
+        assert_eq!(complex_values(&sliced).unwrap(), &spectrum[10..30]);
+        assert!(real_values(&array).is_err());
+    }
+
+    #[test]
+    fn test_arrow_rejects_nulls() {
+        let array = Float64Array::from(vec![Some(1.0), None]);
+        assert!(matches!(
+            real_values(&array),
+            Err(FftError::InvalidParameter(_))
+        ));
+        let item = Arc::new(Field::new("item", DataType::Float64, true));
+        let list = FixedSizeListArray::new(item, 2, Arc::new(array), None);
+        assert!(matches!(
+            complex_values(&list),
+            Err(FftError::InvalidParameter(_))
+        ));
+    }
+}
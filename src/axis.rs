@@ -0,0 +1,188 @@
+use crate::error::FftError;
+
+// FrequencyAxis maps the bins of an n-point transform of a signal sampled at
+// sample_rate Hz to frequencies and back. Bin k holds k * sample_rate / n Hz
+// for k <= n / 2 and the negative frequency (k - n) * sample_rate / n above;
+// for even n the Nyquist bin n / 2 is reported as +sample_rate / 2.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FrequencyAxis {
+    n: usize,
+    sample_rate: f64,
+}
+
+impl FrequencyAxis {
+    pub fn new(n: usize, sample_rate: f64) -> Result<Self, FftError> {
+        if n == 0 {
+            return Err(FftError::InvalidParameter("axis must have bins"));
+        }
+        if !(sample_rate > 0_f64 && sample_rate.is_finite()) {
+            return Err(FftError::InvalidParameter("sample rate must be positive"));
+        }
+        Ok(FrequencyAxis { n, sample_rate })
+    }
+
+    pub fn len(&self) -> usize {
+        self.n
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.n == 0
+    }
+
+    pub fn sample_rate(&self) -> f64 {
+        self.sample_rate
+    }
+
+    // resolution returns the spacing of the bins in Hz
+    pub fn resolution(&self) -> f64 {
+        self.sample_rate / self.n as f64
+    }
+
+    pub fn nyquist(&self) -> f64 {
+        self.sample_rate / 2_f64
+    }
+
+    // one_sided_len returns the number of bins from DC to Nyquist, n / 2 + 1,
+    // as kept for real signals
+    pub fn one_sided_len(&self) -> usize {
+        self.n / 2 + 1
+    }
+
+    // bin_to_hz returns the signed frequency of bin k, or None past the end
+    pub fn bin_to_hz(&self, k: usize) -> Option<f64> {
+        if k >= self.n {
+            None
+        } else if 2 * k <= self.n {
+            Some(k as f64 * self.resolution())
+        } else {
+            Some(-((self.n - k) as f64) * self.resolution())
+        }
+    }
+
+    // hz_to_bin returns the one-sided bin 0..=n/2 nearest to f Hz, or None
+    // if f is not finite or lies more than half a bin below 0 Hz or above
+    // Nyquist. Negative frequencies are reached by hz_to_bin_fractional.
+    pub fn hz_to_bin(&self, f: f64) -> Option<usize> {
+        let half_bin = self.resolution() / 2_f64;
+        if !f.is_finite() || f < -half_bin || f > self.nyquist() + half_bin {
+            return None;
+        }
+        Some(((f / self.resolution()).round().max(0_f64) as usize).min(self.n / 2))
+    }
+
+    // hz_to_bin_floor returns the bin at or below f Hz (towards the next
+    // lower frequency for negative f), or None if |f| exceeds Nyquist
+    pub fn hz_to_bin_floor(&self, f: f64) -> Option<usize> {
+        self.hz_to_bin_fractional(f)
+            .map(|k| k.floor() as usize % self.n)
+    }
+
+    // hz_to_bin_fractional returns the fractional bin position of f Hz, for
+    // interpolating between bins: f / resolution for f >= 0 and
+    // n + f / resolution for negative f, or None if |f| exceeds Nyquist
+    pub fn hz_to_bin_fractional(&self, f: f64) -> Option<f64> {
+        if f.is_nan() || f.abs() > self.nyquist() {
+            return None;
+        }
+        let k = f / self.resolution();
+        Some(if k < 0_f64 { self.n as f64 + k } else { k })
+    }
+
+    // iter returns the frequencies of all bins in bin order
+    pub fn iter(&self) -> Frequencies {
+        Frequencies {
+            axis: *self,
+            next: 0,
+            end: self.n,
+        }
+    }
+
+    // positive returns the frequencies of the one-sided bins 0..=n/2
+    pub fn positive(&self) -> Frequencies {
+        Frequencies {
+            axis: *self,
+            next: 0,
+            end: self.one_sided_len(),
+        }
+    }
+}
+
+impl IntoIterator for &FrequencyAxis {
+    type Item = f64;
+    type IntoIter = Frequencies;
+
+    fn into_iter(self) -> Frequencies {
+        self.iter()
+    }
+}
+
+// Frequencies iterates over the bin frequencies of a FrequencyAxis.
+#[derive(Debug, Clone)]
+pub struct Frequencies {
+    axis: FrequencyAxis,
+    next: usize,
+    end: usize,
+}
+
+impl Iterator for Frequencies {
+    type Item = f64;
+
+    fn next(&mut self) -> Option<f64> {
+        if self.next >= self.end {
+            return None;
+        }
+        self.next += 1;
+        self.axis.bin_to_hz(self.next - 1)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let left = self.end - self.next;
+        (left, Some(left))
+    }
+}
+
+impl ExactSizeIterator for Frequencies {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bin_to_hz_and_back() {
+        let axis = FrequencyAxis::new(8, 800.0).unwrap();
+        let all: Vec<f64> = axis.iter().collect();
+        assert_eq!(
+            all,
+            [0.0, 100.0, 200.0, 300.0, 400.0, -300.0, -200.0, -100.0]
+        );
+        assert_eq!(axis.positive().len(), 5);
+        assert_eq!(axis.bin_to_hz(8), None);
+        for (k, f) in axis.positive().enumerate() {
+            assert_eq!(axis.hz_to_bin(f), Some(k));
+        }
+        assert_eq!(axis.hz_to_bin(449.0), Some(4));
+        assert_eq!(axis.hz_to_bin(-49.0), Some(0));
+        for f in [-50.1, -400.0, 450.1, f64::NAN, f64::INFINITY] {
+            assert_eq!(axis.hz_to_bin(f), None);
+        }
+    }
+
+    #[test]
+    fn test_rounding_modes() {
+        let axis = FrequencyAxis::new(7, 700.0).unwrap();
+        assert_eq!(axis.hz_to_bin(160.0), Some(2));
+        assert_eq!(axis.hz_to_bin_floor(160.0), Some(1));
+        assert_eq!(axis.hz_to_bin_fractional(160.0), Some(1.6));
+        assert_eq!(axis.hz_to_bin_floor(-50.0), Some(6));
+        assert_eq!(axis.hz_to_bin(-40.0), Some(0));
+        assert_eq!(axis.hz_to_bin(-60.0), None);
+        assert_eq!(axis.hz_to_bin(390.0), Some(3));
+        // Odd lengths have no Nyquist bin: 350 Hz lies between bins 3 and 4.
+        assert_eq!(axis.bin_to_hz(3), Some(300.0));
+        assert_eq!(axis.bin_to_hz(4), Some(-300.0));
+        assert!(matches!(
+            FrequencyAxis::new(8, 0.0),
+            Err(FftError::InvalidParameter(_))
+        ));
+    }
+}
@@ -0,0 +1,339 @@
+use num::complex::Complex64;
+
+use crate::error::FftError;
+use crate::planner::{FftPlan, FftPlanner};
+
+// BackendPlan is a forward transform of a fixed length prepared by some
+// FftBackend. Implementations provide the in-place complex transform; the
+// real-input and strided forms are derived from it unless a backend has a
+// faster native path.
+pub trait BackendPlan: Send + Sync {
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // process computes the complex-to-complex transform of buffer in place
+    fn process(&self, buffer: &mut [Complex64]) -> Result<(), FftError>;
+
+    // process_real writes the bins 0..=len/2 of the spectrum of a real input
+    fn process_real(&self, input: &[f64], output: &mut [Complex64]) -> Result<(), FftError> {
+        if input.len() != self.len() {
+            return Err(FftError::LengthMismatch(self.len(), input.len()));
+        }
+        if output.len() != self.len() / 2 + 1 {
+            return Err(FftError::LengthMismatch(self.len() / 2 + 1, output.len()));
+        }
+        let mut buffer: Vec<Complex64> = input.iter().map(|&v| Complex64::new(v, 0_f64)).collect();
+        self.process(&mut buffer)?;
+        output.copy_from_slice(&buffer[..output.len()]);
+        Ok(())
+    }
+
+    // process_strided transforms in place the len values buffer[i * stride],
+    // e.g. one column of a row-major matrix
+    fn process_strided(&self, buffer: &mut [Complex64], stride: usize) -> Result<(), FftError> {
+        if stride == 0 {
+            return Err(FftError::InvalidParameter(
+                "stride must be greater than zero",
+            ));
+        }
+        let needed = self.len().saturating_sub(1) * stride + 1;
+        if buffer.len() < needed {
+            return Err(FftError::LengthMismatch(needed, buffer.len()));
+        }
+        let mut gathered: Vec<Complex64> = buffer
+            .iter()
+            .step_by(stride)
+            .take(self.len())
+            .cloned()
+            .collect();
+        self.process(&mut gathered)?;
+        for (slot, v) in buffer.iter_mut().step_by(stride).zip(gathered) {
+            *slot = v;
+        }
+        Ok(())
+    }
+}
+
+// FftBackend creates plans. The crate's transforms that take a backend work
+// with any implementation, so a GPU or an external library can be swapped in
+// for the built-in scalar code without changing the calling code.
+pub trait FftBackend: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    fn plan(&self, len: usize) -> Result<Box<dyn BackendPlan>, FftError>;
+}
+
+impl BackendPlan for FftPlan {
+    fn len(&self) -> usize {
+        FftPlan::len(self)
+    }
+
+    fn process(&self, buffer: &mut [Complex64]) -> Result<(), FftError> {
+        FftPlan::process(self, buffer)
+    }
+}
+
+// ScalarBackend is the built-in portable implementation, planning every
+// length through planner.
+#[derive(Debug, Clone, Default)]
+pub struct ScalarBackend {
+    pub planner: FftPlanner,
+}
+
+impl FftBackend for ScalarBackend {
+    fn name(&self) -> &'static str {
+        "scalar"
+    }
+
+    fn plan(&self, len: usize) -> Result<Box<dyn BackendPlan>, FftError> {
+        Ok(Box::new(self.planner.plan(len)?))
+    }
+}
+
+#[cfg(feature = "simd")]
+mod simd {
+    use super::*;
+    use crate::fft::fft_in_place;
+
+    // SimdPlan runs fft_in_place on buffers of its length.
+    #[derive(Debug, Clone, Copy)]
+    struct SimdPlan {
+        len: usize,
+    }
+
+    impl BackendPlan for SimdPlan {
+        fn len(&self) -> usize {
+            self.len
+        }
+
+        fn process(&self, buffer: &mut [Complex64]) -> Result<(), FftError> {
+            if buffer.len() != self.len {
+                return Err(FftError::LengthMismatch(self.len, buffer.len()));
+            }
+            fft_in_place(buffer)
+        }
+    }
+
+    // SimdBackend plans every length with the kernels of fft, whose radix-4
+    // passes run the AVX kernels of the simd module. Planning fails on CPUs
+    // without AVX, so callers can fall back to ScalarBackend.
+    #[derive(Debug, Clone, Copy, Default)]
+    pub struct SimdBackend;
+
+    impl FftBackend for SimdBackend {
+        fn name(&self) -> &'static str {
+            "simd"
+        }
+
+        fn plan(&self, len: usize) -> Result<Box<dyn BackendPlan>, FftError> {
+            if !crate::simd::available() {
+                return Err(FftError::Backend("AVX is not available on this CPU"));
+            }
+            Ok(Box::new(SimdPlan { len }))
+        }
+    }
+}
+
+#[cfg(feature = "simd")]
+pub use self::simd::SimdBackend;
+
+#[cfg(feature = "cuda")]
+mod cuda {
+    use super::*;
+    use crate::cuda::CudaFftPlan;
+
+    impl BackendPlan for CudaFftPlan {
+        fn len(&self) -> usize {
+            CudaFftPlan::len(self)
+        }
+
+        fn process(&self, buffer: &mut [Complex64]) -> Result<(), FftError> {
+            CudaFftPlan::process(self, buffer)
+        }
+    }
+
+    // CudaBackend runs single transforms through cuFFT; use CudaFftPlan
+    // directly to batch them.
+    #[derive(Debug, Clone, Copy, Default)]
+    pub struct CudaBackend;
+
+    impl FftBackend for CudaBackend {
+        fn name(&self) -> &'static str {
+            "cufft"
+        }
+
+        fn plan(&self, len: usize) -> Result<Box<dyn BackendPlan>, FftError> {
+            Ok(Box::new(CudaFftPlan::new(len, 1)?))
+        }
+    }
+}
+
+#[cfg(feature = "cuda")]
+pub use self::cuda::CudaBackend;
+
+#[cfg(all(feature = "accelerate", target_vendor = "apple"))]
+mod vdsp {
+    use super::*;
+    use crate::vdsp::VdspFftPlan;
+
+    impl BackendPlan for VdspFftPlan {
+        fn len(&self) -> usize {
+            VdspFftPlan::len(self)
+        }
+
+        fn process(&self, buffer: &mut [Complex64]) -> Result<(), FftError> {
+            VdspFftPlan::process(self, buffer)
+        }
+    }
+
+    #[derive(Debug, Clone, Copy, Default)]
+    pub struct VdspBackend;
+
+    impl FftBackend for VdspBackend {
+        fn name(&self) -> &'static str {
+            "vdsp"
+        }
+
+        fn plan(&self, len: usize) -> Result<Box<dyn BackendPlan>, FftError> {
+            Ok(Box::new(VdspFftPlan::new(len)?))
+        }
+    }
+}
+
+#[cfg(all(feature = "accelerate", target_vendor = "apple"))]
+pub use self::vdsp::VdspBackend;
+
+#[cfg(feature = "fftw")]
+mod fftw {
+    use super::*;
+    use crate::fftw::FftwPlan;
+
+    impl BackendPlan for FftwPlan {
+        fn len(&self) -> usize {
+            FftwPlan::len(self)
+        }
+
+        fn process(&self, buffer: &mut [Complex64]) -> Result<(), FftError> {
+            FftwPlan::process(self, buffer)
+        }
+    }
+
+    #[derive(Debug, Clone, Copy, Default)]
+    pub struct FftwBackend;
+
+    impl FftBackend for FftwBackend {
+        fn name(&self) -> &'static str {
+            "fftw"
+        }
+
+        fn plan(&self, len: usize) -> Result<Box<dyn BackendPlan>, FftError> {
+            Ok(Box::new(FftwPlan::new(len)?))
+        }
+    }
+}
+
+#[cfg(feature = "fftw")]
+pub use self::fftw::FftwBackend;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fft::fft;
+
+    #[test]
+    fn test_scalar_backend_real_and_complex() {
+        let x = crate::utils::generate_random_values();
+        let plan = ScalarBackend::default().plan(1024).unwrap();
+        let mut half = vec![Complex64::default(); 513];
+        plan.process_real(&x, &mut half).unwrap();
+        let expected = fft(&x).unwrap();
+        for (a, b) in half.iter().zip(expected.iter()) {
+            assert!((a - b).norm() < 1e-9);
+        }
+        assert!(matches!(
+            plan.process_real(&x[..10], &mut half),
+            Err(FftError::LengthMismatch(1024, 10))
+        ));
+    }
+
+    #[test]
+    fn test_backend_strided() {
+        // Transform the columns of a 4 x 8 row-major matrix.
+        let x = crate::utils::generate_random_values();
+        let mut matrix: Vec<Complex64> = x[..32].iter().map(|&v| Complex64::new(v, 0.0)).collect();
+        let plan = ScalarBackend::default().plan(4).unwrap();
+        for col in 0..8 {
+            plan.process_strided(&mut matrix[col..], 8).unwrap();
+        }
+        for col in 0..8 {
+            let column: Vec<f64> = (0..4).map(|row| x[8 * row + col]).collect();
+            let expected = fft(&column).unwrap();
+            for row in 0..4 {
+                assert!((matrix[8 * row + col] - expected[row]).norm() < 1e-12);
+            }
+        }
+    }
+
+    #[cfg(feature = "simd")]
+    #[test]
+    fn test_simd_backend_matches_scalar() {
+        let x = crate::utils::generate_random_values();
+        let scalar = ScalarBackend::default();
+        for len in [1, 8, 64, 1024, 12, 97] {
+            let plan = match SimdBackend.plan(len) {
+                Ok(plan) => plan,
+                Err(e) => {
+                    assert!(!crate::simd::available());
+                    assert!(matches!(e, FftError::Backend(_)));
+                    return;
+                }
+            };
+            let mut simd: Vec<Complex64> = (0..len)
+                .map(|i| Complex64::new(x[i], x[1023 - i]))
+                .collect();
+            let mut expected = simd.clone();
+            plan.process(&mut simd).unwrap();
+            scalar.plan(len).unwrap().process(&mut expected).unwrap();
+            for (a, b) in simd.iter().zip(&expected) {
+                assert!((a - b).norm() < 1e-9, "length {}", len);
+            }
+
+            let mut half = vec![Complex64::default(); len / 2 + 1];
+            plan.process_real(&x[..len], &mut half).unwrap();
+            let mut expected_half = half.clone();
+            scalar
+                .plan(len)
+                .unwrap()
+                .process_real(&x[..len], &mut expected_half)
+                .unwrap();
+            assert!(half
+                .iter()
+                .zip(&expected_half)
+                .all(|(a, b)| (a - b).norm() < 1e-9));
+        }
+    }
+
+    #[test]
+    fn test_backend_is_object_safe() {
+        let backends: Vec<Box<dyn FftBackend>> = vec![Box::new(ScalarBackend::default())];
+        for backend in &backends {
+            assert_eq!(backend.name(), "scalar");
+            let x: Vec<f64> = (0..12).map(|i| i as f64).collect();
+            let mut buffer: Vec<Complex64> = x.iter().map(|&v| Complex64::new(v, 0.0)).collect();
+            let plan = backend.plan(12).unwrap();
+            plan.process(&mut buffer).unwrap();
+            let expected = fft(&x).unwrap();
+            assert!(buffer
+                .iter()
+                .zip(&expected)
+                .all(|(a, b)| (a - b).norm() < 1e-12));
+            assert!(matches!(
+                plan.process(&mut buffer[..8]),
+                Err(FftError::LengthMismatch(12, 8))
+            ));
+        }
+    }
+}
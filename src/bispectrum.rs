@@ -0,0 +1,140 @@
+use num::complex::Complex64;
+
+use crate::error::FftError;
+use crate::fft::fft;
+use crate::window::hann;
+
+// Bispectrum holds segment-averaged third-order spectra over the bins
+// 0..=n/2 of the segment length n. bispectrum[k1][k2] is the average of
+// X(k1) X(k2) X*(k1 + k2), and bicoherence[k1][k2] its normalized magnitude
+// squared in [0, 1]; entries with k1 + k2 > n / 2 are left at zero.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bispectrum {
+    pub bispectrum: Vec<Vec<Complex64>>,
+    pub bicoherence: Vec<Vec<f64>>,
+}
+
+// bispectrum estimates the bispectrum and bicoherence of x from Hann-windowed
+// segments of segment_len samples taken every hop samples. A bicoherence
+// close to one at (k1, k2) means the phases of the components at k1, k2 and
+// k1 + k2 are coupled, as produced by quadratic nonlinearities; independent
+// components average towards zero as the number of segments grows.
+pub fn bispectrum(x: &[f64], segment_len: usize, hop: usize) -> Result<Bispectrum, FftError> {
+    if segment_len == 0 {
+        return Err(FftError::InvalidParameter(
+            "segment_len must be greater than zero",
+        ));
+    }
+    if hop == 0 {
+        return Err(FftError::InvalidParameter("hop must be greater than zero"));
+    }
+    if x.len() < segment_len {
+        return Err(FftError::LengthMismatch(segment_len, x.len()));
+    }
+
+    let window = hann(segment_len);
+    let half = segment_len / 2 + 1;
+    let mut sum = vec![vec![Complex64::default(); half]; half];
+    let mut pair_power = vec![vec![0_f64; half]; half];
+    let mut power = vec![0_f64; half];
+
+    for start in (0..=x.len() - segment_len).step_by(hop) {
+        let segment: Vec<f64> = x[start..start + segment_len]
+            .iter()
+            .zip(window.iter())
+            .map(|(s, w)| s * w)
+            .collect();
+        // For real input the exp(+j) kernel of fft yields the complex
+        // conjugate of the conventional spectrum.
+        let spectrum: Vec<Complex64> = fft(&segment)?[..half].iter().map(|c| c.conj()).collect();
+        for (p, c) in power.iter_mut().zip(spectrum.iter()) {
+            *p += c.norm_sqr();
+        }
+        for k1 in 0..half {
+            for k2 in 0..half - k1 {
+                let pair = spectrum[k1] * spectrum[k2];
+                sum[k1][k2] += pair * spectrum[k1 + k2].conj();
+                pair_power[k1][k2] += pair.norm_sqr();
+            }
+        }
+    }
+
+    let segments = ((x.len() - segment_len) / hop + 1) as f64;
+    let mut bicoherence = vec![vec![0_f64; half]; half];
+    for k1 in 0..half {
+        for k2 in 0..half - k1 {
+            let denom = pair_power[k1][k2] * power[k1 + k2];
+            if denom > 0_f64 {
+                bicoherence[k1][k2] = sum[k1][k2].norm_sqr() / denom;
+            }
+            sum[k1][k2] /= segments;
+        }
+    }
+
+    Ok(Bispectrum {
+        bispectrum: sum,
+        bicoherence,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+    use std::f64::consts::PI;
+
+    const N: usize = 128;
+
+    // segments concatenates 64 segments holding tones at bins 10, 25 and 35
+    // with random phases; if coupled, the phase at bin 35 is the sum of the
+    // other two
+    fn segments(coupled: bool) -> Vec<f64> {
+        let mut rng = StdRng::seed_from_u64(if coupled { 1 } else { 2 });
+        let mut x = Vec::new();
+        for _ in 0..64 {
+            let p1 = 2_f64 * PI * rng.random::<f64>();
+            let p2 = 2_f64 * PI * rng.random::<f64>();
+            let p3 = if coupled {
+                p1 + p2
+            } else {
+                2_f64 * PI * rng.random::<f64>()
+            };
+            x.extend((0..N).map(|i| {
+                let w = 2_f64 * PI * i as f64 / N as f64;
+                (10_f64 * w + p1).cos() + (25_f64 * w + p2).cos() + (35_f64 * w + p3).cos()
+            }));
+        }
+        x
+    }
+
+    #[test]
+    fn test_bicoherence_detects_phase_coupling() {
+        let b = bispectrum(&segments(true), N, N).unwrap();
+        assert_eq!(b.bicoherence.len(), N / 2 + 1);
+        assert!(b.bicoherence[10][25] > 0.99);
+        assert!(b.bicoherence[25][10] > 0.99);
+        assert!(b.bispectrum[10][25].norm() > 0.0);
+    }
+
+    #[test]
+    fn test_bicoherence_of_independent_tones_is_small() {
+        let b = bispectrum(&segments(false), N, N).unwrap();
+        assert!(b.bicoherence[10][25] < 0.2, "{}", b.bicoherence[10][25]);
+    }
+
+    #[test]
+    fn test_bispectrum_invalid_input() {
+        assert!(matches!(
+            bispectrum(&[0.0; 8], 16, 4),
+            Err(FftError::LengthMismatch(16, 8))
+        ));
+        assert!(matches!(
+            bispectrum(&[0.0; 32], 16, 0),
+            Err(FftError::InvalidParameter(_))
+        ));
+        assert!(matches!(
+            bispectrum(&[1.0; 8], 0, 1),
+            Err(FftError::InvalidParameter(_))
+        ));
+    }
+}
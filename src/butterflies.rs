@@ -0,0 +1,196 @@
+use core::f64::consts::FRAC_1_SQRT_2;
+use num::complex::Complex64;
+
+use crate::error::FftError;
+use crate::small::TWIDDLES;
+
+// Straight-line kernels for the small sizes that end the recursion of the
+// larger transforms, all with the exp(+) kernel of fft. Sizes 2 to 8 are
+// fully unrolled; 16 and 32 combine two unrolled halves with constant
+// twiddles. None of them allocates.
+
+// has_butterfly tells whether butterfly handles length n
+pub const fn has_butterfly(n: usize) -> bool {
+    matches!(n, 1 | 2 | 3 | 4 | 5 | 7 | 8 | 16 | 32)
+}
+
+// butterfly transforms x in place if its length has a kernel
+pub fn butterfly(x: &mut [Complex64]) -> Result<(), FftError> {
+    match x.len() {
+        1 => {}
+        2 => butterfly2(x),
+        3 => butterfly3(x),
+        4 => butterfly4(x),
+        5 => butterfly5(x),
+        7 => butterfly7(x),
+        8 => butterfly8(x),
+        16 => butterfly16(x),
+        32 => butterfly32(x),
+        _ => {
+            return Err(FftError::InvalidParameter(
+                "no butterfly kernel for this length",
+            ))
+        }
+    }
+    Ok(())
+}
+
+// i_times returns i * c
+fn i_times(c: Complex64) -> Complex64 {
+    Complex64::new(-c.im, c.re)
+}
+
+fn butterfly2(x: &mut [Complex64]) {
+    let (a, b) = (x[0], x[1]);
+    x[0] = a + b;
+    x[1] = a - b;
+}
+
+// W3 = exp(2j pi / 3) = -1/2 + j sqrt(3)/2
+const SIN_3: f64 = 0.8660254037844387;
+
+fn butterfly3(x: &mut [Complex64]) {
+    let (a, b, c) = (x[0], x[1], x[2]);
+    let t = b + c;
+    let d = i_times(b - c) * SIN_3;
+    let m = a - t * 0.5;
+    x[0] = a + t;
+    x[1] = m + d;
+    x[2] = m - d;
+}
+
+fn butterfly4(x: &mut [Complex64]) {
+    let (a, b, c, d) = (x[0], x[1], x[2], x[3]);
+    let t0 = a + c;
+    let t1 = a - c;
+    let t2 = b + d;
+    let t3 = i_times(b - d);
+    x[0] = t0 + t2;
+    x[1] = t1 + t3;
+    x[2] = t0 - t2;
+    x[3] = t1 - t3;
+}
+
+const COS_5: [f64; 2] = [0.30901699437494745, -0.8090169943749473];
+const SIN_5: [f64; 2] = [0.9510565162951535, 0.5877852522924732];
+
+fn butterfly5(x: &mut [Complex64]) {
+    let x0 = x[0];
+    let (t1, t2) = (x[1] + x[4], x[2] + x[3]);
+    let (d1, d2) = (x[1] - x[4], x[2] - x[3]);
+    let m1 = x0 + t1 * COS_5[0] + t2 * COS_5[1];
+    let m2 = x0 + t1 * COS_5[1] + t2 * COS_5[0];
+    let s1 = i_times(d1 * SIN_5[0] + d2 * SIN_5[1]);
+    let s2 = i_times(d1 * SIN_5[1] - d2 * SIN_5[0]);
+    x[0] = x0 + t1 + t2;
+    x[1] = m1 + s1;
+    x[4] = m1 - s1;
+    x[2] = m2 + s2;
+    x[3] = m2 - s2;
+}
+
+const COS_7: [f64; 3] = [0.6234898018587336, -0.22252093395631434, -0.900968867902419];
+const SIN_7: [f64; 3] = [0.7818314824680298, 0.9749279121818236, 0.43388373911755823];
+
+fn butterfly7(x: &mut [Complex64]) {
+    let x0 = x[0];
+    let (t1, t2, t3) = (x[1] + x[6], x[2] + x[5], x[3] + x[4]);
+    let (d1, d2, d3) = (x[1] - x[6], x[2] - x[5], x[3] - x[4]);
+    // cos(2 pi k m / 7) and sin(2 pi k m / 7) for k, m in 1..=3, folded
+    // into the first half period
+    let m1 = x0 + t1 * COS_7[0] + t2 * COS_7[1] + t3 * COS_7[2];
+    let m2 = x0 + t1 * COS_7[1] + t2 * COS_7[2] + t3 * COS_7[0];
+    let m3 = x0 + t1 * COS_7[2] + t2 * COS_7[0] + t3 * COS_7[1];
+    let s1 = i_times(d1 * SIN_7[0] + d2 * SIN_7[1] + d3 * SIN_7[2]);
+    let s2 = i_times(d1 * SIN_7[1] - d2 * SIN_7[2] - d3 * SIN_7[0]);
+    let s3 = i_times(d1 * SIN_7[2] - d2 * SIN_7[0] + d3 * SIN_7[1]);
+    x[0] = x0 + t1 + t2 + t3;
+    x[1] = m1 + s1;
+    x[6] = m1 - s1;
+    x[2] = m2 + s2;
+    x[5] = m2 - s2;
+    x[3] = m3 + s3;
+    x[4] = m3 - s3;
+}
+
+fn butterfly8(x: &mut [Complex64]) {
+    let mut even = [x[0], x[2], x[4], x[6]];
+    let mut odd = [x[1], x[3], x[5], x[7]];
+    butterfly4(&mut even);
+    butterfly4(&mut odd);
+    let w1 = Complex64::new(FRAC_1_SQRT_2, FRAC_1_SQRT_2);
+    let w3 = Complex64::new(-FRAC_1_SQRT_2, FRAC_1_SQRT_2);
+    let odd = [odd[0], odd[1] * w1, i_times(odd[2]), odd[3] * w3];
+    for k in 0..4 {
+        x[k] = even[k] + odd[k];
+        x[k + 4] = even[k] - odd[k];
+    }
+}
+
+// combine merges the transforms of the even and odd samples of a transform
+// of length 2 * N, whose twiddles W^k are TWIDDLES[k * 32 / N]
+fn combine<const N: usize>(x: &mut [Complex64], even: &[Complex64; N], odd: &[Complex64; N]) {
+    let step = 32 / N;
+    for k in 0..N {
+        let v = odd[k] * TWIDDLES[k * step];
+        x[k] = even[k] + v;
+        x[k + N] = even[k] - v;
+    }
+}
+
+fn butterfly16(x: &mut [Complex64]) {
+    let mut even = [Complex64::default(); 8];
+    let mut odd = [Complex64::default(); 8];
+    for k in 0..8 {
+        even[k] = x[2 * k];
+        odd[k] = x[2 * k + 1];
+    }
+    butterfly8(&mut even);
+    butterfly8(&mut odd);
+    combine(x, &even, &odd);
+}
+
+fn butterfly32(x: &mut [Complex64]) {
+    let mut even = [Complex64::default(); 16];
+    let mut odd = [Complex64::default(); 16];
+    for k in 0..16 {
+        even[k] = x[2 * k];
+        odd[k] = x[2 * k + 1];
+    }
+    butterfly16(&mut even);
+    butterfly16(&mut odd);
+    combine(x, &even, &odd);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dft::dft_complex;
+
+    #[test]
+    fn test_butterflies_match_dft() {
+        let values = crate::utils::generate_random_values();
+        for n in [1, 2, 3, 4, 5, 7, 8, 16, 32] {
+            assert!(has_butterfly(n));
+            let x: Vec<Complex64> = (0..n)
+                .map(|i| Complex64::new(values[2 * i], values[2 * i + 1]))
+                .collect();
+            let mut y = x.clone();
+            butterfly(&mut y).unwrap();
+            let expected = dft_complex(&x).unwrap();
+            for (k, (a, b)) in y.iter().zip(expected.iter()).enumerate() {
+                assert!((a - b).norm() < 1e-12, "size {} bin {}", n, k);
+            }
+        }
+    }
+
+    #[test]
+    fn test_butterfly_unsupported_length() {
+        let mut x = vec![Complex64::default(); 6];
+        assert!(!has_butterfly(6));
+        assert!(matches!(
+            butterfly(&mut x),
+            Err(FftError::InvalidParameter(_))
+        ));
+    }
+}
@@ -0,0 +1,50 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use crate::error::FftError;
+
+// CancellationToken is a flag shared between a long-running operation and
+// whoever may want to stop it. Clones share the flag; once cancel has been
+// called, operations checking the token return FftError::Cancelled at their
+// next check point.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        CancellationToken::default()
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    // check returns FftError::Cancelled once the token has been cancelled
+    pub fn check(&self) -> Result<(), FftError> {
+        if self.is_cancelled() {
+            return Err(FftError::Cancelled);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cancellation_token_is_shared() {
+        let token = CancellationToken::new();
+        let other = token.clone();
+        assert!(token.check().is_ok());
+        other.cancel();
+        assert!(token.is_cancelled());
+        assert!(matches!(token.check(), Err(FftError::Cancelled)));
+    }
+}
@@ -0,0 +1,165 @@
+use num::complex::Complex64;
+use std::mem::{align_of, size_of, size_of_val};
+
+use crate::error::FftError;
+
+// Complex64 is #[repr(C)] { re: f64, im: f64 }, so a slice of n values has
+// the layout of 2n interleaved f64 values and of 16n bytes, the format GPU
+// APIs, DMA buffers and raw sample files use. The functions below
+// reinterpret buffers between those views without copying; every f64 bit
+// pattern is a valid value, so only length and alignment need checking.
+
+// as_interleaved views complex samples as re, im, re, im, ...
+pub fn as_interleaved(x: &[Complex64]) -> &[f64] {
+    // SAFETY: Complex64 is two f64 values with no padding and f64 alignment.
+    unsafe { std::slice::from_raw_parts(x.as_ptr() as *const f64, 2 * x.len()) }
+}
+
+pub fn as_interleaved_mut(x: &mut [Complex64]) -> &mut [f64] {
+    // SAFETY: as in as_interleaved; the borrow is exclusive for its lifetime.
+    unsafe { std::slice::from_raw_parts_mut(x.as_mut_ptr() as *mut f64, 2 * x.len()) }
+}
+
+// into_interleaved turns owned complex samples into re, im, re, im, ...
+// keeping the allocation
+pub fn into_interleaved(x: Vec<Complex64>) -> Vec<f64> {
+    let mut x = std::mem::ManuallyDrop::new(x);
+    // SAFETY: as in as_interleaved; an allocation of n Complex64 values has
+    // the size and alignment of one of 2n f64 values.
+    unsafe { Vec::from_raw_parts(x.as_mut_ptr() as *mut f64, 2 * x.len(), 2 * x.capacity()) }
+}
+
+// from_interleaved views re, im, re, im, ... as complex samples; the length
+// must be even
+pub fn from_interleaved(x: &[f64]) -> Result<&[Complex64], FftError> {
+    if !x.len().is_multiple_of(2) {
+        return Err(FftError::InvalidParameter(
+            "interleaved length must be even",
+        ));
+    }
+    // SAFETY: Complex64 has the size of two f64 values and their alignment.
+    Ok(unsafe { std::slice::from_raw_parts(x.as_ptr() as *const Complex64, x.len() / 2) })
+}
+
+pub fn from_interleaved_mut(x: &mut [f64]) -> Result<&mut [Complex64], FftError> {
+    if !x.len().is_multiple_of(2) {
+        return Err(FftError::InvalidParameter(
+            "interleaved length must be even",
+        ));
+    }
+    // SAFETY: as in from_interleaved; the borrow is exclusive for its lifetime.
+    Ok(unsafe { std::slice::from_raw_parts_mut(x.as_mut_ptr() as *mut Complex64, x.len() / 2) })
+}
+
+// as_bytes views complex samples as their native-endian bytes
+pub fn as_bytes(x: &[Complex64]) -> &[u8] {
+    // SAFETY: any initialized memory may be read as bytes.
+    unsafe { std::slice::from_raw_parts(x.as_ptr() as *const u8, size_of_val(x)) }
+}
+
+pub fn as_bytes_mut(x: &mut [Complex64]) -> &mut [u8] {
+    // SAFETY: every byte pattern written back is a valid pair of f64 values.
+    unsafe { std::slice::from_raw_parts_mut(x.as_mut_ptr() as *mut u8, size_of_val(x)) }
+}
+
+// real_as_bytes views real samples as their native-endian bytes
+pub fn real_as_bytes(x: &[f64]) -> &[u8] {
+    // SAFETY: any initialized memory may be read as bytes.
+    unsafe { std::slice::from_raw_parts(x.as_ptr() as *const u8, size_of_val(x)) }
+}
+
+fn check_bytes(bytes: &[u8], size: usize) -> Result<(), FftError> {
+    if !bytes.len().is_multiple_of(size) {
+        return Err(FftError::InvalidParameter(
+            "byte length is not a multiple of the sample size",
+        ));
+    }
+    if !(bytes.as_ptr() as usize).is_multiple_of(align_of::<f64>()) {
+        return Err(FftError::InvalidParameter("bytes are not aligned for f64"));
+    }
+    Ok(())
+}
+
+// from_bytes views native-endian bytes as complex samples; the buffer must
+// hold a whole number of samples and be aligned to 8 bytes
+pub fn from_bytes(bytes: &[u8]) -> Result<&[Complex64], FftError> {
+    check_bytes(bytes, size_of::<Complex64>())?;
+    // SAFETY: length and alignment were checked and any bits form valid f64s.
+    Ok(unsafe {
+        std::slice::from_raw_parts(
+            bytes.as_ptr() as *const Complex64,
+            bytes.len() / size_of::<Complex64>(),
+        )
+    })
+}
+
+pub fn from_bytes_mut(bytes: &mut [u8]) -> Result<&mut [Complex64], FftError> {
+    check_bytes(bytes, size_of::<Complex64>())?;
+    // SAFETY: as in from_bytes; the borrow is exclusive for its lifetime.
+    Ok(unsafe {
+        std::slice::from_raw_parts_mut(
+            bytes.as_mut_ptr() as *mut Complex64,
+            bytes.len() / size_of::<Complex64>(),
+        )
+    })
+}
+
+// real_from_bytes views native-endian bytes as real samples
+pub fn real_from_bytes(bytes: &[u8]) -> Result<&[f64], FftError> {
+    check_bytes(bytes, size_of::<f64>())?;
+    // SAFETY: length and alignment were checked and any bits form valid f64s.
+    Ok(unsafe {
+        std::slice::from_raw_parts(bytes.as_ptr() as *const f64, bytes.len() / size_of::<f64>())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interleaved_round_trip() {
+        let mut x = vec![Complex64::new(1.0, 2.0), Complex64::new(3.0, 4.0)];
+        assert_eq!(as_interleaved(&x), &[1.0, 2.0, 3.0, 4.0]);
+        as_interleaved_mut(&mut x)[3] = -4.0;
+        assert_eq!(x[1], Complex64::new(3.0, -4.0));
+
+        let mut flat = vec![5.0, 6.0, 7.0, 8.0];
+        assert_eq!(
+            from_interleaved(&flat).unwrap()[1],
+            Complex64::new(7.0, 8.0)
+        );
+        from_interleaved_mut(&mut flat).unwrap()[0] *= 2.0;
+        assert_eq!(flat[..2], [10.0, 12.0]);
+        assert!(matches!(
+            from_interleaved(&flat[..3]),
+            Err(FftError::InvalidParameter(_))
+        ));
+    }
+
+    #[test]
+    fn test_bytes_round_trip() {
+        let x = vec![Complex64::new(1.5, -2.5), Complex64::new(0.0, 1e300)];
+        let bytes = as_bytes(&x);
+        assert_eq!(bytes.len(), 32);
+        assert_eq!(bytes[..8], 1.5_f64.to_ne_bytes());
+        assert_eq!(from_bytes(bytes).unwrap(), &x[..]);
+        assert_eq!(real_from_bytes(bytes).unwrap()[1], -2.5);
+        assert_eq!(real_as_bytes(as_interleaved(&x)), bytes);
+    }
+
+    #[test]
+    fn test_from_bytes_checks_layout() {
+        let x = vec![Complex64::new(1.0, 2.0); 4];
+        let bytes = as_bytes(&x);
+        assert!(matches!(
+            from_bytes(&bytes[..24]),
+            Err(FftError::InvalidParameter(_))
+        ));
+        assert!(matches!(
+            from_bytes(&bytes[4..20]),
+            Err(FftError::InvalidParameter(_))
+        ));
+        assert_eq!(from_bytes(&bytes[16..]).unwrap().len(), 3);
+    }
+}
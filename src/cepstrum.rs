@@ -0,0 +1,206 @@
+use num::complex::Complex64;
+use std::f64::consts::LN_10;
+
+use crate::error::FftError;
+use crate::fft::{fft, ifft};
+
+// The functions below take the magnitudes |X[k]| of full spectra of n bins,
+// n a power of two, as fft returns them for real signals. Magnitudes are
+// floored at FLOOR times the largest one before taking logarithms.
+const FLOOR: f64 = 1e-12;
+
+fn log_magnitude(magnitude: &[f64]) -> Vec<f64> {
+    let floor = magnitude.iter().fold(0_f64, |m, &v| m.max(v)) * FLOOR;
+    let floor = floor.max(f64::MIN_POSITIVE);
+    magnitude.iter().map(|&v| v.max(floor).ln()).collect()
+}
+
+// real_cepstrum returns the inverse transform of ln|X[k]|
+pub fn real_cepstrum(magnitude: &[f64]) -> Result<Vec<f64>, FftError> {
+    let log_mag: Vec<Complex64> = log_magnitude(magnitude)
+        .into_iter()
+        .map(|v| Complex64::new(v, 0_f64))
+        .collect();
+    ifft(&log_mag)
+}
+
+// lifter keeps the quefrencies 0..=order and their mirror images of a real
+// cepstrum and transforms back, giving the smoothed log-magnitude
+fn lifter(log_mag: &[f64], order: usize) -> Result<Vec<f64>, FftError> {
+    let n = log_mag.len();
+    let spectrum: Vec<Complex64> = log_mag.iter().map(|&v| Complex64::new(v, 0_f64)).collect();
+    let mut cepstrum = ifft(&spectrum)?;
+    for (q, c) in cepstrum.iter_mut().enumerate() {
+        if q > order && n - q > order {
+            *c = 0_f64;
+        }
+    }
+    Ok(fft(cepstrum)?.iter().map(|c| c.re).collect())
+}
+
+// EnvelopeMethod selects the spectral envelope estimator. Cepstral keeps the
+// first order cepstral coefficients, which averages the log-magnitude and so
+// runs below the harmonic peaks. TrueEnvelope repeats the cepstral smoothing
+// on the maximum of the spectrum and the last envelope until the envelope
+// passes within tolerance_db of every peak, or max_iterations is reached.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EnvelopeMethod {
+    Cepstral {
+        order: usize,
+    },
+    TrueEnvelope {
+        order: usize,
+        max_iterations: usize,
+        tolerance_db: f64,
+    },
+}
+
+// spectral_envelope returns the smooth magnitude envelope of one spectrum
+pub fn spectral_envelope(magnitude: &[f64], method: EnvelopeMethod) -> Result<Vec<f64>, FftError> {
+    let n = magnitude.len();
+    if !n.is_power_of_two() {
+        return Err(FftError::NotAPowerOfTwo(n));
+    }
+    let log_mag = log_magnitude(magnitude);
+    let envelope = match method {
+        EnvelopeMethod::Cepstral { order } => lifter(&log_mag, order)?,
+        EnvelopeMethod::TrueEnvelope {
+            order,
+            max_iterations,
+            tolerance_db,
+        } => {
+            let tolerance = tolerance_db / 20_f64 * LN_10;
+            let mut target = log_mag.clone();
+            let mut envelope = lifter(&target, order)?;
+            for _ in 0..max_iterations {
+                let above = log_mag
+                    .iter()
+                    .zip(&envelope)
+                    .fold(0_f64, |m, (a, e)| m.max(a - e));
+                if above <= tolerance {
+                    break;
+                }
+                for (t, e) in target.iter_mut().zip(&envelope) {
+                    *t = t.max(*e);
+                }
+                envelope = lifter(&target, order)?;
+            }
+            envelope
+        }
+    };
+    Ok(envelope.into_iter().map(f64::exp).collect())
+}
+
+// spectral_envelopes returns the envelope of every frame of short-time
+// spectra such as stft output
+pub fn spectral_envelopes(
+    spectra: &[Vec<Complex64>],
+    method: EnvelopeMethod,
+) -> Result<Vec<Vec<f64>>, FftError> {
+    spectra
+        .iter()
+        .map(|frame| {
+            let magnitude: Vec<f64> = frame.iter().map(|c| c.norm()).collect();
+            spectral_envelope(&magnitude, method)
+        })
+        .collect()
+}
+
+// minimum_phase returns the minimum-phase spectrum with the given magnitude.
+// The real cepstrum is folded onto positive quefrencies, which makes the log
+// spectrum causal, and exponentiated back. The cepstrum of a spectrum with
+// zeros close to the unit circle decays slowly and aliases, so magnitudes
+// should be sampled on a grid much denser than the filter is long.
+pub fn minimum_phase(magnitude: &[f64]) -> Result<Vec<Complex64>, FftError> {
+    let n = magnitude.len();
+    if !n.is_power_of_two() {
+        return Err(FftError::NotAPowerOfTwo(n));
+    }
+    let cepstrum = real_cepstrum(magnitude)?;
+    let folded: Vec<f64> = (0..n)
+        .map(|q| match q {
+            0 => cepstrum[0],
+            q if 2 * q == n => cepstrum[q],
+            q if 2 * q < n => 2_f64 * cepstrum[q],
+            _ => 0_f64,
+        })
+        .collect();
+    Ok(fft(folded)?.iter().map(|c| c.exp()).collect())
+}
+
+// minimum_phase_impulse returns the causal impulse response of
+// minimum_phase(magnitude), e.g. to realize a filter designed by its
+// magnitude response
+pub fn minimum_phase_impulse(magnitude: &[f64]) -> Result<Vec<f64>, FftError> {
+    ifft(&minimum_phase(magnitude)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::PI;
+
+    // harmonics returns the full magnitude spectrum of a comb of harmonics
+    // every 16 bins under a smooth formant curve, and the curve itself
+    fn harmonics(n: usize) -> (Vec<f64>, Vec<f64>) {
+        let curve: Vec<f64> = (0..n)
+            .map(|k| 1_f64 + 0.5 * (2_f64 * PI * k as f64 / n as f64).cos())
+            .collect();
+        let comb = (0..n)
+            .map(|k| {
+                if k % 16 == 0 {
+                    curve[k]
+                } else {
+                    1e-3 * curve[k]
+                }
+            })
+            .collect();
+        (comb, curve)
+    }
+
+    #[test]
+    fn test_true_envelope_follows_peaks() {
+        let (comb, curve) = harmonics(256);
+        let cepstral = spectral_envelope(&comb, EnvelopeMethod::Cepstral { order: 4 }).unwrap();
+        let true_env = spectral_envelope(
+            &comb,
+            EnvelopeMethod::TrueEnvelope {
+                order: 4,
+                max_iterations: 200,
+                tolerance_db: 1_f64,
+            },
+        )
+        .unwrap();
+        let mut peaks = (0..256).step_by(16);
+        // Plain cepstral smoothing averages the log spectrum and runs far
+        // below the peaks; the true envelope passes through them.
+        assert!(peaks.clone().all(|k| cepstral[k] < 0.5 * curve[k]));
+        assert!(peaks.all(|k| (true_env[k] / curve[k]).ln().abs() < 0.2));
+
+        let flat = vec![2_f64; 64];
+        let env = spectral_envelope(&flat, EnvelopeMethod::Cepstral { order: 3 }).unwrap();
+        assert!(env.iter().all(|v| (v - 2_f64).abs() < 1e-9));
+        assert!(matches!(
+            spectral_envelope(&flat[..48], EnvelopeMethod::Cepstral { order: 3 }),
+            Err(FftError::NotAPowerOfTwo(48))
+        ));
+    }
+
+    #[test]
+    fn test_minimum_phase_reconstruction() {
+        // h = [0.5, 1] has its zero outside the unit circle; its
+        // minimum-phase counterpart with the same magnitude is [1, 0.5].
+        let mut h = vec![0_f64; 64];
+        h[0] = 0.5;
+        h[1] = 1_f64;
+        let magnitude: Vec<f64> = fft(&h).unwrap().iter().map(|c| c.norm()).collect();
+        let spectrum = minimum_phase(&magnitude).unwrap();
+        for (c, m) in spectrum.iter().zip(&magnitude) {
+            assert!((c.norm() - m).abs() < 1e-9);
+        }
+        let impulse = minimum_phase_impulse(&magnitude).unwrap();
+        assert!((impulse[0] - 1_f64).abs() < 1e-9);
+        assert!((impulse[1] - 0.5).abs() < 1e-9);
+        assert!(impulse[2..].iter().all(|v| v.abs() < 1e-9));
+    }
+}
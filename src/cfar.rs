@@ -0,0 +1,258 @@
+use crate::error::FftError;
+
+// CfarMethod selects how the noise level around the cell under test is
+// estimated from the training cells. CellAveraging takes their mean, which is
+// optimal in homogeneous noise; OrderedStatistic takes the rank-th smallest
+// training cell (1-based, so rank 1 is the smallest and 2 * train the
+// largest), which keeps working when other targets or clutter
+// edges fall into the training window.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CfarMethod {
+    CellAveraging,
+    OrderedStatistic { rank: usize },
+}
+
+// Cfar holds the parameters of a constant false alarm rate detector. guard
+// cells on each side of the cell under test are skipped, the next train cells
+// on each side form the noise estimate, and a cell is declared a detection when
+// its power exceeds scale times that estimate.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Cfar {
+    pub guard: usize,
+    pub train: usize,
+    pub scale: f64,
+    pub method: CfarMethod,
+}
+
+impl Cfar {
+    // cell_averaging returns a CA-CFAR whose scale is derived from the desired
+    // probability of false alarm for exponentially distributed (square-law
+    // detected) noise and 2 * train training cells.
+    pub fn cell_averaging(guard: usize, train: usize, pfa: f64) -> Self {
+        let n = (2 * train) as f64;
+        Cfar {
+            guard,
+            train,
+            scale: n * (pfa.powf(-1_f64 / n) - 1_f64),
+            method: CfarMethod::CellAveraging,
+        }
+    }
+
+    // ordered_statistic returns an OS-CFAR using the rank-th smallest of the
+    // training cells and the given threshold scale.
+    pub fn ordered_statistic(guard: usize, train: usize, rank: usize, scale: f64) -> Self {
+        Cfar {
+            guard,
+            train,
+            scale,
+            method: CfarMethod::OrderedStatistic { rank },
+        }
+    }
+
+    fn validate(&self) -> Result<(), FftError> {
+        if self.train == 0 {
+            return Err(FftError::InvalidParameter(
+                "at least one training cell is required",
+            ));
+        }
+        if self.scale <= 0_f64 {
+            return Err(FftError::InvalidParameter(
+                "threshold scale must be positive",
+            ));
+        }
+        if let CfarMethod::OrderedStatistic { rank } = self.method {
+            if rank == 0 || rank > 2 * self.train {
+                return Err(FftError::InvalidParameter(
+                    "rank must be between 1 and the number of training cells",
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn noise_level(&self, cells: &mut [f64]) -> f64 {
+        match self.method {
+            CfarMethod::CellAveraging => cells.iter().sum::<f64>() / cells.len() as f64,
+            CfarMethod::OrderedStatistic { rank } => {
+                // Windows clipped at the edges have fewer cells, so the rank is
+                // scaled to the same quantile.
+                let full = (2 * self.train) as f64;
+                let r = ((rank as f64 / full) * cells.len() as f64).ceil() as usize;
+                let r = r.clamp(1, cells.len()) - 1;
+                cells.select_nth_unstable_by(r, |a, b| a.total_cmp(b));
+                cells[r]
+            }
+        }
+    }
+}
+
+// Detection is a cell whose power exceeded the CFAR threshold, with the
+// estimated signal-to-noise ratio relative to the local noise level.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Detection {
+    pub index: usize,
+    pub snr_db: f64,
+}
+
+// Detection2d is a detection in a two-dimensional map such as a range-Doppler
+// map, indexed by row and column.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Detection2d {
+    pub row: usize,
+    pub col: usize,
+    pub snr_db: f64,
+}
+
+fn snr_db(power: f64, noise: f64) -> f64 {
+    10_f64 * (power / noise.max(f64::MIN_POSITIVE)).log10()
+}
+
+// cfar runs the detector over a power spectrum (e.g. |X[k]|^2). Training
+// windows are clipped at the ends of the spectrum.
+pub fn cfar(power: &[f64], params: &Cfar) -> Result<Vec<Detection>, FftError> {
+    params.validate()?;
+    let n = power.len();
+    let reach = params.guard + params.train;
+    let mut cells = Vec::with_capacity(2 * params.train);
+    let mut detections = Vec::new();
+    for i in 0..n {
+        cells.clear();
+        let lo = i.saturating_sub(reach);
+        let hi = (i + reach).min(n.saturating_sub(1));
+        cells.extend(
+            (lo..=hi)
+                .filter(|&j| j.abs_diff(i) > params.guard)
+                .map(|j| power[j]),
+        );
+        if cells.is_empty() {
+            continue;
+        }
+        let noise = params.noise_level(&mut cells);
+        if power[i] > params.scale * noise {
+            detections.push(Detection {
+                index: i,
+                snr_db: snr_db(power[i], noise),
+            });
+        }
+    }
+    Ok(detections)
+}
+
+// cfar_2d runs the detector over a two-dimensional power map. The training
+// region is the square ring of width train around a (2 * guard + 1)-sided
+// guard square, clipped at the edges of the map. The CA scale computed by
+// Cfar::cell_averaging assumes the one-dimensional cell count, so for maps it
+// is usually tuned by hand.
+pub fn cfar_2d(map: &[Vec<f64>], params: &Cfar) -> Result<Vec<Detection2d>, FftError> {
+    params.validate()?;
+    let rows = map.len();
+    let cols = map.first().map_or(0, |r| r.len());
+    if let Some(row) = map.iter().find(|r| r.len() != cols) {
+        return Err(FftError::LengthMismatch(cols, row.len()));
+    }
+
+    let reach = params.guard + params.train;
+    let mut cells = Vec::new();
+    let mut detections = Vec::new();
+    for r in 0..rows {
+        for c in 0..cols {
+            cells.clear();
+            let (r_lo, r_hi) = (r.saturating_sub(reach), (r + reach).min(rows - 1));
+            let (c_lo, c_hi) = (c.saturating_sub(reach), (c + reach).min(cols - 1));
+            for (rr, row) in map.iter().enumerate().take(r_hi + 1).skip(r_lo) {
+                for (cc, &v) in row.iter().enumerate().take(c_hi + 1).skip(c_lo) {
+                    if rr.abs_diff(r) > params.guard || cc.abs_diff(c) > params.guard {
+                        cells.push(v);
+                    }
+                }
+            }
+            if cells.is_empty() {
+                continue;
+            }
+            let noise = params.noise_level(&mut cells);
+            if map[r][c] > params.scale * noise {
+                detections.push(Detection2d {
+                    row: r,
+                    col: c,
+                    snr_db: snr_db(map[r][c], noise),
+                });
+            }
+        }
+    }
+    Ok(detections)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    fn exponential_noise(n: usize, seed: u64) -> Vec<f64> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        (0..n)
+            .map(|_| -(1_f64 - rng.random::<f64>()).ln())
+            .collect()
+    }
+
+    #[test]
+    fn test_ca_cfar_detects_targets() {
+        let mut power = exponential_noise(512, 1);
+        power[100] = 1000.0;
+        power[300] = 200.0;
+        let detections = cfar(&power, &Cfar::cell_averaging(2, 16, 1e-6)).unwrap();
+        let indices: Vec<usize> = detections.iter().map(|d| d.index).collect();
+        assert_eq!(indices, vec![100, 300]);
+        assert!((detections[0].snr_db - 30.0).abs() < 2.0);
+    }
+
+    #[test]
+    fn test_os_cfar_resists_masking() {
+        let mut power = exponential_noise(256, 2);
+        // Closely spaced targets inside each other's training windows.
+        power[120] = 400.0;
+        power[126] = 400.0;
+        power[131] = 400.0;
+
+        let ca = cfar(&power, &Cfar::cell_averaging(1, 8, 1e-6)).unwrap();
+        let os = cfar(&power, &Cfar::ordered_statistic(1, 8, 12, 20.0)).unwrap();
+        let os_indices: Vec<usize> = os.iter().map(|d| d.index).collect();
+        assert!(ca.len() < 3);
+        assert_eq!(os_indices, vec![120, 126, 131]);
+    }
+
+    #[test]
+    fn test_cfar_2d_range_doppler() {
+        let noise = exponential_noise(32 * 32, 3);
+        let mut map: Vec<Vec<f64>> = noise.chunks(32).map(|r| r.to_vec()).collect();
+        map[10][20] = 1000.0;
+        let params = Cfar {
+            guard: 1,
+            train: 3,
+            scale: 30.0,
+            method: CfarMethod::CellAveraging,
+        };
+        let detections = cfar_2d(&map, &params).unwrap();
+        assert_eq!(detections.len(), 1);
+        assert_eq!((detections[0].row, detections[0].col), (10, 20));
+    }
+
+    #[test]
+    fn test_cfar_invalid_params() {
+        for rank in [0, 9] {
+            let params = Cfar::ordered_statistic(1, 4, rank, 10.0);
+            assert!(matches!(
+                cfar(&[1.0; 16], &params),
+                Err(FftError::InvalidParameter(_))
+            ));
+        }
+        for rank in [1, 8] {
+            let params = Cfar::ordered_statistic(1, 4, rank, 10.0);
+            assert!(cfar(&[1.0; 16], &params).unwrap().is_empty());
+        }
+        let ragged = vec![vec![1.0; 4], vec![1.0; 3]];
+        assert!(matches!(
+            cfar_2d(&ragged, &Cfar::cell_averaging(0, 1, 1e-3)),
+            Err(FftError::LengthMismatch(4, 3))
+        ));
+    }
+}
@@ -0,0 +1,154 @@
+use num::complex::Complex64;
+use std::f64::consts::PI;
+
+use crate::doppler::doppler_shift;
+use crate::error::FftError;
+use crate::fft::fft_complex;
+use crate::utils::parabolic_peak;
+
+// Zero-padding factor applied before searching the spectrum of x^M.
+const PADDING: usize = 4;
+
+fn check_rate(sample_rate: f64) -> Result<(), FftError> {
+    if sample_rate <= 0_f64 {
+        return Err(FftError::InvalidParameter("sample rate must be positive"));
+    }
+    Ok(())
+}
+
+// cfo_power estimates the carrier frequency offset in Hz of a complex
+// baseband M-PSK signal without knowing the data. Raising x to the order-th
+// power strips the modulation and leaves a tone at order times the offset,
+// which is located in a zero-padded FFT and refined by parabolic
+// interpolation. The unambiguous range is +-sample_rate / (2 order); order 1
+// suits an unmodulated carrier, 2 BPSK, 4 QPSK.
+pub fn cfo_power(x: &[Complex64], sample_rate: f64, order: u32) -> Result<f64, FftError> {
+    check_rate(sample_rate)?;
+    if order == 0 {
+        return Err(FftError::InvalidParameter(
+            "order must be greater than zero",
+        ));
+    }
+    if x.len() < 2 {
+        return Err(FftError::LengthMismatch(2, x.len()));
+    }
+
+    let n = (x.len() * PADDING).next_power_of_two();
+    let mut powered: Vec<Complex64> = x.iter().map(|c| c.powu(order)).collect();
+    powered.resize(n, Complex64::default());
+    let spectrum: Vec<f64> = fft_complex(&powered)?.iter().map(|c| c.norm()).collect();
+    let (k, _) = spectrum
+        .iter()
+        .enumerate()
+        .max_by(|a, b| a.1.total_cmp(b.1))
+        .unwrap();
+    let (offset, _) = parabolic_peak(
+        spectrum[(k + n - 1) % n],
+        spectrum[k],
+        spectrum[(k + 1) % n],
+    );
+    let signed = if k > n / 2 {
+        k as f64 - n as f64
+    } else {
+        k as f64
+    };
+    // fft uses the exp(+j) kernel, so a positive frequency lands on a negative
+    // bin.
+    Ok(-(signed + offset) * sample_rate / (n as f64 * order as f64))
+}
+
+// cfo_data_aided estimates the carrier frequency offset in Hz of received
+// relative to a known, time-aligned reference (a preamble) from the peak of
+// the spectrum of their cross product received * conj(reference)
+pub fn cfo_data_aided(
+    reference: &[Complex64],
+    received: &[Complex64],
+    sample_rate: f64,
+) -> Result<f64, FftError> {
+    if received.len() != reference.len() {
+        return Err(FftError::LengthMismatch(reference.len(), received.len()));
+    }
+    Ok(doppler_shift(reference, received, sample_rate, 0)?.shift_hz)
+}
+
+// cfo_repetition estimates the carrier frequency offset in Hz from a preamble
+// made of repetitions of period samples (Schmidl-Cox style): the phase of
+// the correlation between x and x delayed by one period is 2 pi offset
+// period / sample_rate. The unambiguous range is +-sample_rate / (2 period).
+pub fn cfo_repetition(x: &[Complex64], period: usize, sample_rate: f64) -> Result<f64, FftError> {
+    check_rate(sample_rate)?;
+    if period == 0 {
+        return Err(FftError::InvalidParameter(
+            "period must be greater than zero",
+        ));
+    }
+    if x.len() <= period {
+        return Err(FftError::LengthMismatch(period + 1, x.len()));
+    }
+    let correlation: Complex64 = x[period..]
+        .iter()
+        .zip(x.iter())
+        .map(|(late, early)| late * early.conj())
+        .sum();
+    Ok(correlation.arg() * sample_rate / (2_f64 * PI * period as f64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    // qpsk returns random QPSK symbols, one per sample, rotated by a carrier
+    // offset of cfo Hz
+    fn qpsk(len: usize, cfo: f64, fs: f64, seed: u64) -> Vec<Complex64> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        (0..len)
+            .map(|i| {
+                let symbol = rng.random_range(0..4) as f64;
+                let phase = PI / 4_f64 + symbol * PI / 2_f64 + 2_f64 * PI * cfo * i as f64 / fs;
+                Complex64::from_polar(1_f64, phase)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_cfo_power_strips_qpsk() {
+        let fs = 1e6;
+        for cfo in [1234.5, -20000.0] {
+            let x = qpsk(2048, cfo, fs, 1);
+            let estimate = cfo_power(&x, fs, 4).unwrap();
+            assert!((estimate - cfo).abs() < 10.0, "{} vs {}", estimate, cfo);
+        }
+        assert!(matches!(
+            cfo_power(&qpsk(16, 0.0, fs, 1), fs, 0),
+            Err(FftError::InvalidParameter(_))
+        ));
+    }
+
+    #[test]
+    fn test_cfo_data_aided() {
+        let fs = 1e6;
+        let reference = qpsk(1024, 0.0, fs, 2);
+        let received = qpsk(1024, -3100.0, fs, 2);
+        let estimate = cfo_data_aided(&reference, &received, fs).unwrap();
+        assert!((estimate + 3100.0).abs() < 20.0, "{}", estimate);
+    }
+
+    #[test]
+    fn test_cfo_repetition() {
+        let fs = 1e6;
+        let period = 64;
+        let block = qpsk(period, 0.0, fs, 3);
+        let x: Vec<Complex64> = (0..4 * period)
+            .map(|i| {
+                block[i % period] * Complex64::from_polar(1.0, 2.0 * PI * 2500.0 * i as f64 / fs)
+            })
+            .collect();
+        let estimate = cfo_repetition(&x, period, fs).unwrap();
+        assert!((estimate - 2500.0).abs() < 1e-6);
+        assert!(matches!(
+            cfo_repetition(&x[..64], period, fs),
+            Err(FftError::LengthMismatch(65, 64))
+        ));
+    }
+}
@@ -0,0 +1,145 @@
+pub use num::complex::Complex64;
+use std::f64::consts::PI;
+
+use crate::butterflies::{butterfly, has_butterfly};
+
+// codelet! emits a transform specialized for one fixed size, built from a
+// factorization of the size into radices that have butterfly kernels (2, 3,
+// 4, 5, 7, 8, 16, 32). Every pass is a separate instantiation of pass with
+// the size and radix as constants, so the compiler sees straight-line loops
+// of known length, much like an FFTW codelet; twiddles are computed on first
+// use and the scratch lives on the stack.
+//
+//     codelet!(pub fn fft480(480) = [32, 3, 5]);
+//     let mut x = [Complex64::default(); 480];
+//     fft480(&mut x);
+#[macro_export]
+macro_rules! codelet {
+    ($(#[$meta:meta])* $vis:vis fn $name:ident($n:literal) = [$($radix:literal),+ $(,)?]) => {
+        $(#[$meta])*
+        $vis fn $name(x: &mut [$crate::codelet::Complex64; $n]) {
+            const { assert!(1 $(* $radix)+ == $n, "radices must multiply to the size") };
+            static TWIDDLES: ::std::sync::OnceLock<::std::vec::Vec<$crate::codelet::Complex64>> =
+                ::std::sync::OnceLock::new();
+            let twiddles = TWIDDLES.get_or_init(|| $crate::codelet::twiddles($n));
+            let mut scratch = [$crate::codelet::Complex64::default(); $n];
+            let mut src: &mut [$crate::codelet::Complex64; $n] = x;
+            let mut dst: &mut [$crate::codelet::Complex64; $n] = &mut scratch;
+            let mut stride = 1;
+            let mut swapped = false;
+            $(
+                $crate::codelet::pass::<$n, $radix>(src, dst, stride, twiddles);
+                ::std::mem::swap(&mut src, &mut dst);
+                swapped = !swapped;
+                stride *= $radix;
+            )+
+            let _ = stride;
+            if swapped {
+                dst.copy_from_slice(&src[..]);
+            }
+        }
+    };
+}
+
+// twiddles returns W^j = exp(2j pi j / n) for j < n
+#[doc(hidden)]
+pub fn twiddles(n: usize) -> Vec<Complex64> {
+    (0..n)
+        .map(|j| Complex64::new(0_f64, 2_f64 * PI * j as f64 / n as f64).exp())
+        .collect()
+}
+
+// pass runs one self-sorting (Stockham) decimation-in-frequency pass of
+// radix P over a transform of length N, after stride = product of the
+// radices of the previous passes: with l = N / stride and m = l / P, the P
+// values src[t + stride * (q + m * r)] go through a P-point butterfly and
+// output k is scaled by W_l^(q k) into dst[t + stride * (P * q + k)].
+#[doc(hidden)]
+#[inline(always)]
+pub fn pass<const N: usize, const P: usize>(
+    src: &[Complex64; N],
+    dst: &mut [Complex64; N],
+    stride: usize,
+    twiddles: &[Complex64],
+) {
+    const { assert!(has_butterfly(P), "radix has no butterfly kernel") };
+    let m = N / (stride * P);
+    let mut a = [Complex64::default(); P];
+    for q in 0..m {
+        for t in 0..stride {
+            for (r, v) in a.iter_mut().enumerate() {
+                *v = src[t + stride * (q + m * r)];
+            }
+            // P has a kernel, checked above.
+            let _ = butterfly(&mut a);
+            for (k, v) in a.iter().enumerate() {
+                dst[t + stride * (P * q + k)] = v * twiddles[q * k * stride];
+            }
+        }
+    }
+}
+
+codelet!(
+    // fft480 transforms 480 points in place
+    pub fn fft480(480) = [32, 3, 5]
+);
+codelet!(
+    // fft1024 transforms 1024 points in place
+    pub fn fft1024(1024) = [32, 32]
+);
+codelet!(
+    // fft2048 transforms 2048 points in place
+    pub fn fft2048(2048) = [32, 8, 8]
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dft::dft_complex;
+    use crate::fft::fft_complex;
+
+    fn random_complex<const N: usize>() -> [Complex64; N] {
+        let values = crate::utils::generate_random_values();
+        let mut x = [Complex64::default(); N];
+        for (i, v) in x.iter_mut().enumerate() {
+            *v = Complex64::new(values[i % 1024], values[(3 * i + 1) % 1024]);
+        }
+        x
+    }
+
+    fn assert_close(a: &[Complex64], b: &[Complex64]) {
+        for (k, (x, y)) in a.iter().zip(b.iter()).enumerate() {
+            assert!((x - y).norm() < 1e-9, "bin {}", k);
+        }
+    }
+
+    #[test]
+    fn test_codelets_match_fft() {
+        let x = random_complex::<1024>();
+        let mut y = x;
+        fft1024(&mut y);
+        assert_close(&y, &fft_complex(&x).unwrap());
+
+        let x = random_complex::<2048>();
+        let mut y = x;
+        fft2048(&mut y);
+        assert_close(&y, &fft_complex(&x).unwrap());
+    }
+
+    #[test]
+    fn test_mixed_radix_codelet() {
+        let x = random_complex::<480>();
+        let mut y = x;
+        fft480(&mut y);
+        assert_close(&y, &dft_complex(&x).unwrap());
+    }
+
+    #[test]
+    fn test_user_codelet() {
+        codelet!(fn fft105(105) = [3, 5, 7]);
+        let x = random_complex::<105>();
+        let mut y = x;
+        fft105(&mut y);
+        assert_close(&y, &dft_complex(&x).unwrap());
+    }
+}
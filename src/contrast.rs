@@ -0,0 +1,121 @@
+use crate::error::FftError;
+use crate::frames::{frames, PadMode};
+use crate::pipeline::{Pipeline, Scale};
+
+// Fraction of the bins of a band averaged to estimate its peak and valley.
+const QUANTILE: f64 = 0.02;
+
+// spectral_contrast returns, for every Hann-windowed frame of x (laid out as
+// for stft), the contrast in dB between the peaks and the valleys of
+// n_bands + 1 sub-bands, as in librosa: band 0 covers 0 to fmin Hz, band k
+// covers fmin 2^(k-1) to fmin 2^k Hz and the last band runs from
+// fmin 2^(n_bands-1) Hz to the Nyquist frequency. Peak and valley are the
+// mean magnitudes of the strongest and weakest 2% of the bins of a band.
+pub fn spectral_contrast(
+    x: &[f64],
+    sample_rate: f64,
+    frame_len: usize,
+    hop: usize,
+    n_bands: usize,
+    fmin: f64,
+) -> Result<Vec<Vec<f64>>, FftError> {
+    if sample_rate <= 0_f64 || fmin <= 0_f64 {
+        return Err(FftError::InvalidParameter(
+            "sample rate and fmin must be positive",
+        ));
+    }
+    if n_bands == 0 || fmin * 2_f64.powi(n_bands as i32 - 1) >= sample_rate / 2_f64 {
+        return Err(FftError::InvalidParameter(
+            "bands must fit below the Nyquist frequency",
+        ));
+    }
+
+    // Bin ranges of every band; each band holds at least one bin.
+    let bins = frame_len / 2 + 1;
+    let resolution = sample_rate / frame_len as f64;
+    let edge = |k: usize| match k {
+        0 => 0,
+        k if k > n_bands => bins,
+        k => ((fmin * 2_f64.powi(k as i32 - 1) / resolution).round() as usize).min(bins),
+    };
+    let bands: Vec<(usize, usize)> = (0..=n_bands)
+        .map(|k| {
+            let lo = edge(k).min(bins - 1);
+            (lo, edge(k + 1).max(lo + 1))
+        })
+        .collect();
+
+    let mut pipeline = Pipeline::builder(frame_len)
+        .hann()
+        .scale(Scale::Magnitude)
+        .build()?;
+    let mut sorted = Vec::with_capacity(bins);
+    frames(x, frame_len, hop, PadMode::Zero)?
+        .map(|frame| {
+            let magnitude = pipeline.process(&frame)?;
+            Ok(bands
+                .iter()
+                .map(|&(lo, hi)| {
+                    sorted.clear();
+                    sorted.extend_from_slice(&magnitude[lo..hi]);
+                    sorted.sort_by(f64::total_cmp);
+                    let count = ((QUANTILE * sorted.len() as f64).round() as usize).max(1);
+                    let mean = |s: &[f64]| s.iter().sum::<f64>() / count as f64;
+                    let valley = mean(&sorted[..count]);
+                    let peak = mean(&sorted[sorted.len() - count..]);
+                    10_f64 * ((peak + 1e-10) / (valley + 1e-10)).log10()
+                })
+                .collect())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+    use std::f64::consts::PI;
+
+    #[test]
+    fn test_contrast_shape() {
+        let x = crate::utils::generate_random_values();
+        let c = spectral_contrast(&x, 22050.0, 256, 128, 6, 200.0).unwrap();
+        assert_eq!(
+            c.len(),
+            crate::frames::frame_count(1024, 256, 128, PadMode::Zero)
+        );
+        assert!(c.iter().all(|f| f.len() == 7));
+        assert!(c.iter().flatten().all(|v| *v >= 0.0));
+    }
+
+    #[test]
+    fn test_tone_has_more_contrast_than_noise() {
+        let fs = 16000_f64;
+        let mut rng = StdRng::seed_from_u64(12);
+        let noise: Vec<f64> = (0..4096).map(|_| rng.random::<f64>() - 0.5).collect();
+        let tone: Vec<f64> = noise
+            .iter()
+            .enumerate()
+            .map(|(i, n)| 0.01 * n + (2_f64 * PI * 3000_f64 * i as f64 / fs).sin())
+            .collect();
+        // 3 kHz falls in band 4, 1.6 to 3.2 kHz with fmin = 200 Hz.
+        let band = 4;
+        let mid = |c: &Vec<Vec<f64>>| c[c.len() / 2][band];
+        let noise_c = spectral_contrast(&noise, fs, 512, 256, 6, 200.0).unwrap();
+        let tone_c = spectral_contrast(&tone, fs, 512, 256, 6, 200.0).unwrap();
+        assert!(mid(&tone_c) > mid(&noise_c) + 20.0);
+    }
+
+    #[test]
+    fn test_contrast_invalid_params() {
+        let x = [0_f64; 512];
+        assert!(matches!(
+            spectral_contrast(&x, 8000.0, 256, 128, 6, 200.0),
+            Err(FftError::InvalidParameter(_))
+        ));
+        assert!(matches!(
+            spectral_contrast(&x, 8000.0, 256, 128, 0, 200.0),
+            Err(FftError::InvalidParameter(_))
+        ));
+    }
+}
@@ -0,0 +1,226 @@
+use num::complex::Complex64;
+use std::ffi::{c_int, c_void};
+use std::mem::size_of;
+use std::ptr;
+use std::sync::Mutex;
+
+use crate::error::FftError;
+use crate::utils::checked_len;
+
+// Bindings to the subset of the CUDA runtime and cuFFT used by CudaFftPlan,
+// declared from cufft.h and cuda_runtime_api.h of CUDA 11 and 12; both
+// libraries ship with the CUDA toolkit.
+type CufftHandle = c_int;
+
+const CUFFT_SUCCESS: c_int = 0;
+const CUFFT_Z2Z: c_int = 0x69;
+// cuFFT's "inverse" direction uses the exp(+) kernel of fft, without scaling.
+const CUFFT_INVERSE: c_int = 1;
+const CUDA_SUCCESS: c_int = 0;
+const CUDA_MEMCPY_HOST_TO_DEVICE: c_int = 1;
+const CUDA_MEMCPY_DEVICE_TO_HOST: c_int = 2;
+
+#[link(name = "cufft")]
+extern "C" {
+    fn cufftPlan1d(plan: *mut CufftHandle, nx: c_int, kind: c_int, batch: c_int) -> c_int;
+    fn cufftExecZ2Z(
+        plan: CufftHandle,
+        idata: *mut c_void,
+        odata: *mut c_void,
+        direction: c_int,
+    ) -> c_int;
+    fn cufftDestroy(plan: CufftHandle) -> c_int;
+}
+
+#[link(name = "cudart")]
+extern "C" {
+    fn cudaMalloc(ptr: *mut *mut c_void, size: usize) -> c_int;
+    fn cudaFree(ptr: *mut c_void) -> c_int;
+    fn cudaMemcpy(dst: *mut c_void, src: *const c_void, count: usize, kind: c_int) -> c_int;
+}
+
+fn check(status: c_int, ok: c_int, what: &'static str) -> Result<(), FftError> {
+    if status != ok {
+        return Err(FftError::Backend(what));
+    }
+    Ok(())
+}
+
+// Device is a cuFFT plan together with the device buffer it runs on. Each
+// is None until created, so a plan failing half-way releases only what it
+// got.
+#[derive(Debug)]
+struct Device {
+    handle: Option<CufftHandle>,
+    buffer: Option<*mut c_void>,
+}
+
+// The plan handle and device pointer are only used under the mutex.
+unsafe impl Send for Device {}
+
+impl Drop for Device {
+    fn drop(&mut self) {
+        unsafe {
+            if let Some(handle) = self.handle {
+                cufftDestroy(handle);
+            }
+            if let Some(buffer) = self.buffer {
+                cudaFree(buffer);
+            }
+        }
+    }
+}
+
+// CudaFftPlan runs batches of forward transforms of a fixed length on an
+// NVIDIA GPU through cuFFT. It is used like FftPlan, with the buffer holding
+// batch transforms one after the other; every call copies the buffer to the
+// device and back, so large batches amortize the transfers best. Calls from
+// several threads are serialized on the plan's device buffer.
+#[derive(Debug)]
+pub struct CudaFftPlan {
+    len: usize,
+    batch: usize,
+    device: Mutex<Device>,
+}
+
+impl CudaFftPlan {
+    // new plans batch transforms of length len, which may be any length
+    pub fn new(len: usize, batch: usize) -> Result<Self, FftError> {
+        if len == 0 {
+            return Err(FftError::InvalidParameter("len must be greater than zero"));
+        }
+        if batch == 0 {
+            return Err(FftError::InvalidParameter(
+                "batch must be greater than zero",
+            ));
+        }
+        let (nx, count) = match (c_int::try_from(len), c_int::try_from(batch)) {
+            (Ok(nx), Ok(count)) => (nx, count),
+            _ => {
+                return Err(FftError::InvalidParameter(
+                    "transform is too large for cuFFT",
+                ))
+            }
+        };
+        let bytes = checked_len(&[batch, len, size_of::<Complex64>()])?;
+        let mut device = Device {
+            handle: None,
+            buffer: None,
+        };
+        unsafe {
+            let mut buffer = ptr::null_mut();
+            check(
+                cudaMalloc(&mut buffer, bytes),
+                CUDA_SUCCESS,
+                "cudaMalloc failed",
+            )?;
+            device.buffer = Some(buffer);
+            let mut handle = 0;
+            check(
+                cufftPlan1d(&mut handle, nx, CUFFT_Z2Z, count),
+                CUFFT_SUCCESS,
+                "cufftPlan1d failed",
+            )?;
+            device.handle = Some(handle);
+        }
+        Ok(CudaFftPlan {
+            len,
+            batch,
+            device: Mutex::new(device),
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn batch(&self) -> usize {
+        self.batch
+    }
+
+    // process transforms the batch in buffer in place
+    pub fn process(&self, buffer: &mut [Complex64]) -> Result<(), FftError> {
+        let total = self.len * self.batch;
+        if buffer.len() != total {
+            return Err(FftError::LengthMismatch(total, buffer.len()));
+        }
+        let bytes = total * size_of::<Complex64>();
+        let device = self
+            .device
+            .lock()
+            .map_err(|_| FftError::Backend("cuFFT plan was poisoned"))?;
+        let (Some(handle), Some(device_buffer)) = (device.handle, device.buffer) else {
+            return Err(FftError::Backend("cuFFT plan was not created"));
+        };
+        // SAFETY: the device buffer holds bytes bytes and Complex64 matches
+        // cufftDoubleComplex.
+        unsafe {
+            check(
+                cudaMemcpy(
+                    device_buffer,
+                    buffer.as_ptr() as *const c_void,
+                    bytes,
+                    CUDA_MEMCPY_HOST_TO_DEVICE,
+                ),
+                CUDA_SUCCESS,
+                "copy to device failed",
+            )?;
+            check(
+                cufftExecZ2Z(handle, device_buffer, device_buffer, CUFFT_INVERSE),
+                CUFFT_SUCCESS,
+                "cufftExecZ2Z failed",
+            )?;
+            check(
+                cudaMemcpy(
+                    buffer.as_mut_ptr() as *mut c_void,
+                    device_buffer,
+                    bytes,
+                    CUDA_MEMCPY_DEVICE_TO_HOST,
+                ),
+                CUDA_SUCCESS,
+                "copy from device failed",
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cuda_matches_fft() {
+        let x: Vec<Complex64> = crate::utils::generate_random_values()
+            .chunks(2)
+            .map(|c| Complex64::new(c[0], c[1]))
+            .collect();
+        let plan = CudaFftPlan::new(128, 4).unwrap();
+        let mut y = x.clone();
+        plan.process(&mut y).unwrap();
+        for (chunk, out) in x.chunks(128).zip(y.chunks(128)) {
+            let expected = crate::fft::fft_complex(chunk).unwrap();
+            for (a, b) in out.iter().zip(expected.iter()) {
+                assert!((a - b).norm() < 1e-9);
+            }
+        }
+        assert!(matches!(
+            plan.process(&mut y[..100]),
+            Err(FftError::LengthMismatch(512, 100))
+        ));
+
+        let plan = CudaFftPlan::new(100, 3).unwrap();
+        let mut y = x[..300].to_vec();
+        plan.process(&mut y).unwrap();
+        for (chunk, out) in x[..300].chunks(100).zip(y.chunks(100)) {
+            let expected = crate::fft::fft_complex(chunk).unwrap();
+            for (a, b) in out.iter().zip(expected.iter()) {
+                assert!((a - b).norm() < 1e-9);
+            }
+        }
+    }
+}
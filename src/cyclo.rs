@@ -0,0 +1,186 @@
+use num::complex::Complex64;
+use std::f64::consts::PI;
+
+use crate::error::FftError;
+use crate::fft::fft_complex;
+use crate::window::hann;
+
+// SpectralCorrelation is an estimate of the spectral correlation function
+// |S_x^alpha(f)| on a regular grid. Both axes are normalized to the sample
+// rate: frequencies runs from -0.5 to 0.5 and cyclic_frequencies from -1 to 1.
+// magnitude[a][f] holds the value at cyclic_frequencies[a] and
+// frequencies[f]; grid points not reached by the estimator are zero.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpectralCorrelation {
+    pub frequencies: Vec<f64>,
+    pub cyclic_frequencies: Vec<f64>,
+    pub magnitude: Vec<Vec<f64>>,
+}
+
+impl SpectralCorrelation {
+    // alpha_profile returns, for every cyclic frequency, the maximum of the
+    // spectral correlation over all spectral frequencies. Peaks away from
+    // alpha = 0 reveal cyclostationary features such as symbol rates and
+    // carrier offsets; it is the usual input to modulation classifiers.
+    pub fn alpha_profile(&self) -> Vec<f64> {
+        self.magnitude
+            .iter()
+            .map(|row| row.iter().cloned().fold(0_f64, f64::max))
+            .collect()
+    }
+}
+
+// centered_spectrum returns the spectrum of x ordered by frequency, where
+// entry i is the frequency i / n - 0.5 under the usual exp(-j) convention.
+// fft uses the exp(+j) kernel, so that frequency lives at bin (n/2 - i) mod n.
+fn centered_spectrum(x: &[Complex64]) -> Result<Vec<Complex64>, FftError> {
+    let n = x.len();
+    let spectrum = fft_complex(x)?;
+    Ok((0..n).map(|i| spectrum[(n + n / 2 - i) % n]).collect())
+}
+
+// spectral_correlation estimates the spectral correlation function of x with
+// the FFT accumulation method (FAM). x is channelized by Hann-windowed FFTs of
+// np samples hopping np / 4 samples, each channel is shifted to baseband, and
+// for every pair of channels the product sequence X_k1 * conj(X_k2) is
+// transformed by a second FFT across blocks. The number of blocks is the
+// largest power of two that fits in x, which sets the cyclic resolution to
+// 4 / (np * blocks).
+pub fn spectral_correlation(x: &[Complex64], np: usize) -> Result<SpectralCorrelation, FftError> {
+    if !np.is_power_of_two() || np < 4 {
+        return Err(FftError::InvalidParameter(
+            "channel length must be a power of two of at least 4",
+        ));
+    }
+    let hop = np / 4;
+    if x.len() < np + hop {
+        return Err(FftError::LengthMismatch(np + hop, x.len()));
+    }
+    let blocks = 1 << ((x.len() - np) / hop + 1).ilog2();
+
+    let window = hann(np);
+    let channel_freq = |k: usize| k as f64 / np as f64 - 0.5;
+    let channels: Vec<Vec<Complex64>> = (0..blocks)
+        .map(|p| {
+            let start = p * hop;
+            let segment: Vec<Complex64> = x[start..start + np]
+                .iter()
+                .zip(window.iter())
+                .map(|(s, w)| s * w)
+                .collect();
+            let spectrum = centered_spectrum(&segment)?;
+            // Shift every channel to baseband relative to the start of x.
+            Ok(spectrum
+                .iter()
+                .enumerate()
+                .map(|(k, c)| {
+                    c * Complex64::from_polar(1_f64, -2_f64 * PI * channel_freq(k) * start as f64)
+                })
+                .collect())
+        })
+        .collect::<Result<_, FftError>>()?;
+
+    // Grid: spectral frequency step 1 / (2 np), cyclic frequency step
+    // 1 / (blocks * hop).
+    let n_freq = 2 * np;
+    let alpha_steps = blocks * hop;
+    let n_alpha = 2 * alpha_steps + 1;
+    let mut magnitude = vec![vec![0_f64; n_freq]; n_alpha];
+    // Only the central part of every second FFT lies inside the strip covered
+    // by its channel pair.
+    let q_max = (blocks * hop / (2 * np)) as isize;
+
+    let mut product = vec![Complex64::default(); blocks];
+    for k1 in 0..np {
+        for k2 in 0..np {
+            for p in 0..blocks {
+                product[p] = channels[p][k1] * channels[p][k2].conj();
+            }
+            let strip = centered_spectrum(&product)?;
+            let f = (channel_freq(k1) + channel_freq(k2)) / 2_f64;
+            let f_idx = ((f + 0.5) * n_freq as f64).round() as usize;
+            let alpha0 = channel_freq(k1) - channel_freq(k2);
+            for q in -q_max..=q_max {
+                let value = strip[(q + blocks as isize / 2) as usize].norm() / blocks as f64;
+                let alpha = alpha0 + q as f64 / alpha_steps as f64;
+                let a_idx = (alpha * alpha_steps as f64).round() as isize + alpha_steps as isize;
+                if (0..n_alpha as isize).contains(&a_idx) && f_idx < n_freq {
+                    let cell = &mut magnitude[a_idx as usize][f_idx];
+                    *cell = cell.max(value);
+                }
+            }
+        }
+    }
+
+    Ok(SpectralCorrelation {
+        frequencies: (0..n_freq)
+            .map(|i| i as f64 / n_freq as f64 - 0.5)
+            .collect(),
+        cyclic_frequencies: (0..n_alpha)
+            .map(|a| (a as f64 - alpha_steps as f64) / alpha_steps as f64)
+            .collect(),
+        magnitude,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    // bpsk returns rectangular-pulse BPSK with the given samples per symbol
+    fn bpsk(len: usize, samples_per_symbol: usize, seed: u64) -> Vec<Complex64> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut symbol = 1_f64;
+        (0..len)
+            .map(|i| {
+                if i % samples_per_symbol == 0 {
+                    symbol = if rng.random::<bool>() { 1_f64 } else { -1_f64 };
+                }
+                Complex64::new(symbol, 0_f64)
+            })
+            .collect()
+    }
+
+    fn strongest_alpha(scf: &SpectralCorrelation, min_alpha: f64) -> f64 {
+        let profile = scf.alpha_profile();
+        let (a, _) = profile
+            .iter()
+            .enumerate()
+            .filter(|(a, _)| scf.cyclic_frequencies[*a] > min_alpha)
+            .max_by(|x, y| x.1.total_cmp(y.1))
+            .unwrap();
+        scf.cyclic_frequencies[a]
+    }
+
+    #[test]
+    fn test_scf_grid_shape() {
+        let x = bpsk(4096, 8, 1);
+        let scf = spectral_correlation(&x, 32).unwrap();
+        assert_eq!(scf.frequencies.len(), 64);
+        assert_eq!(scf.magnitude.len(), scf.cyclic_frequencies.len());
+        assert!((scf.cyclic_frequencies[0] + 1.0).abs() < 1e-12);
+        assert!((scf.cyclic_frequencies.last().unwrap() - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_scf_finds_symbol_rate() {
+        let x = bpsk(8192, 8, 2);
+        let scf = spectral_correlation(&x, 32).unwrap();
+        let alpha = strongest_alpha(&scf, 0.05);
+        assert!((alpha - 0.125).abs() < 0.01, "alpha {}", alpha);
+    }
+
+    #[test]
+    fn test_scf_invalid_input() {
+        let x = bpsk(16, 4, 3);
+        assert!(matches!(
+            spectral_correlation(&x, 12),
+            Err(FftError::InvalidParameter(_))
+        ));
+        assert!(matches!(
+            spectral_correlation(&x, 32),
+            Err(FftError::LengthMismatch(40, 16))
+        ));
+    }
+}
@@ -0,0 +1,138 @@
+use alloc::{vec, vec::Vec};
+use num::complex::Complex;
+
+use crate::error::FftError;
+use crate::fft::{fft, fft_complex};
+use crate::float::{from_usize, twiddle, FftNum};
+
+// Discrete cosine and sine transforms of types II and III, unnormalized:
+//   dct2: X[k] = sum_n x[n] cos(pi k (2n + 1) / 2N)
+//   dct3: x[n] = X[0] / 2 + sum_{k>0} X[k] cos(pi k (2n + 1) / 2N)
+//   dst2: X[k] = sum_n x[n] sin(pi (k + 1) (2n + 1) / 2N)
+//   dst3: x[n] = (-1)^n X[N-1] / 2
+//                + sum_{k<N-1} X[k] sin(pi (k + 1) (2n + 1) / 2N)
+// Type III inverts type II up to a factor N / 2; idct and idst include it.
+// Every length is accepted, each transform costing one FFT of length N.
+
+// dct2 computes the type-II DCT, the transform of JPEG blocks, by Makhoul's
+// method: the even samples followed by the odd ones reversed are
+// transformed, and bin k is rotated by exp(j pi k / 2N)
+pub fn dct2<T: FftNum>(x: &[T]) -> Result<Vec<T>, FftError> {
+    let n = x.len();
+    let reordered: Vec<T> = (0..n)
+        .map(|i| {
+            if 2 * i < n {
+                x[2 * i]
+            } else {
+                x[2 * (n - i) - 1]
+            }
+        })
+        .collect();
+    let spectrum = fft(&reordered)?;
+    Ok(spectrum
+        .iter()
+        .enumerate()
+        .map(|(k, v)| (v * twiddle::<T>(k, 4 * n)).re)
+        .collect())
+}
+
+// idct inverts dct2
+pub fn idct<T: FftNum>(x: &[T]) -> Result<Vec<T>, FftError> {
+    let n = x.len();
+    if n == 0 {
+        return Ok(Vec::new());
+    }
+    // undo the rotation of dct2: V[k] = exp(j pi k / 2N) (X[k] - j X[N-k])
+    let spectrum: Vec<Complex<T>> = (0..n)
+        .map(|k| {
+            let mirror = if k == 0 { T::zero() } else { x[n - k] };
+            Complex::new(x[k], -mirror) * twiddle::<T>(k, 4 * n)
+        })
+        .collect();
+    // the inverse DFT of the exp(-) convention is fft's exp(+) sum over N
+    let scale = from_usize::<T>(n);
+    let v: Vec<T> = fft_complex(&spectrum)?
+        .iter()
+        .map(|c| c.re / scale)
+        .collect();
+    let mut out = vec![T::zero(); n];
+    for (i, &value) in v.iter().enumerate() {
+        let j = if 2 * i < n { 2 * i } else { 2 * (n - i) - 1 };
+        out[j] = value;
+    }
+    Ok(out)
+}
+
+// dct3 computes the type-III DCT
+pub fn dct3<T: FftNum>(x: &[T]) -> Result<Vec<T>, FftError> {
+    let half_n = from_usize::<T>(x.len()) / T::from_f64(2_f64);
+    Ok(idct(x)?.into_iter().map(|v| v * half_n).collect())
+}
+
+// dst2 computes the type-II DST from the DCT of the input with alternating
+// signs, read backwards
+pub fn dst2<T: FftNum>(x: &[T]) -> Result<Vec<T>, FftError> {
+    let mut c = dct2(&alternate(x))?;
+    c.reverse();
+    Ok(c)
+}
+
+// dst3 computes the type-III DST
+pub fn dst3<T: FftNum>(x: &[T]) -> Result<Vec<T>, FftError> {
+    let reversed: Vec<T> = x.iter().rev().copied().collect();
+    Ok(alternate(&dct3(&reversed)?))
+}
+
+// idst inverts dst2
+pub fn idst<T: FftNum>(x: &[T]) -> Result<Vec<T>, FftError> {
+    let reversed: Vec<T> = x.iter().rev().copied().collect();
+    Ok(alternate(&idct(&reversed)?))
+}
+
+// alternate negates the odd samples
+fn alternate<T: FftNum>(x: &[T]) -> Vec<T> {
+    x.iter()
+        .enumerate()
+        .map(|(i, &v)| if i % 2 == 1 { -v } else { v })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::PI;
+
+    fn direct(x: &[f64], kernel: impl Fn(usize, usize) -> f64) -> Vec<f64> {
+        (0..x.len())
+            .map(|k| x.iter().enumerate().map(|(i, v)| v * kernel(k, i)).sum())
+            .collect()
+    }
+
+    #[test]
+    fn test_dct_and_dst_match_definitions() {
+        for n in [1, 2, 7, 8, 12] {
+            let x: Vec<f64> = (0..n).map(|i| ((i * 37) % 11) as f64 - 5_f64).collect();
+            let m = 2_f64 * n as f64;
+            let cos2 = direct(&x, |k, i| (PI * k as f64 * (2 * i + 1) as f64 / m).cos());
+            let sin2 = direct(&x, |k, i| {
+                (PI * (k + 1) as f64 * (2 * i + 1) as f64 / m).sin()
+            });
+            let cos3 = direct(&x, |i, k| {
+                let w = if k == 0 { 0.5 } else { 1_f64 };
+                w * (PI * k as f64 * (2 * i + 1) as f64 / m).cos()
+            });
+            let sin3 = direct(&x, |i, k| {
+                let w = if k == n - 1 { 0.5 } else { 1_f64 };
+                w * (PI * (k + 1) as f64 * (2 * i + 1) as f64 / m).sin()
+            });
+            let close = |a: &[f64], b: &[f64]| a.iter().zip(b).all(|(a, b)| (a - b).abs() < 1e-9);
+            assert!(close(&dct2(&x).unwrap(), &cos2), "dct2 {}", n);
+            assert!(close(&dst2(&x).unwrap(), &sin2), "dst2 {}", n);
+            assert!(close(&dct3(&x).unwrap(), &cos3), "dct3 {}", n);
+            assert!(close(&dst3(&x).unwrap(), &sin3), "dst3 {}", n);
+            assert!(close(&idct(&dct2(&x).unwrap()).unwrap(), &x));
+            assert!(close(&idst(&dst2(&x).unwrap()).unwrap(), &x));
+        }
+        assert!(dct2::<f64>(&[]).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,102 @@
+use num::complex::Complex64;
+use std::f64::consts::PI;
+
+use crate::error::FftError;
+use crate::fft::{fft, ifft_hermitian};
+
+// fractional_delay delays x by delay samples, which may be fractional or
+// negative, by multiplying its spectrum with the linear phase ramp of the
+// delay. |delay| may not exceed the length of a non-empty x, beyond which
+// nothing of x remains in the returned samples. x is zero-padded to a power
+// of two of at least twice its length plus the delay, so the shifted signal
+// does not wrap around, and the first x.len() samples of the result are returned. The Nyquist bin of the padded
+// transform has no sign of frequency; it keeps the real part cos(pi delay)
+// of its phase factor so the result stays real.
+pub fn fractional_delay(x: &[f64], delay: f64) -> Result<Vec<f64>, FftError> {
+    fractional_delay_band_limited(x, delay, 1_f64)
+}
+
+// fractional_delay_band_limited is fractional_delay that also removes every
+// component above cutoff times the Nyquist frequency, 0 < cutoff <= 1. A
+// fractional delay is ill-defined close to Nyquist, where it only changes the
+// amplitude of a real signal, so a cutoff below 1 avoids the ringing that
+// content there causes.
+pub fn fractional_delay_band_limited(
+    x: &[f64],
+    delay: f64,
+    cutoff: f64,
+) -> Result<Vec<f64>, FftError> {
+    if !delay.is_finite() {
+        return Err(FftError::InvalidParameter("delay must be finite"));
+    }
+    if cutoff.is_nan() || cutoff <= 0_f64 || cutoff > 1_f64 {
+        return Err(FftError::InvalidParameter("cutoff must lie in (0, 1]"));
+    }
+    if x.is_empty() {
+        return Ok(Vec::new());
+    }
+    if delay.abs() > x.len() as f64 {
+        return Err(FftError::InvalidParameter(
+            "delay must not exceed the signal length",
+        ));
+    }
+    let padded_len = 2 * x.len() + delay.abs().ceil() as usize;
+    let n = padded_len
+        .checked_next_power_of_two()
+        .ok_or(FftError::SizeTooLarge(padded_len))?;
+    let mut padded = x.to_vec();
+    padded.resize(n, 0_f64);
+    let mut spectrum = fft(&padded)?;
+
+    // fft uses the exp(+) kernel, under which a delay by d multiplies bin k
+    // by exp(2j pi k d / n)
+    let half = n / 2;
+    for (k, bin) in spectrum.iter_mut().enumerate().take(half + 1) {
+        let f = k as f64 / half as f64;
+        let phase = 2_f64 * PI * k as f64 * delay / n as f64;
+        *bin *= if f > cutoff {
+            Complex64::default()
+        } else if k == half && n > 1 {
+            Complex64::new(phase.cos(), 0_f64)
+        } else {
+            Complex64::from_polar(1_f64, phase)
+        };
+    }
+    let mut y = ifft_hermitian(&spectrum)?;
+    y.truncate(x.len());
+    Ok(y)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fractional_delay() {
+        let x: Vec<f64> = (0..100).map(|i| ((i * 37) % 11) as f64).collect();
+        let y = fractional_delay(&x, 3_f64).unwrap();
+        assert_eq!(y.len(), 100);
+        assert!(y[..3].iter().all(|v| v.abs() < 1e-9));
+        assert!((3..100).all(|i| (y[i] - x[i - 3]).abs() < 1e-9));
+
+        // A slow tone is delayed by a fraction of a sample away from the ends.
+        let omega = 2_f64 * PI * 0.01;
+        let tone: Vec<f64> = (0..512).map(|i| (omega * i as f64).sin()).collect();
+        let y = fractional_delay_band_limited(&tone, 0.25, 0.5).unwrap();
+        for (i, v) in y.iter().enumerate().take(300).skip(200) {
+            let expected = (omega * (i as f64 - 0.25)).sin();
+            assert!((v - expected).abs() < 1e-2, "{}: {}", i, v);
+        }
+
+        assert!(fractional_delay(&x, f64::NAN).is_err());
+        assert!(fractional_delay_band_limited(&x, 1_f64, 0_f64).is_err());
+        assert!(fractional_delay(&x, -100_f64).is_ok());
+        for delay in [100.5, -1e12, 1e20] {
+            assert!(matches!(
+                fractional_delay(&x, delay),
+                Err(FftError::InvalidParameter(_))
+            ));
+        }
+        assert!(fractional_delay(&[], 1.5).unwrap().is_empty());
+    }
+}
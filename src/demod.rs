@@ -0,0 +1,76 @@
+use crate::error::FftError;
+use crate::hilbert::{envelope, instantaneous_frequency};
+
+fn remove_mean(mut x: Vec<f64>) -> Vec<f64> {
+    if !x.is_empty() {
+        let mean = x.iter().sum::<f64>() / x.len() as f64;
+        x.iter_mut().for_each(|v| *v -= mean);
+    }
+    x
+}
+
+// demodulate_am recovers the message of an amplitude-modulated real signal:
+// the Hilbert envelope of x with its mean (the carrier level) removed
+pub fn demodulate_am(x: &[f64]) -> Result<Vec<f64>, FftError> {
+    Ok(remove_mean(envelope(x)?))
+}
+
+// demodulate_fm recovers the message of a frequency-modulated real signal as
+// the instantaneous frequency deviation in Hz: the instantaneous frequency of
+// the analytic signal with its mean (the carrier frequency) removed
+pub fn demodulate_fm(x: &[f64], sample_rate: f64) -> Result<Vec<f64>, FftError> {
+    if sample_rate <= 0_f64 {
+        return Err(FftError::InvalidParameter("sample rate must be positive"));
+    }
+    Ok(remove_mean(instantaneous_frequency(x, sample_rate)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::PI;
+
+    const FS: f64 = 8192_f64;
+    const CARRIER: f64 = 1024_f64;
+
+    fn message(i: usize) -> f64 {
+        (2_f64 * PI * 16_f64 * i as f64 / FS).cos()
+    }
+
+    #[test]
+    fn test_demodulate_am() {
+        let x: Vec<f64> = (0..8192)
+            .map(|i| (1_f64 + 0.5 * message(i)) * (2_f64 * PI * CARRIER * i as f64 / FS).cos())
+            .collect();
+        let m = demodulate_am(&x).unwrap();
+        for (i, v) in m.iter().enumerate() {
+            assert!((v - 0.5 * message(i)).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_demodulate_fm() {
+        // 100 Hz peak deviation; the phase is the integral of the frequency.
+        let deviation = 100_f64;
+        let x: Vec<f64> = (0..8192)
+            .map(|i| {
+                let t = i as f64 / FS;
+                let phase =
+                    2_f64 * PI * CARRIER * t + deviation / 16_f64 * (2_f64 * PI * 16_f64 * t).sin();
+                phase.cos()
+            })
+            .collect();
+        let m = demodulate_fm(&x, FS).unwrap();
+        assert_eq!(m.len(), x.len());
+        // Skip the first sample, which repeats its neighbour, and allow for
+        // the half-sample delay of the phase-difference discriminator.
+        for (i, v) in m.iter().enumerate().skip(1) {
+            let expected = deviation * (2_f64 * PI * 16_f64 * (i as f64 - 0.5) / FS).cos();
+            assert!((v - expected).abs() < 0.5, "{}: {} vs {}", i, v, expected);
+        }
+        assert!(matches!(
+            demodulate_fm(&x, 0.0),
+            Err(FftError::InvalidParameter(_))
+        ));
+    }
+}
@@ -0,0 +1,210 @@
+use crate::error::FftError;
+use crate::stft::{istft, stft};
+use crate::window::hann;
+
+// SpectralGate holds the parameters of the spectral gating noise reducer.
+//
+// quiet_fraction selects the share of lowest-energy frames used to estimate
+// the noise statistics, n_std sets the gate threshold in standard deviations
+// above the per-bin noise mean (in dB), smooth_freq and smooth_time are the
+// half-widths of the box filter applied to the mask, and reduction is the
+// fraction of gated energy that is removed (1.0 removes it completely).
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpectralGate {
+    pub frame_len: usize,
+    pub hop: usize,
+    pub quiet_fraction: f64,
+    pub n_std: f64,
+    pub smooth_freq: usize,
+    pub smooth_time: usize,
+    pub reduction: f64,
+}
+
+impl Default for SpectralGate {
+    fn default() -> Self {
+        SpectralGate {
+            frame_len: 1024,
+            hop: 256,
+            quiet_fraction: 0.1,
+            n_std: 1.5,
+            smooth_freq: 1,
+            smooth_time: 1,
+            reduction: 1.0,
+        }
+    }
+}
+
+fn mean_std(values: &[f64]) -> (f64, f64) {
+    let n = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / n;
+    let var = values.iter().map(|v| (v - mean) * (v - mean)).sum::<f64>() / n;
+    (mean, var.sqrt())
+}
+
+// smooth_mask averages mask over a (2 * t + 1) x (2 * f + 1) box, clipping the
+// box at the edges of the time-frequency plane.
+fn smooth_mask(mask: &[Vec<f64>], t: usize, f: usize) -> Vec<Vec<f64>> {
+    let n_frames = mask.len();
+    let n_bins = mask[0].len();
+    (0..n_frames)
+        .map(|i| {
+            (0..n_bins)
+                .map(|k| {
+                    let (t_lo, t_hi) = (i.saturating_sub(t), (i + t).min(n_frames - 1));
+                    let (f_lo, f_hi) = (k.saturating_sub(f), (k + f).min(n_bins - 1));
+                    let sum: f64 = (t_lo..=t_hi)
+                        .map(|ti| mask[ti][f_lo..=f_hi].iter().sum::<f64>())
+                        .sum();
+                    sum / ((t_hi - t_lo + 1) * (f_hi - f_lo + 1)) as f64
+                })
+                .collect()
+        })
+        .collect()
+}
+
+// spectral_gate removes stationary noise from x. Noise statistics are taken
+// per frequency bin from the quietest STFT frames of the signal itself; bins
+// that do not rise n_std deviations above the noise mean are attenuated by a
+// smoothed time-frequency mask before the signal is resynthesized.
+pub fn spectral_gate(x: &[f64], params: &SpectralGate) -> Result<Vec<f64>, FftError> {
+    if !(params.quiet_fraction > 0_f64 && params.quiet_fraction <= 1_f64) {
+        return Err(FftError::InvalidParameter(
+            "quiet fraction must be in (0, 1]",
+        ));
+    }
+    if !(0_f64..=1_f64).contains(&params.reduction) {
+        return Err(FftError::InvalidParameter("reduction must be in [0, 1]"));
+    }
+
+    let window = hann(params.frame_len);
+    let mut frames = stft(x, params.frame_len, params.hop, &window)?;
+    let n = params.frame_len;
+    // Only the non-redundant half is gated; the upper half is mirrored so the
+    // spectrum stays conjugate-symmetric.
+    let half = n / 2 + 1;
+
+    let db: Vec<Vec<f64>> = frames
+        .iter()
+        .map(|frame| {
+            frame[..half]
+                .iter()
+                .map(|c| 10_f64 * (c.norm_sqr() + 1e-20).log10())
+                .collect()
+        })
+        .collect();
+
+    let mut order: Vec<usize> = (0..frames.len()).collect();
+    let energy: Vec<f64> = frames
+        .iter()
+        .map(|f| f.iter().map(|c| c.norm_sqr()).sum())
+        .collect();
+    order.sort_by(|&a, &b| energy[a].total_cmp(&energy[b]));
+    let n_quiet = ((frames.len() as f64 * params.quiet_fraction).ceil() as usize).max(1);
+    let quiet = &order[..n_quiet];
+
+    let threshold: Vec<f64> = (0..half)
+        .map(|k| {
+            let values: Vec<f64> = quiet.iter().map(|&f| db[f][k]).collect();
+            let (mean, std) = mean_std(&values);
+            mean + params.n_std * std
+        })
+        .collect();
+
+    let mask: Vec<Vec<f64>> = db
+        .iter()
+        .map(|row| {
+            row.iter()
+                .zip(threshold.iter())
+                .map(|(v, t)| if v > t { 1_f64 } else { 0_f64 })
+                .collect()
+        })
+        .collect();
+    let mask = smooth_mask(&mask, params.smooth_time, params.smooth_freq);
+
+    for (frame, m) in frames.iter_mut().zip(mask.iter()) {
+        for k in 0..half {
+            let gain = 1_f64 - params.reduction * (1_f64 - m[k]);
+            frame[k] *= gain;
+            if k != 0 && k != n - k {
+                frame[n - k] *= gain;
+            }
+        }
+    }
+
+    istft(&frames, params.hop, &window, x.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+    use std::f64::consts::PI;
+
+    fn energy(x: &[f64]) -> f64 {
+        x.iter().map(|v| v * v).sum()
+    }
+
+    #[test]
+    fn test_spectral_gate_attenuates_noise() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let len = 8192;
+        // The first half is noise only, the second half a tone in noise.
+        let x: Vec<f64> = (0..len)
+            .map(|i| {
+                let noise = 0.05 * (rng.random::<f64>() - 0.5);
+                let tone = if i >= len / 2 {
+                    (2_f64 * PI * 64_f64 * i as f64 / 1024_f64).sin()
+                } else {
+                    0_f64
+                };
+                noise + tone
+            })
+            .collect();
+
+        let params = SpectralGate {
+            quiet_fraction: 0.3,
+            ..SpectralGate::default()
+        };
+        let y = spectral_gate(&x, &params).unwrap();
+        assert_eq!(y.len(), x.len());
+
+        // Noise in the quiet part is strongly reduced.
+        let quiet = 1024..len / 2 - 1024;
+        let r = energy(&y[quiet.clone()]) / energy(&x[quiet]);
+        assert!(r < 0.1, "ratio {}", r);
+
+        // The tone passes through largely intact; mask smoothing trims the
+        // edges of its main lobe.
+        let loud = len / 2 + 1024..len - 1024;
+        let ratio = energy(&y[loud.clone()]) / energy(&x[loud]);
+        assert!(ratio > 0.7 && ratio < 1.1, "ratio {}", ratio);
+    }
+
+    #[test]
+    fn test_spectral_gate_zero_reduction_is_identity() {
+        let values = crate::utils::generate_random_values();
+        let params = SpectralGate {
+            frame_len: 64,
+            hop: 16,
+            reduction: 0.0,
+            ..SpectralGate::default()
+        };
+        let y = spectral_gate(&values, &params).unwrap();
+        for i in 0..values.len() {
+            assert!((values[i] - y[i]).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_spectral_gate_invalid_params() {
+        let x = vec![0_f64; 256];
+        let params = SpectralGate {
+            quiet_fraction: 0.0,
+            ..SpectralGate::default()
+        };
+        assert!(matches!(
+            spectral_gate(&x, &params),
+            Err(FftError::InvalidParameter(_))
+        ));
+    }
+}
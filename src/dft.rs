@@ -1,49 +1,56 @@
-use num::complex::{Complex, Complex64};
-use std::f64::consts::PI;
+use alloc::vec::Vec;
+use num::complex::Complex;
 
-use crate::{error::FftError, utils::mul_mv};
+use crate::error::FftError;
+use crate::float::{from_usize, twiddle, FftNum};
+use crate::validate::default_validation;
 
 // dft computes the Discrete Fourier Transform
-pub fn dft(x: &[f64]) -> Result<Vec<Complex64>, FftError> {
-    let x_complex: Vec<Complex64> = (0..x.len()).map(|i| Complex::new(x[i], 0_f64)).collect();
+pub fn dft<T: FftNum>(x: &[T]) -> Result<Vec<Complex<T>>, FftError> {
+    let x_complex: Vec<Complex<T>> = x.iter().map(|&v| Complex::new(v, T::zero())).collect();
     dft_complex(&x_complex)
 }
 
-fn compute_dft_matrix(len: usize, w: Complex64) -> Vec<Vec<Complex64>> {
-    (0..len)
-        .map(|i| {
-            (0..len)
-                .map(|j| {
-                    let i_compl = Complex::new(0_f64, i as f64);
-                    let j_compl = Complex::new(0_f64, j as f64);
-                    (w * i_compl * j_compl).exp()
+// direct_sum evaluates sum_j x[j] exp(2j pi k j / N) for every k, or with
+// exp(-2j pi k j / N) when inverse is set. Each term looks its kernel value up in a table of the N
+// roots of unity, so memory stays O(N) while the work is O(N^2).
+fn direct_sum<T: FftNum>(x: &[Complex<T>], inverse: bool) -> Vec<Complex<T>> {
+    let n = x.len();
+    let roots: Vec<Complex<T>> = (0..n)
+        .map(|j| {
+            let w = twiddle::<T>(j, n);
+            if inverse {
+                w.conj()
+            } else {
+                w
+            }
+        })
+        .collect();
+    (0..n)
+        .map(|k| {
+            // idx tracks k * j mod N without overflowing
+            let mut idx = 0;
+            x.iter()
+                .fold(Complex::new(T::zero(), T::zero()), |acc, &v| {
+                    let term = v * roots[idx];
+                    idx = (idx + k) % n;
+                    acc + term
                 })
-                .collect()
         })
         .collect()
 }
 
-pub fn dft_complex(x: &[Complex64]) -> Result<Vec<Complex64>, FftError> {
-    let w = Complex::new(0_f64, -2_f64 * PI / x.len() as f64);
-
-    // https://en.wikipedia.org/wiki/Discrete_Fourier_transform
-    let dft_matrix: Vec<Vec<Complex64>> = compute_dft_matrix(x.len(), w);
-
-    let r = mul_mv(&dft_matrix, x);
-    Ok(r)
+// https://en.wikipedia.org/wiki/Discrete_Fourier_transform
+pub fn dft_complex<T: FftNum>(x: &[Complex<T>]) -> Result<Vec<Complex<T>>, FftError> {
+    let x = default_validation().check(x)?;
+    Ok(direct_sum(&x, false))
 }
 
 // idft computes the Inverse Discrete Fourier Transform
-pub fn idft(x: &[Complex64]) -> Vec<f64> {
-    let w = Complex::new(0_f64, 2_f64 * PI / x.len() as f64);
-
-    // f_k (dft_matrix) = (SUM{n=0, N-1} f_n * e^(j2pi*k*n)/N)/N
-    let dft_matrix: Vec<Vec<Complex64>> = compute_dft_matrix(x.len(), w);
-    let r = mul_mv(&dft_matrix, x);
-    let n = x.len() as f64;
-    (0..r.len())
-        .map(|i| (r[i] / Complex::new(n, 0_f64)).re)
-        .collect()
+pub fn idft<T: FftNum>(x: &[Complex<T>]) -> Vec<T> {
+    // f_n = (SUM{k=0, N-1} F_k * e^(-j2pi*k*n/N)) / N
+    let n = from_usize::<T>(x.len());
+    direct_sum(x, true).iter().map(|c| c.re / n).collect()
 }
 
 #[test]
@@ -91,3 +98,16 @@ fn test_dft_random_values() {
         );
     }
 }
+
+#[test]
+fn test_dft_matches_fft_without_matrix() {
+    // A 4096-point matrix alone would take 256 MiB.
+    let x: Vec<f64> = (0..4096)
+        .map(|i| ((i * 7919) % 101) as f64 / 101.0)
+        .collect();
+    let direct = dft(&x).unwrap();
+    let fast = crate::fft::fft(&x).unwrap();
+    for (a, b) in direct.iter().zip(&fast) {
+        assert!((a - b).norm() < 1e-8);
+    }
+}
@@ -0,0 +1,49 @@
+use alloc::vec::Vec;
+
+use crate::error::FftError;
+use crate::fft::fft;
+use crate::float::{from_usize, FftNum};
+
+// dht computes the Discrete Hartley Transform
+//   H[k] = sum_n x[n] cas(2 pi k n / N),  cas t = cos t + sin t
+// of a real signal. H is real and holds the same information as the
+// spectrum: X[k] = (H[k] + H[N-k]) / 2 + j (H[k] - H[N-k]) / 2 in fft's exp(+)
+// convention. Any length is accepted; H is read off one FFT as Re + Im.
+pub fn dht<T: FftNum>(x: &[T]) -> Result<Vec<T>, FftError> {
+    Ok(fft(x)?.iter().map(|c| c.re + c.im).collect())
+}
+
+// idht inverts dht; the Hartley transform is its own inverse up to a factor
+// of N
+pub fn idht<T: FftNum>(x: &[T]) -> Result<Vec<T>, FftError> {
+    let scale = from_usize::<T>(x.len());
+    Ok(dht(x)?.into_iter().map(|v| v / scale).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::PI;
+
+    #[test]
+    fn test_dht_definition_and_inverse() {
+        for n in [1, 6, 8, 13] {
+            let x: Vec<f64> = (0..n).map(|i| ((i * 37) % 11) as f64 - 5_f64).collect();
+            let h = dht(&x).unwrap();
+            for (k, v) in h.iter().enumerate() {
+                let expected: f64 = x
+                    .iter()
+                    .enumerate()
+                    .map(|(i, s)| {
+                        let t = 2_f64 * PI * (k * i) as f64 / n as f64;
+                        s * (t.cos() + t.sin())
+                    })
+                    .sum();
+                assert!((v - expected).abs() < 1e-9, "size {}", n);
+            }
+            let back = idht(&h).unwrap();
+            assert!(back.iter().zip(&x).all(|(a, b)| (a - b).abs() < 1e-9));
+        }
+        assert!(dht::<f64>(&[]).unwrap().is_empty());
+    }
+}
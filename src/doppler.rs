@@ -0,0 +1,161 @@
+use num::complex::Complex64;
+
+use crate::error::FftError;
+use crate::fft::fft_complex;
+use crate::utils::parabolic_peak;
+
+// DopplerEstimate is the peak of the ambiguity surface between a reference
+// and a received signal: the frequency offset in Hz, the delay in samples at
+// which it was found, and the peak correlation normalized to [0, 1].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DopplerEstimate {
+    pub shift_hz: f64,
+    pub delay: usize,
+    pub peak: f64,
+}
+
+// Zero-padding factor applied before the Doppler FFT to sample the
+// ambiguity surface more finely than one bin per 1 / len.
+const PADDING: usize = 4;
+
+// doppler_shift estimates the frequency offset of received relative to
+// reference by searching the ambiguity surface for delays 0..=max_delay. For
+// every delay the lag product received[n + delay] * conj(reference[n]) is a
+// tone at the Doppler frequency; its zero-padded FFT is searched for the
+// peak, which is refined by parabolic interpolation.
+pub fn doppler_shift(
+    reference: &[Complex64],
+    received: &[Complex64],
+    sample_rate: f64,
+    max_delay: usize,
+) -> Result<DopplerEstimate, FftError> {
+    if reference.is_empty() {
+        return Err(FftError::InvalidParameter("reference must not be empty"));
+    }
+    if sample_rate <= 0_f64 {
+        return Err(FftError::InvalidParameter("sample rate must be positive"));
+    }
+    if received.len() < reference.len() + max_delay {
+        return Err(FftError::LengthMismatch(
+            reference.len() + max_delay,
+            received.len(),
+        ));
+    }
+
+    let len = reference.len();
+    let n = (len * PADDING).next_power_of_two();
+    let ref_energy: f64 = reference.iter().map(|c| c.norm_sqr()).sum();
+
+    let mut best: Option<(usize, usize, Vec<f64>, f64)> = None;
+    let mut best_value = -1_f64;
+    for delay in 0..=max_delay {
+        let window = &received[delay..delay + len];
+        let mut product: Vec<Complex64> = window
+            .iter()
+            .zip(reference.iter())
+            .map(|(r, s)| r * s.conj())
+            .collect();
+        product.resize(n, Complex64::default());
+        let spectrum: Vec<f64> = fft_complex(&product)?.iter().map(|c| c.norm()).collect();
+
+        let rx_energy: f64 = window.iter().map(|c| c.norm_sqr()).sum();
+        let scale = (ref_energy * rx_energy).sqrt();
+        let (k, &value) = spectrum
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .unwrap();
+        let value = if scale > 0_f64 { value / scale } else { 0_f64 };
+        if value > best_value {
+            best_value = value;
+            best = Some((delay, k, spectrum, scale));
+        }
+    }
+
+    let (delay, k, spectrum, scale) = best.unwrap();
+    let (offset, peak) = parabolic_peak(
+        spectrum[(k + n - 1) % n],
+        spectrum[k],
+        spectrum[(k + 1) % n],
+    );
+    let signed = if k > n / 2 {
+        k as f64 - n as f64
+    } else {
+        k as f64
+    };
+    // fft uses the exp(+j) kernel, so a tone of positive frequency shows up at
+    // a negative bin.
+    let shift_hz = -(signed + offset) * sample_rate / n as f64;
+    Ok(DopplerEstimate {
+        shift_hz,
+        delay,
+        peak: if scale > 0_f64 {
+            (peak / scale).min(1_f64)
+        } else {
+            0_f64
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+    use std::f64::consts::PI;
+
+    fn reference_signal(len: usize) -> Vec<Complex64> {
+        let mut rng = StdRng::seed_from_u64(11);
+        (0..len)
+            .map(|_| Complex64::from_polar(1.0, 2.0 * PI * rng.random::<f64>()))
+            .collect()
+    }
+
+    fn shifted(reference: &[Complex64], delay: usize, shift: f64, fs: f64) -> Vec<Complex64> {
+        let mut out = vec![Complex64::default(); delay];
+        out.extend(reference.iter().enumerate().map(|(i, s)| {
+            s * Complex64::from_polar(1.0, 2.0 * PI * shift * (i + delay) as f64 / fs)
+        }));
+        out.extend(vec![Complex64::default(); 8]);
+        out
+    }
+
+    #[test]
+    fn test_doppler_shift_positive_and_negative() {
+        let fs = 8000.0;
+        let reference = reference_signal(1024);
+        for &shift in &[123.4, -56.7] {
+            let received = shifted(&reference, 0, shift, fs);
+            let est = doppler_shift(&reference, &received, fs, 0).unwrap();
+            assert!(
+                (est.shift_hz - shift).abs() < 0.5,
+                "expected {}, got {}",
+                shift,
+                est.shift_hz
+            );
+            assert!(est.peak > 0.9);
+        }
+    }
+
+    #[test]
+    fn test_doppler_shift_with_delay() {
+        let fs = 1000.0;
+        let reference = reference_signal(512);
+        let received = shifted(&reference, 5, 40.0, fs);
+        let est = doppler_shift(&reference, &received, fs, 8).unwrap();
+        assert_eq!(est.delay, 5);
+        assert!((est.shift_hz - 40.0).abs() < 0.5);
+    }
+
+    #[test]
+    fn test_doppler_shift_invalid_input() {
+        let reference = reference_signal(16);
+        assert!(matches!(
+            doppler_shift(&reference, &reference[..8], 1.0, 0),
+            Err(FftError::LengthMismatch(16, 8))
+        ));
+        assert!(matches!(
+            doppler_shift(&[], &reference, 1.0, 0),
+            Err(FftError::InvalidParameter(_))
+        ));
+    }
+}
@@ -0,0 +1,77 @@
+use crate::error::FftError;
+
+// Pre-emphasis coefficient used by most speech front ends.
+pub const DEFAULT_PRE_EMPHASIS: f64 = 0.97;
+
+pub(crate) fn check_coefficient(a: f64) -> Result<(), FftError> {
+    if !(0_f64..1_f64).contains(&a) {
+        return Err(FftError::InvalidParameter(
+            "emphasis coefficient must lie in [0, 1)",
+        ));
+    }
+    Ok(())
+}
+
+// pre_emphasis applies the first-order high-pass y[n] = x[n] - a x[n-1],
+// taking x[-1] = 0. It flattens the spectral tilt of voiced speech before
+// spectral analysis.
+pub fn pre_emphasis(x: &[f64], a: f64) -> Result<Vec<f64>, FftError> {
+    check_coefficient(a)?;
+    let mut prev = 0_f64;
+    Ok(x.iter()
+        .map(|&s| {
+            let y = s - a * prev;
+            prev = s;
+            y
+        })
+        .collect())
+}
+
+// de_emphasis inverts pre_emphasis with the one-pole low-pass
+// y[n] = x[n] + a y[n-1], taking y[-1] = 0
+pub fn de_emphasis(x: &[f64], a: f64) -> Result<Vec<f64>, FftError> {
+    check_coefficient(a)?;
+    let mut prev = 0_f64;
+    Ok(x.iter()
+        .map(|&s| {
+            prev = s + a * prev;
+            prev
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pre_emphasis_values() {
+        let y = pre_emphasis(&[1.0, 2.0, 4.0], 0.5).unwrap();
+        assert_eq!(y, vec![1.0, 1.5, 3.0]);
+    }
+
+    #[test]
+    fn test_de_emphasis_inverts_pre_emphasis() {
+        let x = crate::utils::generate_random_values();
+        let y = de_emphasis(
+            &pre_emphasis(&x, DEFAULT_PRE_EMPHASIS).unwrap(),
+            DEFAULT_PRE_EMPHASIS,
+        )
+        .unwrap();
+        for (a, b) in x.iter().zip(y.iter()) {
+            assert!((a - b).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_emphasis_invalid_coefficient() {
+        assert!(matches!(
+            pre_emphasis(&[1.0], 1.0),
+            Err(FftError::InvalidParameter(_))
+        ));
+        assert!(matches!(
+            de_emphasis(&[1.0], -0.1),
+            Err(FftError::InvalidParameter(_))
+        ));
+    }
+}
@@ -0,0 +1,195 @@
+use crate::axis::FrequencyAxis;
+use crate::error::FftError;
+use crate::fft::fft;
+use crate::filter::band_pass;
+use crate::hilbert::envelope;
+
+// EnvelopeSpectrum is the one-sided amplitude spectrum of the envelope of a
+// band-passed signal, with the frequency in Hz of every bin.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnvelopeSpectrum {
+    pub frequencies: Vec<f64>,
+    pub amplitudes: Vec<f64>,
+}
+
+// FaultFrequencies are the characteristic defect frequencies of a rolling
+// element bearing, in Hz: outer race (bpfo), inner race (bpfi), rolling
+// element spin (bsf) and cage (ftf).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FaultFrequencies {
+    pub bpfo: f64,
+    pub bpfi: f64,
+    pub bsf: f64,
+    pub ftf: f64,
+}
+
+impl FaultFrequencies {
+    // bearing computes the fault frequencies for a shaft speed in Hz, the
+    // number of rolling elements, the element and pitch diameters (in the same
+    // unit) and the contact angle in radians.
+    pub fn bearing(
+        shaft_hz: f64,
+        n_elements: usize,
+        element_diameter: f64,
+        pitch_diameter: f64,
+        contact_angle: f64,
+    ) -> Self {
+        let ratio = element_diameter / pitch_diameter * contact_angle.cos();
+        let n = n_elements as f64;
+        FaultFrequencies {
+            bpfo: n * shaft_hz / 2_f64 * (1_f64 - ratio),
+            bpfi: n * shaft_hz / 2_f64 * (1_f64 + ratio),
+            bsf: pitch_diameter * shaft_hz / (2_f64 * element_diameter) * (1_f64 - ratio * ratio),
+            ftf: shaft_hz / 2_f64 * (1_f64 - ratio),
+        }
+    }
+
+    fn named(&self) -> [(&'static str, f64); 4] {
+        [
+            ("BPFO", self.bpfo),
+            ("BPFI", self.bpfi),
+            ("BSF", self.bsf),
+            ("FTF", self.ftf),
+        ]
+    }
+}
+
+// FaultMarker annotates a harmonic of a fault frequency with the envelope
+// spectrum amplitude found there.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FaultMarker {
+    pub name: &'static str,
+    pub harmonic: usize,
+    pub frequency: f64,
+    pub amplitude: f64,
+}
+
+impl EnvelopeSpectrum {
+    // markers reports the amplitude at the first harmonics of every fault
+    // frequency, taking the largest bin within one bin of the expected
+    // frequency to tolerate small speed errors. Harmonics above the end of the
+    // spectrum are skipped.
+    pub fn markers(&self, faults: &FaultFrequencies, harmonics: usize) -> Vec<FaultMarker> {
+        let resolution = match self.frequencies.get(1) {
+            Some(&df) => df,
+            None => return Vec::new(),
+        };
+        let last = self.amplitudes.len() - 1;
+        let mut markers = Vec::new();
+        for (name, f) in faults.named() {
+            for h in 1..=harmonics {
+                let frequency = f * h as f64;
+                let bin = (frequency / resolution).round() as usize;
+                if bin > last {
+                    break;
+                }
+                let amplitude = self.amplitudes[bin.saturating_sub(1)..=(bin + 1).min(last)]
+                    .iter()
+                    .cloned()
+                    .fold(0_f64, f64::max);
+                markers.push(FaultMarker {
+                    name,
+                    harmonic: h,
+                    frequency,
+                    amplitude,
+                });
+            }
+        }
+        markers
+    }
+}
+
+// envelope_spectrum runs the standard bearing diagnostics chain on x: band-pass
+// around a structural resonance excited by the fault impacts (low to high Hz),
+// take the Hilbert envelope, remove its mean and compute its one-sided
+// amplitude spectrum. Repetitive impacts show up as peaks at the fault
+// frequency and its harmonics.
+pub fn envelope_spectrum(
+    x: &[f64],
+    sample_rate: f64,
+    low: f64,
+    high: f64,
+) -> Result<EnvelopeSpectrum, FftError> {
+    if x.is_empty() {
+        return Err(FftError::InvalidParameter("signal must not be empty"));
+    }
+    let band = band_pass(x, sample_rate, low, high)?;
+    let mut env = envelope(&band)?;
+    let mean = env.iter().sum::<f64>() / env.len() as f64;
+    env.iter_mut().for_each(|v| *v -= mean);
+
+    let n = env.len();
+    let spectrum = fft(&env)?;
+    let half = n / 2 + 1;
+    let amplitudes = spectrum[..half]
+        .iter()
+        .enumerate()
+        .map(|(k, c)| {
+            let scale = if k == 0 || 2 * k == n { 1_f64 } else { 2_f64 };
+            scale * c.norm() / n as f64
+        })
+        .collect();
+    let frequencies = FrequencyAxis::new(n, sample_rate)?.positive().collect();
+    Ok(EnvelopeSpectrum {
+        frequencies,
+        amplitudes,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+    use std::f64::consts::PI;
+
+    #[test]
+    fn test_bearing_fault_frequencies() {
+        // 9 elements, d / D = 0.25, zero contact angle, 10 Hz shaft.
+        let f = FaultFrequencies::bearing(10.0, 9, 1.0, 4.0, 0.0);
+        assert!((f.bpfo - 33.75).abs() < 1e-12);
+        assert!((f.bpfi - 56.25).abs() < 1e-12);
+        assert!((f.bsf - 18.75).abs() < 1e-12);
+        assert!((f.ftf - 3.75).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_envelope_spectrum_finds_outer_race_fault() {
+        let fs = 16384_f64;
+        let n = 1 << 15;
+        let faults = FaultFrequencies::bearing(25.0, 8, 1.0, 5.0, 0.0);
+        let mut rng = StdRng::seed_from_u64(9);
+        let mut x: Vec<f64> = (0..n).map(|_| 0.2 * (rng.random::<f64>() - 0.5)).collect();
+        // Every outer race impact rings a 3 kHz resonance.
+        let period = fs / faults.bpfo;
+        let mut t = 0_f64;
+        while (t as usize) < n {
+            let start = t as usize;
+            for i in 0..200.min(n - start) {
+                let decay = (-(i as f64) / 30_f64).exp();
+                x[start + i] += decay * (2_f64 * PI * 3000_f64 * i as f64 / fs).sin();
+            }
+            t += period;
+        }
+
+        let spectrum = envelope_spectrum(&x, fs, 2000.0, 4000.0).unwrap();
+        assert_eq!(spectrum.amplitudes.len(), n / 2 + 1);
+        assert!((spectrum.frequencies[1] - 0.5).abs() < 1e-12);
+
+        let markers = spectrum.markers(&faults, 3);
+        let strongest = markers
+            .iter()
+            .max_by(|a, b| a.amplitude.total_cmp(&b.amplitude))
+            .unwrap();
+        assert_eq!((strongest.name, strongest.harmonic), ("BPFO", 1));
+        let bpfi = markers.iter().find(|m| m.name == "BPFI").unwrap();
+        assert!(strongest.amplitude > 5.0 * bpfi.amplitude);
+    }
+
+    #[test]
+    fn test_envelope_spectrum_rejects_empty_signal() {
+        assert!(matches!(
+            envelope_spectrum(&[], 16384.0, 2000.0, 4000.0),
+            Err(FftError::InvalidParameter(_))
+        ));
+    }
+}
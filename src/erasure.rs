@@ -0,0 +1,164 @@
+use crate::error::FftError;
+use crate::ntt::{mod_inv, mod_mul, ntt, root_of_unity, MAX_LEN, MODULUS};
+
+// ErasureCode is a systematic Reed-Solomon style erasure code over GF(p), with
+// p the NTT modulus. A block of data_len symbols is read as the values at
+// the points w^0 .. w^(data_len - 1) of the unique polynomial of degree
+// below data_len, with w a root of unity; the code word is that polynomial
+// evaluated at all total_len points w^i, so its first data_len symbols are
+// the data itself and the rest are parity. Any data_len symbols of a code
+// word, in any positions, recover the data.
+//
+// Interpolation is the quadratic Lagrange form and evaluation at all points
+// is one NTT, which is plenty for experiments with codes of a few thousand
+// symbols.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ErasureCode {
+    data_len: usize,
+    total_len: usize,
+    transform_len: usize,
+    points: Vec<u64>,
+}
+
+impl ErasureCode {
+    pub fn new(data_len: usize, total_len: usize) -> Result<Self, FftError> {
+        if data_len == 0 || data_len > total_len {
+            return Err(FftError::InvalidParameter(
+                "data length must be between 1 and the total length",
+            ));
+        }
+        let transform_len = total_len.next_power_of_two();
+        if transform_len > MAX_LEN {
+            return Err(FftError::SizeTooLarge(total_len));
+        }
+        let w = root_of_unity(transform_len);
+        let mut points = Vec::with_capacity(total_len);
+        let mut x = 1_u64;
+        for _ in 0..total_len {
+            points.push(x);
+            x = mod_mul(x, w);
+        }
+        Ok(ErasureCode {
+            data_len,
+            total_len,
+            transform_len,
+            points,
+        })
+    }
+
+    pub fn data_len(&self) -> usize {
+        self.data_len
+    }
+
+    pub fn total_len(&self) -> usize {
+        self.total_len
+    }
+
+    // encode returns the code word of data: data followed by
+    // total_len - data_len parity symbols
+    pub fn encode(&self, data: &[u64]) -> Result<Vec<u64>, FftError> {
+        if data.len() != self.data_len {
+            return Err(FftError::LengthMismatch(self.data_len, data.len()));
+        }
+        let shares: Vec<(usize, u64)> = data.iter().cloned().enumerate().collect();
+        let mut word = self.evaluate_all(&self.interpolate(&shares)?)?;
+        word.truncate(self.total_len);
+        Ok(word)
+    }
+
+    // decode recovers the data from surviving (position, symbol) pairs of a
+    // code word; the first data_len pairs with distinct positions are used
+    pub fn decode(&self, shares: &[(usize, u64)]) -> Result<Vec<u64>, FftError> {
+        let mut chosen: Vec<(usize, u64)> = Vec::with_capacity(self.data_len);
+        for &(i, v) in shares {
+            if i >= self.total_len {
+                return Err(FftError::InvalidParameter(
+                    "share position is outside the code word",
+                ));
+            }
+            if chosen.len() < self.data_len && chosen.iter().all(|&(j, _)| j != i) {
+                chosen.push((i, v));
+            }
+        }
+        if chosen.len() < self.data_len {
+            return Err(FftError::LengthMismatch(self.data_len, chosen.len()));
+        }
+        let mut data = self.evaluate_all(&self.interpolate(&chosen)?)?;
+        data.truncate(self.data_len);
+        Ok(data)
+    }
+
+    // interpolate returns the coefficients of the polynomial of degree below
+    // shares.len() through the given (position, value) pairs
+    fn interpolate(&self, shares: &[(usize, u64)]) -> Result<Vec<u64>, FftError> {
+        let xs: Vec<u64> = shares.iter().map(|&(i, _)| self.points[i]).collect();
+        if shares.iter().any(|&(_, v)| v >= MODULUS) {
+            return Err(FftError::InvalidParameter(
+                "symbols must be below the field modulus",
+            ));
+        }
+        // master is prod (x - x_j); dividing it by (x - x_i) gives the
+        // numerator of the i-th Lagrange basis polynomial.
+        let mut master = vec![1_u64];
+        for &x in &xs {
+            master.insert(0, 0);
+            for k in 0..master.len() - 1 {
+                master[k] = (master[k] + MODULUS - mod_mul(x, master[k + 1])) % MODULUS;
+            }
+        }
+        let mut coeffs = vec![0_u64; xs.len()];
+        let mut basis = vec![0_u64; xs.len()];
+        for (i, &xi) in xs.iter().enumerate() {
+            // Synthetic division of master by (x - xi).
+            let mut carry = 0_u64;
+            for k in (0..xs.len()).rev() {
+                carry = (master[k + 1] + mod_mul(carry, xi)) % MODULUS;
+                basis[k] = carry;
+            }
+            let denominator = xs
+                .iter()
+                .enumerate()
+                .filter(|&(j, _)| j != i)
+                .fold(1_u64, |acc, (_, &xj)| {
+                    mod_mul(acc, (xi + MODULUS - xj) % MODULUS)
+                });
+            let scale = mod_mul(shares[i].1, mod_inv(denominator));
+            for (c, b) in coeffs.iter_mut().zip(&basis) {
+                *c = (*c + mod_mul(scale, *b)) % MODULUS;
+            }
+        }
+        Ok(coeffs)
+    }
+
+    // evaluate_all returns the polynomial at w^i for all i below the
+    // transform length
+    fn evaluate_all(&self, coeffs: &[u64]) -> Result<Vec<u64>, FftError> {
+        let mut values = vec![0_u64; self.transform_len];
+        values[..coeffs.len()].copy_from_slice(coeffs);
+        ntt(&mut values)?;
+        Ok(values)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_erasure_code_is_systematic_and_recovers() {
+        let code = ErasureCode::new(5, 12).unwrap();
+        let data = [17_u64, 0, 42, MODULUS - 1, 7];
+        let word = code.encode(&data).unwrap();
+        assert_eq!(word.len(), 12);
+        assert_eq!(&word[..5], &data);
+
+        // Lose seven of the twelve symbols, including all the data.
+        let survivors: Vec<(usize, u64)> =
+            [11, 5, 8, 6, 10].iter().map(|&i| (i, word[i])).collect();
+        assert_eq!(code.decode(&survivors).unwrap(), data);
+        assert!(matches!(
+            code.decode(&survivors[..4]),
+            Err(FftError::LengthMismatch(5, 4))
+        ));
+    }
+}
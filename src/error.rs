@@ -1,7 +1,32 @@
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
 use thiserror::Error;
 
-#[derive(Debug, Error)]
+// FftError implements std::error::Error with the std feature; no_std builds
+// get Debug only.
+#[derive(Debug)]
+#[cfg_attr(feature = "std", derive(Error))]
 pub enum FftError {
-    #[error("Input length ({0}) is not a power of two.")]
+    #[cfg_attr(feature = "std", error("Input length ({0}) is not a power of two."))]
     NotAPowerOfTwo(usize),
+    #[cfg_attr(feature = "std", error("Length mismatch: expected {0}, got {1}."))]
+    LengthMismatch(usize, usize),
+    #[cfg_attr(feature = "std", error("Invalid parameter: {0}."))]
+    InvalidParameter(&'static str),
+    #[cfg_attr(feature = "std", error("Backend failure: {0}."))]
+    Backend(&'static str),
+    #[cfg_attr(
+        feature = "std",
+        error("Transform of {0} points is larger than supported.")
+    )]
+    SizeTooLarge(usize),
+    #[cfg_attr(
+        feature = "std",
+        error("Size overflow: shape {shape:?} has more elements than fit in usize.")
+    )]
+    SizeOverflow { shape: Vec<usize> },
+    #[cfg_attr(feature = "std", error("Operation was cancelled."))]
+    Cancelled,
+    #[cfg_attr(feature = "std", error("Input value at index {0} is NaN or infinite."))]
+    NonFinite(usize),
 }
@@ -1,81 +1,522 @@
-use num::complex::{Complex, Complex64};
-use std::f64::consts::PI;
+use alloc::{vec, vec::Vec};
+use num::complex::Complex;
 
-use crate::dft::dft_complex;
 use crate::error::FftError;
-use crate::utils::{add_vv, mul_vv_el};
-
-// fft computes the Fast Fourier Transform
-pub fn fft(x: &[f64]) -> Result<Vec<Complex64>, FftError> {
-    let x_complex: Vec<Complex64> = (0..x.len()).map(|i| Complex::new(x[i], 0_f64)).collect();
-    fft_complex(&x_complex)
-}
-
-fn fft_complex(x: &[Complex64]) -> Result<Vec<Complex64>, FftError> {
-    let N = x.len();
-    if !N.is_power_of_two() {
-        return Err(FftError::NotAPowerOfTwo(N));
-    } else if N <= 2 {
-        return dft_complex(x);
-    }
-
-    let x_even: Vec<Complex64> = (0..N).step_by(2).map(|i| x[i]).collect();
-    let x_odd: Vec<Complex64> = (0..N).skip(1).step_by(2).map(|i| x[i]).collect();
-   
-    let x_even_cmplx = fft_complex(&x_even)?;
-    let x_odd_cmplx = fft_complex(&x_odd)?;
-
-    let w = Complex::new(0_f64, 2_f64 * PI / N as f64);
-    let mut complex = Complex64::default();
-    let f_i: Vec<Complex64> = (0..N)
-        .map(|i| {
-            complex.re = i as f64;
-            (w * complex).exp()
+use crate::float::{from_usize, twiddle, FftNum};
+use crate::samples::IntoSamples;
+use crate::trace;
+use crate::validate::{default_validation, Validation};
+
+// The transforms are generic over FftNum and run in f64 or f32, following the
+// precision of their input; Complex64 and Complex32 name the two bin types.
+
+// fft computes the Fast Fourier Transform of real samples, given as a slice,
+// Vec, array or iterator. Non-finite samples are handled according to
+// default_validation; the _with variants of the transforms take the mode for
+// the one call instead.
+pub fn fft<'a, T: FftNum, K>(x: impl IntoSamples<'a, T, K>) -> Result<Vec<Complex<T>>, FftError> {
+    fft_with(x, default_validation())
+}
+
+// fft_with is fft handling non-finite samples according to mode
+pub fn fft_with<'a, T: FftNum, K>(
+    x: impl IntoSamples<'a, T, K>,
+    mode: Validation,
+) -> Result<Vec<Complex<T>>, FftError> {
+    let x = x.into_samples();
+    trace::span!("fft", len = x.len());
+    let mut x_complex: Vec<Complex<T>> = x.iter().map(|&v| Complex::new(v, T::zero())).collect();
+    mode.apply(&mut x_complex)?;
+    transformed(x_complex)
+}
+
+// rfft computes the bins 0..=N/2 of the Fast Fourier Transform of real
+// samples; the other bins follow from X[N-k] = conj(X[k]). The even and odd
+// samples are packed into z = x_even + j x_odd and transformed with one
+// complex FFT of size N/2, whose output separates into the half-size spectra
+// E[k] = (Z[k] + conj(Z[N/2-k])) / 2 and O[k] = (Z[k] - conj(Z[N/2-k])) / 2j,
+// combined as X[k] = E[k] + W^k O[k] with W = exp(2j pi / N).
+// Odd lengths cannot be split and take the full transform.
+pub fn rfft<T: FftNum>(x: &[T]) -> Result<Vec<Complex<T>>, FftError> {
+    let n = x.len();
+    trace::span!("rfft", len = n);
+    if n == 0 {
+        return Ok(Vec::new());
+    } else if n % 2 == 1 {
+        let mut full = fft(x)?;
+        full.truncate(n / 2 + 1);
+        return Ok(full);
+    }
+    let m = n / 2;
+    let mut z: Vec<Complex<T>> = (0..m)
+        .map(|i| Complex::new(x[2 * i], x[2 * i + 1]))
+        .collect();
+    default_validation().apply(&mut z)?;
+    let z_freq = transformed(z)?;
+
+    let half = T::from_f64(0.5);
+    let inv_two_j = Complex::new(T::zero(), -half);
+    Ok((0..=m)
+        .map(|k| {
+            let zk = z_freq[k % m];
+            let zn = z_freq[(m - k) % m].conj();
+            let even = (zk + zn) * half;
+            let odd = (zk - zn) * inv_two_j;
+            even + twiddle::<T>(k, n) * odd
         })
+        .collect())
+}
+
+// fft_complex computes the Fast Fourier Transform of complex samples, e.g.
+// IQ data. Non-finite samples are handled according to default_validation.
+pub fn fft_complex<T: FftNum>(x: &[Complex<T>]) -> Result<Vec<Complex<T>>, FftError> {
+    fft_complex_with(x, default_validation())
+}
+
+// fft_complex_with is fft_complex handling non-finite samples according to
+// mode
+pub fn fft_complex_with<T: FftNum>(
+    x: &[Complex<T>],
+    mode: Validation,
+) -> Result<Vec<Complex<T>>, FftError> {
+    let mut x = x.to_vec();
+    fft_in_place_with(&mut x, mode)?;
+    Ok(x)
+}
+
+// fft_in_place computes the Fast Fourier Transform of buffer in place,
+// without allocating for power-of-two lengths. Non-finite samples are handled
+// according to default_validation.
+pub fn fft_in_place<T: FftNum>(buffer: &mut [Complex<T>]) -> Result<(), FftError> {
+    fft_in_place_with(buffer, default_validation())
+}
+
+// fft_in_place_with is fft_in_place handling non-finite samples according to
+// mode
+pub fn fft_in_place_with<T: FftNum>(
+    buffer: &mut [Complex<T>],
+    mode: Validation,
+) -> Result<(), FftError> {
+    mode.apply(buffer)?;
+    transform(buffer)
+}
+
+// transform routes buffer to the kernel for its length: the unrolled
+// butterflies up to 32 samples where the precision has them, radix4 for
+// other powers of two, mixed_radix for other lengths made of the factors 2,
+// 3, 5 and 7, and bluestein for the rest.
+pub(crate) fn transform<T: FftNum>(buffer: &mut [Complex<T>]) -> Result<(), FftError> {
+    let n = buffer.len();
+    trace::span!("transform", len = n, algorithm = kernel::<T>(n));
+    if n == 0 {
+        Ok(())
+    } else if !n.is_power_of_two() {
+        if is_smooth(n) {
+            mixed_radix(buffer)
+        } else {
+            bluestein(buffer)
+        }
+    } else {
+        transform_pow2(buffer);
+        Ok(())
+    }
+}
+
+// transform_pow2 is transform for power-of-two lengths, which cannot fail
+pub(crate) fn transform_pow2<T: FftNum>(buffer: &mut [Complex<T>]) {
+    debug_assert!(buffer.len().is_power_of_two());
+    if buffer.len() > 32 || !T::butterfly(buffer) {
+        radix4(buffer);
+    }
+}
+
+// kernel names the kernel transform uses for length n
+#[cfg(feature = "tracing")]
+fn kernel<T: FftNum>(n: usize) -> &'static str {
+    if !n.is_power_of_two() {
+        if is_smooth(n) {
+            "mixed_radix"
+        } else {
+            "bluestein"
+        }
+    } else if n <= 32 && T::has_butterfly(n) {
+        "butterfly"
+    } else {
+        "radix4"
+    }
+}
+
+// radix2 is the iterative radix-2 Cooley-Tukey FFT of a power-of-two buffer:
+// the samples are put in bit-reversed order and combined by log2(N) passes of
+// butterflies over blocks of doubling length. The twiddle W^j of a pass is
+// computed once and applied to the j-th butterfly of every block. It is the
+// reference radix4 is tested against.
+#[cfg(test)]
+fn radix2<T: FftNum>(buffer: &mut [Complex<T>]) {
+    let n = buffer.len();
+    bit_reverse(buffer);
+    let mut len = 2;
+    while len <= n {
+        let half = len / 2;
+        for j in 0..half {
+            let w = twiddle::<T>(j, len);
+            for start in (0..n).step_by(len) {
+                let a = buffer[start + j];
+                let b = buffer[start + j + half] * w;
+                buffer[start + j] = a + b;
+                buffer[start + j + half] = a - b;
+            }
+        }
+        len *= 2;
+    }
+}
+
+// radix4 merges every two radix-2 passes of radix2 into one radix-4 pass,
+// starting with a twiddle-free radix-2 pass when log2(N) is odd. A block of
+// length L combines the quarter-length transforms a0..a3 as
+//   X[j]        = (a0 + c1) + (c2 + c3)    X[j + L/2]  = (a0 + c1) - (c2 + c3)
+//   X[j + L/4]  = (a0 - c1) + j(c2 - c3)   X[j + 3L/4] = (a0 - c1) - j(c2 - c3)
+// with c1 = W^2j a1, c2 = W^j a2 and c3 = W^3j a3, W = exp(2j pi / L): three
+// complex multiplications for four outputs where radix-2 needs four.
+fn radix4<T: FftNum>(buffer: &mut [Complex<T>]) {
+    let n = buffer.len();
+    bit_reverse(buffer);
+    let mut len = 4;
+    if n.trailing_zeros() % 2 == 1 {
+        for pair in buffer.chunks_exact_mut(2) {
+            let (a, b) = (pair[0], pair[1]);
+            pair[0] = a + b;
+            pair[1] = a - b;
+        }
+        len = 8;
+    }
+    while len <= n {
+        if !T::radix4_pass(buffer, len) {
+            radix4_pass(buffer, len);
+        }
+        len *= 4;
+    }
+}
+
+// radix4_pass is the scalar radix-4 pass over the blocks of length len
+fn radix4_pass<T: FftNum>(buffer: &mut [Complex<T>], len: usize) {
+    let n = buffer.len();
+    let quarter = len / 4;
+    for j in 0..quarter {
+        let w1 = twiddle::<T>(j, len);
+        let w2 = twiddle::<T>(2 * j, len);
+        let w3 = twiddle::<T>(3 * j, len);
+        for start in (0..n).step_by(len) {
+            let i0 = start + j;
+            let (i1, i2, i3) = (i0 + quarter, i0 + 2 * quarter, i0 + 3 * quarter);
+            let a0 = buffer[i0];
+            let c1 = buffer[i1] * w2;
+            let c2 = buffer[i2] * w1;
+            let c3 = buffer[i3] * w3;
+            let (s, d) = (a0 + c1, a0 - c1);
+            let (t, u) = (c2 + c3, c2 - c3);
+            let ju = Complex::new(-u.im, u.re);
+            buffer[i0] = s + t;
+            buffer[i2] = s - t;
+            buffer[i1] = d + ju;
+            buffer[i3] = d - ju;
+        }
+    }
+}
+
+const RADICES: [usize; 5] = [4, 2, 3, 5, 7];
+
+// is_smooth tells whether n has no prime factors other than 2, 3, 5 and 7
+fn is_smooth(mut n: usize) -> bool {
+    for p in [2, 3, 5, 7] {
+        while n.is_multiple_of(p) {
+            n /= p;
+        }
+    }
+    n == 1
+}
+
+// mixed_radix is the decimation-in-time Cooley-Tukey step for n = p m with p
+// the first of RADICES dividing n: the p subsequences x[p j + r] are
+// transformed into Y_r, and the output X[k + m q] = sum_r W^(rk) Y_r[k]
+// W_p^(rq), W = exp(2j pi / n), is a p-point transform of the twiddled
+// Y_r[k] for every k < m.
+fn mixed_radix<T: FftNum>(buffer: &mut [Complex<T>]) -> Result<(), FftError> {
+    let n = buffer.len();
+    let Some(&p) = RADICES.iter().find(|&&p| n.is_multiple_of(p)) else {
+        return transform(buffer);
+    };
+    let m = n / p;
+    let mut sub: Vec<Complex<T>> = (0..p)
+        .flat_map(|r| (0..m).map(move |j| (j, r)))
+        .map(|(j, r)| buffer[j * p + r])
+        .collect();
+    for part in sub.chunks_mut(m) {
+        transform(part)?;
+    }
+    let mut t = [Complex::new(T::zero(), T::zero()); 7];
+    for k in 0..m {
+        for r in 0..p {
+            t[r] = sub[r * m + k] * twiddle::<T>((r * k) % n, n);
+        }
+        small_transform(&mut t[..p]);
+        for q in 0..p {
+            buffer[k + m * q] = t[q];
+        }
+    }
+    Ok(())
+}
+
+// small_transform transforms one of the radix-sized blocks of mixed_radix,
+// with an unrolled kernel where the precision has one
+fn small_transform<T: FftNum>(x: &mut [Complex<T>]) {
+    if T::butterfly(x) {
+        return;
+    }
+    let p = x.len();
+    let input = [
+        x[0],
+        x[1 % p],
+        x[2 % p],
+        x[3 % p],
+        x[4 % p],
+        x[5 % p],
+        x[6 % p],
+    ];
+    for (k, out) in x.iter_mut().enumerate() {
+        *out = (0..p).fold(Complex::new(T::zero(), T::zero()), |acc, j| {
+            acc + input[j] * twiddle::<T>((j * k) % p, p)
+        });
+    }
+}
+
+// bluestein computes the transform of any length N with power-of-two
+// transforms. Writing nk = (n^2 + k^2 - (k - n)^2) / 2 turns
+// X[k] = sum_n x[n] W^(nk), W = exp(2j pi / N), into
+// X[k] = c[k] sum_n (x[n] c[n]) conj(c[k - n]) with the chirp
+// c[m] = exp(j pi m^2 / N), a convolution evaluated by transforms of a
+// power of two M >= 2N - 1. m^2 is reduced mod 2N before scaling so the chirp
+// stays accurate for long inputs.
+fn bluestein<T: FftNum>(buffer: &mut [Complex<T>]) -> Result<(), FftError> {
+    let n = buffer.len();
+    let m = (2 * n - 1).next_power_of_two();
+    let chirp: Vec<Complex<T>> = (0..n)
+        .map(|i| twiddle::<T>((i as u128 * i as u128 % (2 * n) as u128) as usize, 2 * n))
+        .collect();
+
+    let zero = Complex::new(T::zero(), T::zero());
+    let mut a = vec![zero; m];
+    for i in 0..n {
+        a[i] = buffer[i] * chirp[i];
+    }
+    let mut b = vec![zero; m];
+    b[0] = chirp[0].conj();
+    for i in 1..n {
+        b[i] = chirp[i].conj();
+        b[m - i] = chirp[i].conj();
+    }
+    transform(&mut a)?;
+    transform(&mut b)?;
+    // Inverse of the product by conjugation: conj(F(conj(A B))) / M.
+    for (x, y) in a.iter_mut().zip(&b) {
+        *x = (*x * y).conj();
+    }
+    transform(&mut a)?;
+    let scale = from_usize::<T>(m);
+    for k in 0..n {
+        buffer[k] = a[k].conj() / scale * chirp[k];
+    }
+    Ok(())
+}
+
+// transformed runs transform on an owned buffer and returns it
+fn transformed<T: FftNum>(mut x: Vec<Complex<T>>) -> Result<Vec<Complex<T>>, FftError> {
+    transform(&mut x)?;
+    Ok(x)
+}
+
+// fft_two_real computes the Fast Fourier Transforms of two real signals of the
+// same length with a single complex FFT. a and b are packed into the real
+// and imaginary parts of z = a + jb; since the spectrum of a real signal is
+// conjugate-symmetric, the two spectra are separated again as
+// A[k] = (Z[k] + conj(Z[N-k])) / 2 and B[k] = (Z[k] - conj(Z[N-k])) / 2j.
+#[allow(clippy::type_complexity)]
+pub fn fft_two_real<T: FftNum>(
+    a: &[T],
+    b: &[T],
+) -> Result<(Vec<Complex<T>>, Vec<Complex<T>>), FftError> {
+    if a.len() != b.len() {
+        return Err(FftError::LengthMismatch(a.len(), b.len()));
+    }
+    let n = a.len();
+    let mut z: Vec<Complex<T>> = (0..n).map(|i| Complex::new(a[i], b[i])).collect();
+    default_validation().apply(&mut z)?;
+    let z_freq = transformed(z)?;
+
+    let half = T::from_f64(0.5);
+    // 1 / 2j = -j / 2
+    let inv_two_j = Complex::new(T::zero(), -half);
+    let (a_freq, b_freq) = (0..n)
+        .map(|k| {
+            let zk = z_freq[k];
+            let zn = z_freq[(n - k) % n].conj();
+            ((zk + zn) * half, (zk - zn) * inv_two_j)
+        })
+        .unzip();
+    Ok((a_freq, b_freq))
+}
+
+// ifft computes the Inverse Fast Fourier Transform, keeping the real part.
+// The real part of an inverse transform only depends on the conjugate-symmetric
+// part (X[k] + conj(X[N-k])) / 2 of the spectrum, so that part is formed and
+// inverted with the half-size transform of ifft_half.
+pub fn ifft<T: FftNum>(x: &[Complex<T>]) -> Result<Vec<T>, FftError> {
+    ifft_with(x, default_validation())
+}
+
+// ifft_with is ifft handling non-finite bins according to mode
+pub fn ifft_with<T: FftNum>(x: &[Complex<T>], mode: Validation) -> Result<Vec<T>, FftError> {
+    let n = x.len();
+    trace::span!("ifft", len = n);
+    if n == 0 {
+        return Ok(Vec::new());
+    }
+    let x = mode.check(x)?;
+    let half = T::from_f64(0.5);
+    let symmetric: Vec<Complex<T>> = (0..=n / 2)
+        .map(|k| (x[k % n] + x[(n - k) % n].conj()) * half)
         .collect();
+    ifft_half(&symmetric, n)
+}
 
-    let mut r: Vec<Complex64> = Vec::new();
-    let mut aa = add_vv(
-        &x_even_cmplx.clone(),
-        &mul_vv_el(&x_odd_cmplx, &f_i[0..N / 2]),
-    );
-    let mut bb = add_vv(&x_even_cmplx, &mul_vv_el(&x_odd_cmplx, &f_i[N / 2..]));
-    r.append(&mut aa);
-    r.append(&mut bb);
+// ifft_hermitian computes the Inverse Fast Fourier Transform of a spectrum the
+// caller knows to be conjugate-symmetric (X[N-k] = conj(X[k])), i.e. the
+// spectrum of a real signal. Only the bins 0..=N/2 are read.
+pub fn ifft_hermitian<T: FftNum>(x: &[Complex<T>]) -> Result<Vec<T>, FftError> {
+    let n = x.len();
+    if n == 0 {
+        return Ok(Vec::new());
+    }
+    let half = default_validation().check(&x[..n / 2 + 1])?;
+    ifft_half(&half, n)
+}
 
-    Ok(r)
+// irfft reconstructs the real signal of length n from the bins 0..=n/2 of
+// its spectrum, as returned by rfft
+pub fn irfft<T: FftNum>(x: &[Complex<T>], n: usize) -> Result<Vec<T>, FftError> {
+    trace::span!("irfft", len = n);
+    let half = default_validation().check(x)?;
+    ifft_half(&half, n)
 }
 
-// ifft computes the Inverse Fast Fourier Transform
-pub fn ifft(x: &[Complex64]) -> Result<Vec<f64>, FftError> {
+// ifft_half reconstructs the real signal of length n from the bins 0..=n/2 of
+// its spectrum using one complex inverse FFT of size n/2. The even and odd
+// samples have the half-size spectra E[k] = (X[k] + X[k + n/2]) / 2 and
+// O[k] = (X[k] - X[k + n/2]) / (2 W^k), where X[k + n/2] = conj(X[n/2 - k])
+// and W = exp(2j pi / n); packing them as E + jO makes the inverse return the
+// even samples in the real part and the odd samples in the imaginary part.
+// Odd n rebuilds the full spectrum and inverts it instead. The callers have
+// validated half already.
+pub(crate) fn ifft_half<T: FftNum>(half: &[Complex<T>], n: usize) -> Result<Vec<T>, FftError> {
+    if n == 0 {
+        return Err(FftError::InvalidParameter(
+            "signal length must be greater than zero",
+        ));
+    }
+    if half.len() != n / 2 + 1 {
+        return Err(FftError::LengthMismatch(n / 2 + 1, half.len()));
+    }
+    if n % 2 == 1 {
+        let full: Vec<Complex<T>> = (0..n)
+            .map(|k| {
+                if k <= n / 2 {
+                    half[k]
+                } else {
+                    half[n - k].conj()
+                }
+            })
+            .collect();
+        return Ok(ifft_complex_with(&full, Validation::Off)?
+            .iter()
+            .map(|c| c.re)
+            .collect());
+    }
+
+    let m = n / 2;
+    let two = T::from_f64(2_f64);
+    let z: Vec<Complex<T>> = (0..m)
+        .map(|k| {
+            let a = half[k];
+            let b = half[m - k].conj();
+            let w_inv = twiddle::<T>(k, n).conj();
+            let even = (a + b) / two;
+            let odd = (a - b) / two * w_inv;
+            even + Complex::new(T::zero(), T::one()) * odd
+        })
+        .collect();
+    let r = ifft_complex_with(&z, Validation::Off)?;
+    Ok(r.iter().flat_map(|c| [c.re, c.im]).collect())
+}
+
+// ifft_complex computes the Inverse Fast Fourier Transform keeping the full
+// complex result
+pub fn ifft_complex<T: FftNum>(x: &[Complex<T>]) -> Result<Vec<Complex<T>>, FftError> {
+    ifft_complex_with(x, default_validation())
+}
+
+// ifft_complex_with is ifft_complex handling non-finite bins according to
+// mode
+pub fn ifft_complex_with<T: FftNum>(
+    x: &[Complex<T>],
+    mode: Validation,
+) -> Result<Vec<Complex<T>>, FftError> {
     // use the IFFT method of computing conjugates, then FFT, then conjugate again, and then divide
     // by N
-    let x_conj: Vec<Complex64> = (0..x.len()).map(|i| x[i].conj()).collect();
-    let x_res = fft_complex(&x_conj)?;
-    let r: Vec<Complex64> = (0..x.len()).map(|i| x_res[i].conj()).collect();
-    let divisor = Complex::<f64>::new(x.len() as f64, 0_f64);
-    let v: Vec<f64> = (0..r.len()).map(|i| (r[i] / divisor).re).collect();
-    Ok(v)
+    let mut x_res: Vec<Complex<T>> = x.iter().map(|c| c.conj()).collect();
+    fft_in_place_with(&mut x_res, mode)?;
+    let divisor = from_usize::<T>(x.len());
+    x_res.iter_mut().for_each(|c| *c = c.conj() / divisor);
+    Ok(x_res)
+}
+
+// bit_reverse permutes buffer (of power-of-two length) into bit-reversed
+// index order
+pub(crate) fn bit_reverse<T>(buffer: &mut [T]) {
+    let n = buffer.len();
+    if n <= 2 {
+        return;
+    }
+    let bits = n.trailing_zeros();
+    for i in 0..n {
+        let j = i.reverse_bits() >> (usize::BITS - bits);
+        if i < j {
+            buffer.swap(i, j);
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use num::complex::Complex64;
 
     #[test]
     fn test_fft_complex_not_power_of_two() {
-        let input = vec![
-            Complex64::new(1.0, 0.0),
-            Complex64::new(2.0, 0.0),
-            Complex64::new(3.0, 0.0), // Length is 3 (not a power of two)
-        ];
-
-        let result = fft_complex(&input);
-        assert!(result.is_err());
-
-        if let Err(FftError::NotAPowerOfTwo(n)) = result {
-            assert_eq!(n, 3);
+        let values = crate::utils::generate_random_values();
+        for n in [3, 12, 100, 1000] {
+            let input: Vec<Complex64> = (0..n)
+                .map(|i| Complex64::new(values[i], values[i + 1]))
+                .collect();
+            let result = fft_complex(&input).unwrap();
+            let expected = crate::dft::dft_complex(&input).unwrap();
+            assert!(result
+                .iter()
+                .zip(&expected)
+                .all(|(a, b)| (a - b).norm() < 1e-9));
+            let o = ifft_complex(&result).unwrap();
+            assert!(o.iter().zip(&input).all(|(a, b)| (a - b).norm() < 1e-12));
         }
+        assert!(fft_complex::<f64>(&[]).unwrap().is_empty());
     }
 
     #[test]
@@ -104,6 +545,177 @@ mod tests {
         assert_eq!(format!("{:.1}", o[7]), "0.8");
     }
 
+    #[test]
+    fn test_radix4_matches_radix2() {
+        let values = crate::utils::generate_random_values();
+        for n in [64, 128, 256, 512] {
+            let x: Vec<Complex64> = (0..n)
+                .map(|i| Complex64::new(values[2 * i], values[2 * i + 1]))
+                .collect();
+            let (mut a, mut b) = (x.clone(), x);
+            radix2(&mut a);
+            radix4(&mut b);
+            assert!(
+                a.iter().zip(&b).all(|(a, b)| (a - b).norm() < 1e-10),
+                "size {}",
+                n
+            );
+        }
+    }
+
+    #[test]
+    fn test_mixed_radix_lengths() {
+        assert!(is_smooth(480) && is_smooth(1200) && !is_smooth(1000 + 9));
+        for n in [15, 63, 480, 960, 1200] {
+            let x: Vec<Complex64> = (0..n)
+                .map(|i| Complex64::new((i % 7) as f64, (i % 3) as f64 - 1_f64))
+                .collect();
+            let mut y = x.clone();
+            mixed_radix(&mut y).unwrap();
+            let expected = crate::dft::dft_complex(&x).unwrap();
+            assert!(
+                y.iter().zip(&expected).all(|(a, b)| (a - b).norm() < 1e-8),
+                "size {}",
+                n
+            );
+        }
+    }
+
+    #[test]
+    fn test_single_precision() {
+        let values = crate::utils::generate_random_values();
+        let single: Vec<f32> = values.iter().map(|&v| v as f32).collect();
+        for n in [1024, 1000, 1009] {
+            let expected = fft(&values[..n]).unwrap();
+            let spectrum = fft(&single[..n]).unwrap();
+            for (a, b) in spectrum.iter().zip(&expected) {
+                let a = Complex64::new(a.re as f64, a.im as f64);
+                assert!((a - b).norm() < 1e-3, "size {}", n);
+            }
+            let o: Vec<f32> = ifft(&spectrum).unwrap();
+            assert!(o.iter().zip(&single).all(|(a, b)| (a - b).abs() < 1e-5));
+        }
+    }
+
+    #[test]
+    fn test_fft_two_real() {
+        let a = crate::utils::generate_random_values();
+        let b: Vec<f64> = crate::utils::generate_random_values()
+            .iter()
+            .map(|v| v - 0.5)
+            .collect();
+        let (a_freq, b_freq) = fft_two_real(&a, &b).unwrap();
+        let a_ref = fft(&a).unwrap();
+        let b_ref = fft(&b).unwrap();
+        for k in 0..a.len() {
+            assert!((a_freq[k] - a_ref[k]).norm() < 1e-9);
+            assert!((b_freq[k] - b_ref[k]).norm() < 1e-9);
+        }
+
+        assert!(matches!(
+            fft_two_real(&a, &b[..512]),
+            Err(FftError::LengthMismatch(1024, 512))
+        ));
+        let (a3, b3) = fft_two_real(&a[..3], &b[..3]).unwrap();
+        assert!((a3[1] - fft(&a[..3]).unwrap()[1]).norm() < 1e-9);
+        assert!((b3[2] - fft(&b[..3]).unwrap()[2]).norm() < 1e-9);
+    }
+
+    #[test]
+    fn test_rfft_matches_fft_half() {
+        let values = crate::utils::generate_random_values();
+        let half = rfft(&values).unwrap();
+        let full = fft(&values).unwrap();
+        assert_eq!(half.len(), values.len() / 2 + 1);
+        for k in 0..half.len() {
+            assert!((half[k] - full[k]).norm() < 1e-9);
+        }
+        assert_eq!(rfft(&[2_f64]).unwrap(), vec![Complex64::new(2_f64, 0_f64)]);
+        for n in [6, 45] {
+            let half = rfft(&values[..n]).unwrap();
+            let full = fft(&values[..n]).unwrap();
+            assert_eq!(half.len(), n / 2 + 1);
+            assert!(half.iter().zip(&full).all(|(a, b)| (a - b).norm() < 1e-9));
+            let o = irfft(&half, n).unwrap();
+            assert!(o.iter().zip(&values).all(|(a, b)| (a - b).abs() < 1e-9));
+        }
+    }
+
+    #[test]
+    fn test_irfft_round_trip() {
+        let values = crate::utils::generate_random_values();
+        let o = irfft(&rfft(&values).unwrap(), values.len()).unwrap();
+        assert_eq!(o.len(), values.len());
+        assert!(o.iter().zip(&values).all(|(a, b)| (a - b).abs() < 1e-9));
+        assert!(matches!(
+            irfft(&rfft(&values).unwrap(), 512),
+            Err(FftError::LengthMismatch(257, 513))
+        ));
+    }
+
+    #[test]
+    fn test_complex_round_trip() {
+        let values = crate::utils::generate_random_values();
+        let iq: Vec<Complex64> = values
+            .chunks(2)
+            .map(|c| Complex64::new(c[0], c[1] - 0.5))
+            .collect();
+        let o = ifft_complex(&fft_complex(&iq).unwrap()).unwrap();
+        assert!(o.iter().zip(&iq).all(|(a, b)| (a - b).norm() < 1e-9));
+    }
+
+    #[test]
+    fn test_fft_in_place_matches_dft() {
+        let values = crate::utils::generate_random_values();
+        let x: Vec<Complex64> = values
+            .chunks(2)
+            .map(|c| Complex64::new(c[0], c[1]))
+            .collect();
+        let expected = crate::dft::dft_complex(&x).unwrap();
+        let mut buffer = x.clone();
+        fft_in_place(&mut buffer).unwrap();
+        assert!(buffer
+            .iter()
+            .zip(&expected)
+            .all(|(a, b)| (a - b).norm() < 1e-9));
+        let mut odd = x[..100].to_vec();
+        fft_in_place(&mut odd).unwrap();
+        let expected = crate::dft::dft_complex(&x[..100]).unwrap();
+        assert!(odd
+            .iter()
+            .zip(&expected)
+            .all(|(a, b)| (a - b).norm() < 1e-9));
+    }
+
+    #[test]
+    fn test_ifft_hermitian_matches_full_inverse() {
+        let values = crate::utils::generate_random_values();
+        let spectrum = fft(&values).unwrap();
+        let fast = ifft_hermitian(&spectrum).unwrap();
+        let full = ifft_complex(&spectrum).unwrap();
+        assert_eq!(fast.len(), values.len());
+        for i in 0..values.len() {
+            assert!((fast[i] - full[i].re).abs() < 1e-12);
+            assert!((fast[i] - values[i]).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_ifft_of_non_hermitian_spectrum_keeps_real_part() {
+        let spectrum: Vec<Complex64> = (0..16)
+            .map(|i| Complex64::new(i as f64, (i * i) as f64 * 0.1))
+            .collect();
+        let r = ifft(&spectrum).unwrap();
+        let full = ifft_complex(&spectrum).unwrap();
+        for i in 0..16 {
+            assert!((r[i] - full[i].re).abs() < 1e-12);
+        }
+        assert_eq!(ifft(&spectrum[..1]).unwrap(), vec![0.0]);
+        let r = ifft(&spectrum[..6]).unwrap();
+        let full = ifft_complex(&spectrum[..6]).unwrap();
+        assert!(r.iter().zip(&full).all(|(a, b)| (a - b.re).abs() < 1e-12));
+    }
+
     #[test]
     fn test_fft_random_values() {
         let values = crate::utils::generate_random_values();
@@ -0,0 +1,145 @@
+use alloc::vec::Vec;
+use num::complex::Complex;
+
+use crate::error::FftError;
+use crate::fftn::transform_axes;
+use crate::float::{from_usize, FftNum};
+use crate::utils::checked_len;
+
+// Two-dimensional transforms of rows x cols arrays stored row-major, as
+// images usually are: x[r * cols + c] is row r, column c. The transform runs
+// along every row and then along every column, so bin (u, v) of the result
+// holds vertical frequency u and horizontal frequency v. Any row and column
+// length is accepted; the 1D kernels pick the algorithm. Non-finite values
+// are handled according to default_validation.
+
+fn check_shape<T>(x: &[T], rows: usize, cols: usize) -> Result<(), FftError> {
+    let len = checked_len(&[rows, cols])?;
+    if x.len() != len {
+        return Err(FftError::LengthMismatch(len, x.len()));
+    }
+    Ok(())
+}
+
+// fft2 computes the 2D Fast Fourier Transform of a real array
+pub fn fft2<T: FftNum>(x: &[T], rows: usize, cols: usize) -> Result<Vec<Complex<T>>, FftError> {
+    check_shape(x, rows, cols)?;
+    let mut buffer: Vec<Complex<T>> = x.iter().map(|&v| Complex::new(v, T::zero())).collect();
+    transform2(&mut buffer, rows, cols)?;
+    Ok(buffer)
+}
+
+// fft2_complex computes the 2D Fast Fourier Transform of a complex array
+pub fn fft2_complex<T: FftNum>(
+    x: &[Complex<T>],
+    rows: usize,
+    cols: usize,
+) -> Result<Vec<Complex<T>>, FftError> {
+    check_shape(x, rows, cols)?;
+    let mut buffer = x.to_vec();
+    transform2(&mut buffer, rows, cols)?;
+    Ok(buffer)
+}
+
+// fft2_rows is fft2 for an image given as one Vec per row, which must all
+// have the same length; the spectrum comes back in the same layout
+pub fn fft2_rows<T: FftNum>(x: &[Vec<T>]) -> Result<Vec<Vec<Complex<T>>>, FftError> {
+    let cols = x.first().map_or(0, Vec::len);
+    if let Some(row) = x.iter().find(|row| row.len() != cols) {
+        return Err(FftError::LengthMismatch(cols, row.len()));
+    }
+    let flat: Vec<T> = x.iter().flatten().copied().collect();
+    let spectrum = fft2(&flat, x.len(), cols)?;
+    Ok(spectrum.chunks(cols.max(1)).map(<[_]>::to_vec).collect())
+}
+
+// ifft2 computes the 2D Inverse Fast Fourier Transform, keeping the real
+// part, e.g. of a filtered image spectrum
+pub fn ifft2<T: FftNum>(x: &[Complex<T>], rows: usize, cols: usize) -> Result<Vec<T>, FftError> {
+    Ok(ifft2_complex(x, rows, cols)?.iter().map(|c| c.re).collect())
+}
+
+// ifft2_complex computes the 2D Inverse Fast Fourier Transform keeping the
+// full complex result
+pub fn ifft2_complex<T: FftNum>(
+    x: &[Complex<T>],
+    rows: usize,
+    cols: usize,
+) -> Result<Vec<Complex<T>>, FftError> {
+    check_shape(x, rows, cols)?;
+    // conjugate, transform forward, conjugate again and divide by N
+    let mut buffer: Vec<Complex<T>> = x.iter().map(|c| c.conj()).collect();
+    transform2(&mut buffer, rows, cols)?;
+    let divisor = from_usize::<T>(x.len());
+    buffer.iter_mut().for_each(|c| *c = c.conj() / divisor);
+    Ok(buffer)
+}
+
+// transform2 transforms every row of buffer in place, then every column
+fn transform2<T: FftNum>(
+    buffer: &mut [Complex<T>],
+    rows: usize,
+    cols: usize,
+) -> Result<(), FftError> {
+    transform_axes(buffer, &[rows, cols], &[1, 0])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dft::dft_complex;
+    use num::complex::Complex64;
+
+    #[test]
+    fn test_fft2_matches_separable_dft() {
+        let (rows, cols) = (6, 8);
+        let x: Vec<f64> = (0..rows * cols).map(|i| ((i * 37) % 11) as f64).collect();
+        let spectrum = fft2(&x, rows, cols).unwrap();
+
+        // Reference: the DFT of every row, then of every column.
+        let row_dfts: Vec<Vec<Complex64>> = x
+            .chunks(cols)
+            .map(|row| {
+                let row: Vec<Complex64> = row.iter().map(|&v| Complex64::new(v, 0_f64)).collect();
+                dft_complex(&row).unwrap()
+            })
+            .collect();
+        for c in 0..cols {
+            let column: Vec<Complex64> = row_dfts.iter().map(|row| row[c]).collect();
+            for (r, v) in dft_complex(&column).unwrap().iter().enumerate() {
+                assert!((spectrum[r * cols + c] - v).norm() < 1e-9);
+            }
+        }
+
+        let back = ifft2(&spectrum, rows, cols).unwrap();
+        assert!(back.iter().zip(&x).all(|(a, b)| (a - b).abs() < 1e-9));
+
+        let nested: Vec<Vec<f64>> = x.chunks(cols).map(<[f64]>::to_vec).collect();
+        let by_rows = fft2_rows(&nested).unwrap();
+        assert_eq!(by_rows.concat(), spectrum);
+        assert!(matches!(
+            fft2(&x, 5, 8),
+            Err(FftError::LengthMismatch(40, 48))
+        ));
+    }
+
+    #[test]
+    fn test_fft2_convolution() {
+        // Multiplying spectra convolves circularly: a delta at (1, 2) shifts
+        // the image by one row and two columns.
+        let (rows, cols) = (4, 4);
+        let image: Vec<f64> = (0..16).map(|i| i as f64).collect();
+        let mut delta = vec![0_f64; 16];
+        delta[cols + 2] = 1_f64;
+        let a = fft2(&image, rows, cols).unwrap();
+        let b = fft2(&delta, rows, cols).unwrap();
+        let product: Vec<Complex64> = a.iter().zip(&b).map(|(x, y)| x * y).collect();
+        let shifted = ifft2(&product, rows, cols).unwrap();
+        for r in 0..rows {
+            for c in 0..cols {
+                let src = ((r + rows - 1) % rows) * cols + (c + cols - 2) % cols;
+                assert!((shifted[r * cols + c] - image[src]).abs() < 1e-9);
+            }
+        }
+    }
+}
@@ -0,0 +1,218 @@
+use alloc::vec::Vec;
+use num::complex::Complex;
+
+use crate::error::FftError;
+use crate::fft::fft_in_place;
+use crate::float::{from_usize, FftNum};
+use crate::utils::checked_len;
+
+// N-dimensional transforms of arrays stored row-major (C order) with the
+// given shape, numpy's fftn semantics: the last axis varies fastest, and the
+// full transform is the 1D transform along every axis in turn. The _axes
+// variants transform along the listed axes only, so fftn_axes(x, &[b, n],
+// &[1]) is a batch of b transforms of length n. Non-finite values are
+// handled according to default_validation.
+
+fn check_shape<T>(x: &[T], shape: &[usize]) -> Result<(), FftError> {
+    let len = checked_len(shape)?;
+    if x.len() != len {
+        return Err(FftError::LengthMismatch(len, x.len()));
+    }
+    Ok(())
+}
+
+fn check_axes(shape: &[usize], axes: &[usize]) -> Result<(), FftError> {
+    for (i, &axis) in axes.iter().enumerate() {
+        if axis >= shape.len() {
+            return Err(FftError::InvalidParameter(
+                "axis out of range for the shape",
+            ));
+        }
+        if axes[..i].contains(&axis) {
+            return Err(FftError::InvalidParameter("axis listed twice"));
+        }
+    }
+    Ok(())
+}
+
+// fftn computes the N-dimensional Fast Fourier Transform over every axis
+pub fn fftn<T: FftNum>(x: &[Complex<T>], shape: &[usize]) -> Result<Vec<Complex<T>>, FftError> {
+    let axes: Vec<usize> = (0..shape.len()).collect();
+    fftn_axes(x, shape, &axes)
+}
+
+// fftn_axes computes the Fast Fourier Transform along the given axes
+pub fn fftn_axes<T: FftNum>(
+    x: &[Complex<T>],
+    shape: &[usize],
+    axes: &[usize],
+) -> Result<Vec<Complex<T>>, FftError> {
+    check_shape(x, shape)?;
+    check_axes(shape, axes)?;
+    let mut buffer = x.to_vec();
+    transform_axes(&mut buffer, shape, axes)?;
+    Ok(buffer)
+}
+
+// ifftn computes the N-dimensional Inverse Fast Fourier Transform over every
+// axis
+pub fn ifftn<T: FftNum>(x: &[Complex<T>], shape: &[usize]) -> Result<Vec<Complex<T>>, FftError> {
+    let axes: Vec<usize> = (0..shape.len()).collect();
+    ifftn_axes(x, shape, &axes)
+}
+
+// ifftn_axes computes the Inverse Fast Fourier Transform along the given
+// axes, dividing by the product of their lengths
+pub fn ifftn_axes<T: FftNum>(
+    x: &[Complex<T>],
+    shape: &[usize],
+    axes: &[usize],
+) -> Result<Vec<Complex<T>>, FftError> {
+    check_shape(x, shape)?;
+    check_axes(shape, axes)?;
+    // conjugate, transform forward, conjugate again and divide
+    let mut buffer: Vec<Complex<T>> = x.iter().map(|c| c.conj()).collect();
+    transform_axes(&mut buffer, shape, axes)?;
+    let count: usize = axes.iter().map(|&a| shape[a]).product();
+    let divisor = from_usize::<T>(count);
+    buffer.iter_mut().for_each(|c| *c = c.conj() / divisor);
+    Ok(buffer)
+}
+
+// fft_axis transforms real data along one axis, e.g. the rows (axis 1) or
+// columns (axis 0) of a matrix. It takes the contiguous elements and shape of
+// a standard-layout array, as ndarray's as_slice and shape return them, and
+// the result has the same shape. The ndarray module takes ndarray arrays of
+// any layout directly.
+pub fn fft_axis<T: FftNum>(
+    x: &[T],
+    shape: &[usize],
+    axis: usize,
+) -> Result<Vec<Complex<T>>, FftError> {
+    let x: Vec<Complex<T>> = x.iter().map(|&v| Complex::new(v, T::zero())).collect();
+    fftn_axes(&x, shape, &[axis])
+}
+
+// ifft_axis inverts fft_axis, keeping the full complex result
+pub fn ifft_axis<T: FftNum>(
+    x: &[Complex<T>],
+    shape: &[usize],
+    axis: usize,
+) -> Result<Vec<Complex<T>>, FftError> {
+    ifftn_axes(x, shape, &[axis])
+}
+
+// transform_axes transforms buffer in place along each of axes in turn. The
+// last axis is contiguous and transformed directly; the lines along other
+// axes are gathered into a scratch buffer.
+pub(crate) fn transform_axes<T: FftNum>(
+    buffer: &mut [Complex<T>],
+    shape: &[usize],
+    axes: &[usize],
+) -> Result<(), FftError> {
+    if buffer.is_empty() {
+        return Ok(());
+    }
+    let mut line = Vec::new();
+    for &axis in axes {
+        let len = shape[axis];
+        let stride: usize = shape[axis + 1..].iter().product();
+        if stride == 1 {
+            for chunk in buffer.chunks_exact_mut(len) {
+                fft_in_place(chunk)?;
+            }
+            continue;
+        }
+        for block in buffer.chunks_exact_mut(len * stride) {
+            for i in 0..stride {
+                line.clear();
+                line.extend(block.iter().skip(i).step_by(stride));
+                fft_in_place(&mut line)?;
+                for (dst, &v) in block.iter_mut().skip(i).step_by(stride).zip(&line) {
+                    *dst = v;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dft::dft_complex;
+    use crate::fft2::fft2_complex;
+    use num::complex::Complex64;
+
+    fn signal(n: usize) -> Vec<Complex64> {
+        (0..n)
+            .map(|i| Complex64::new(((i * 37) % 11) as f64, ((i * 5) % 3) as f64))
+            .collect()
+    }
+
+    #[test]
+    fn test_fftn_matches_direct_sum() {
+        let shape = [3, 4, 5];
+        let x = signal(60);
+        let spectrum = fftn(&x, &shape).unwrap();
+        // Direct 3D DFT with the exp(+) kernel of fft.
+        for (index, bin) in spectrum.iter().enumerate() {
+            let k = [index / 20, index / 5 % 4, index % 5];
+            let mut sum = Complex64::default();
+            for (j, v) in x.iter().enumerate() {
+                let n = [j / 20, j / 5 % 4, j % 5];
+                let phase: f64 = (0..3).map(|a| (k[a] * n[a]) as f64 / shape[a] as f64).sum();
+                sum += v * Complex64::from_polar(1_f64, 2_f64 * std::f64::consts::PI * phase);
+            }
+            assert!((bin - sum).norm() < 1e-9);
+        }
+        let back = ifftn(&spectrum, &shape).unwrap();
+        assert!(back.iter().zip(&x).all(|(a, b)| (a - b).norm() < 1e-9));
+        let planar = fft2_complex(&x[..20], 4, 5).unwrap();
+        let general = fftn(&x[..20], &[4, 5]).unwrap();
+        assert!(planar
+            .iter()
+            .zip(&general)
+            .all(|(a, b)| (a - b).norm() < 1e-9));
+    }
+
+    #[test]
+    fn test_fftn_axes() {
+        let x = signal(24);
+        // Along the last axis only: a batch of 1D transforms.
+        let rows = fftn_axes(&x, &[4, 6], &[1]).unwrap();
+        for (row, input) in rows.chunks(6).zip(x.chunks(6)) {
+            let expected = dft_complex(input).unwrap();
+            assert!(row
+                .iter()
+                .zip(&expected)
+                .all(|(a, b)| (a - b).norm() < 1e-9));
+        }
+        // The order of the axes does not matter.
+        let a = fftn_axes(&x, &[2, 3, 4], &[2, 0]).unwrap();
+        let b = fftn_axes(&x, &[2, 3, 4], &[0, 2]).unwrap();
+        assert!(a.iter().zip(&b).all(|(a, b)| (a - b).norm() < 1e-9));
+        let back = ifftn_axes(&a, &[2, 3, 4], &[0, 2]).unwrap();
+        assert!(back.iter().zip(&x).all(|(a, b)| (a - b).norm() < 1e-9));
+
+        // Columns of a 4 x 6 matrix.
+        let real: Vec<f64> = x.iter().map(|c| c.re).collect();
+        let columns = fft_axis(&real, &[4, 6], 0).unwrap();
+        for c in 0..6 {
+            let column: Vec<Complex64> = (0..4)
+                .map(|r| Complex64::new(real[r * 6 + c], 0_f64))
+                .collect();
+            let expected = dft_complex(&column).unwrap();
+            assert!((0..4).all(|r| (columns[r * 6 + c] - expected[r]).norm() < 1e-9));
+        }
+        let back = ifft_axis(&columns, &[4, 6], 0).unwrap();
+        assert!(back.iter().zip(&real).all(|(a, b)| (a.re - b).abs() < 1e-9));
+
+        assert!(fftn_axes(&x, &[4, 6], &[2]).is_err());
+        assert!(fftn_axes(&x, &[4, 6], &[1, 1]).is_err());
+        assert!(matches!(
+            fftn(&x, &[5, 5]),
+            Err(FftError::LengthMismatch(25, 24))
+        ));
+    }
+}
@@ -0,0 +1,126 @@
+use num::complex::Complex64;
+use std::ffi::{c_int, c_uint, c_void};
+use std::sync::Mutex;
+
+use crate::error::FftError;
+
+// Bindings to the subset of FFTW 3 used by FftwPlan, declared from the
+// fftw3.h of FFTW 3.3, whose API has not changed since 3.0. Intel MKL exports the
+// same functions through its FFTW3 interface, so linking a libfftw3 built on
+// MKL switches the implementation without code changes.
+type FftwPlanHandle = *mut c_void;
+
+// FFTW's backward sign is the exp(+) kernel of fft, without scaling.
+const FFTW_BACKWARD: c_int = 1;
+const FFTW_UNALIGNED: c_uint = 1 << 1;
+const FFTW_ESTIMATE: c_uint = 1 << 6;
+
+#[link(name = "fftw3")]
+extern "C" {
+    fn fftw_plan_dft_1d(
+        n: c_int,
+        input: *mut Complex64,
+        output: *mut Complex64,
+        sign: c_int,
+        flags: c_uint,
+    ) -> FftwPlanHandle;
+    fn fftw_execute_dft(plan: FftwPlanHandle, input: *mut Complex64, output: *mut Complex64);
+    fn fftw_destroy_plan(plan: FftwPlanHandle);
+}
+
+// FFTW's planner is not thread-safe; executing a finished plan is.
+static PLANNER: Mutex<()> = Mutex::new(());
+
+// FftwPlan is a forward transform of a fixed length computed by FFTW, used
+// like FftPlan, so results and speed can be compared against the built-in
+// plans. The plan is made with FFTW_ESTIMATE and FFTW_UNALIGNED, which lets
+// it run on any caller buffer from any thread.
+#[derive(Debug)]
+pub struct FftwPlan {
+    len: usize,
+    plan: FftwPlanHandle,
+}
+
+unsafe impl Send for FftwPlan {}
+unsafe impl Sync for FftwPlan {}
+
+impl FftwPlan {
+    // new plans an in-place transform of length len, which must be a power of
+    // two to agree with the rest of the crate
+    pub fn new(len: usize) -> Result<Self, FftError> {
+        if !len.is_power_of_two() {
+            return Err(FftError::NotAPowerOfTwo(len));
+        }
+        let n = c_int::try_from(len)
+            .map_err(|_| FftError::InvalidParameter("transform is too large for FFTW"))?;
+        // FFTW_ESTIMATE leaves the planning buffer untouched.
+        let mut buffer = vec![Complex64::default(); len];
+        let plan = {
+            let _guard = PLANNER
+                .lock()
+                .map_err(|_| FftError::Backend("FFTW planner was poisoned"))?;
+            unsafe {
+                fftw_plan_dft_1d(
+                    n,
+                    buffer.as_mut_ptr(),
+                    buffer.as_mut_ptr(),
+                    FFTW_BACKWARD,
+                    FFTW_ESTIMATE | FFTW_UNALIGNED,
+                )
+            }
+        };
+        if plan.is_null() {
+            return Err(FftError::Backend("fftw_plan_dft_1d failed"));
+        }
+        Ok(FftwPlan { len, plan })
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    // process transforms buffer in place
+    pub fn process(&self, buffer: &mut [Complex64]) -> Result<(), FftError> {
+        if buffer.len() != self.len {
+            return Err(FftError::LengthMismatch(self.len, buffer.len()));
+        }
+        // SAFETY: Complex64 matches fftw_complex and the plan was made
+        // in-place for len values without alignment requirements.
+        unsafe { fftw_execute_dft(self.plan, buffer.as_mut_ptr(), buffer.as_mut_ptr()) };
+        Ok(())
+    }
+}
+
+impl Drop for FftwPlan {
+    fn drop(&mut self) {
+        let _guard = PLANNER.lock();
+        unsafe { fftw_destroy_plan(self.plan) };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fftw_matches_fft() {
+        let x: Vec<Complex64> = crate::utils::generate_random_values()
+            .chunks(2)
+            .map(|c| Complex64::new(c[0], c[1]))
+            .collect();
+        let mut y = x.clone();
+        FftwPlan::new(512).unwrap().process(&mut y).unwrap();
+        let expected = crate::fft::fft_complex(&x).unwrap();
+        for (a, b) in y.iter().zip(expected.iter()) {
+            assert!((a - b).norm() < 1e-9);
+        }
+        assert!(matches!(
+            FftwPlan::new(12),
+            Err(FftError::NotAPowerOfTwo(12))
+        ));
+    }
+}
@@ -0,0 +1,314 @@
+use num::complex::Complex64;
+use std::f64::consts::PI;
+
+use crate::error::FftError;
+use crate::fft::{fft, ifft, irfft, rfft};
+use crate::window::kaiser_from_spec;
+
+// OverlapSave keeps the tail of the input seen so far so that consecutive
+// blocks can be transformed as overlapping frames. Each frame is the last
+// history_len input samples followed by the new block; after circular
+// convolution with a filter of at most history_len + 1 taps, only the samples
+// that line up with the new block are free of wrap-around and are kept.
+#[derive(Debug, Clone)]
+pub(crate) struct OverlapSave {
+    history: Vec<f64>,
+}
+
+impl OverlapSave {
+    pub(crate) fn new(history_len: usize) -> Self {
+        OverlapSave {
+            history: vec![0_f64; history_len],
+        }
+    }
+
+    // frame returns the history followed by block and advances the history
+    pub(crate) fn frame(&mut self, block: &[f64]) -> Vec<f64> {
+        let mut frame = Vec::with_capacity(self.history.len() + block.len());
+        frame.extend_from_slice(&self.history);
+        frame.extend_from_slice(block);
+        let keep = self.history.len();
+        self.history.copy_from_slice(&frame[frame.len() - keep..]);
+        frame
+    }
+
+    pub(crate) fn reset(&mut self) {
+        self.history.iter_mut().for_each(|v| *v = 0_f64);
+    }
+}
+
+// BlockMethod selects how FftFirFilter stitches the filtered blocks together.
+// OverlapAdd transforms every block zero-padded and adds the tail of its
+// convolution to the next output; OverlapSave transforms every block with
+// the taps.len() - 1 input samples before it and discards the wrapped part.
+// Both give the same output; OverlapSave skips the addition.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BlockMethod {
+    #[default]
+    OverlapAdd,
+    OverlapSave,
+}
+
+// FftFirFilter applies an FIR filter to a long or unbounded signal in blocks
+// of at most block_size samples, each filtered by one real FFT of the next
+// power of two of at least block_size + taps.len() - 1 points. Output sample
+// i is sum_t taps[t] x[i - t] over everything passed to process so far, so
+// the filter adds no delay beyond that of the taps, and chunk boundaries do
+// not change the output.
+#[derive(Debug, Clone)]
+pub struct FftFirFilter {
+    taps_len: usize,
+    block_size: usize,
+    fft_len: usize,
+    spectrum: Vec<Complex64>,
+    method: BlockMethod,
+    tail: Vec<f64>,
+    input: OverlapSave,
+}
+
+impl FftFirFilter {
+    pub fn new(taps: &[f64], block_size: usize) -> Result<Self, FftError> {
+        if taps.is_empty() {
+            return Err(FftError::InvalidParameter("filter needs at least one tap"));
+        }
+        if block_size == 0 {
+            return Err(FftError::InvalidParameter("block size must be positive"));
+        }
+        let fft_len = (block_size + taps.len() - 1).next_power_of_two();
+        let mut padded = taps.to_vec();
+        padded.resize(fft_len, 0_f64);
+        Ok(FftFirFilter {
+            taps_len: taps.len(),
+            block_size,
+            fft_len,
+            spectrum: rfft(&padded)?,
+            method: BlockMethod::default(),
+            tail: vec![0_f64; taps.len() - 1],
+            input: OverlapSave::new(taps.len() - 1),
+        })
+    }
+
+    // with_method selects the block method, overlap-add unless set
+    pub fn with_method(mut self, method: BlockMethod) -> Self {
+        self.method = method;
+        self.reset();
+        self
+    }
+
+    pub fn method(&self) -> BlockMethod {
+        self.method
+    }
+
+    pub fn taps_len(&self) -> usize {
+        self.taps_len
+    }
+
+    pub fn block_size(&self) -> usize {
+        self.block_size
+    }
+
+    // process filters the next x.len() samples of the signal and returns as
+    // many output samples
+    pub fn process(&mut self, x: &[f64]) -> Result<Vec<f64>, FftError> {
+        let mut out = Vec::with_capacity(x.len());
+        for block in x.chunks(self.block_size) {
+            match self.method {
+                BlockMethod::OverlapAdd => {
+                    let mut y = self.convolve(block.to_vec())?;
+                    y.truncate(block.len() + self.tail.len());
+                    for (v, t) in y.iter_mut().zip(&self.tail) {
+                        *v += t;
+                    }
+                    self.tail.copy_from_slice(&y[block.len()..]);
+                    out.extend_from_slice(&y[..block.len()]);
+                }
+                BlockMethod::OverlapSave => {
+                    let history = self.taps_len - 1;
+                    let frame = self.input.frame(block);
+                    let y = self.convolve(frame)?;
+                    out.extend_from_slice(&y[history..history + block.len()]);
+                }
+            }
+        }
+        Ok(out)
+    }
+
+    // reset clears the signal history, as if process had not been called
+    pub fn reset(&mut self) {
+        self.tail.iter_mut().for_each(|v| *v = 0_f64);
+        self.input.reset();
+    }
+
+    // convolve returns the circular convolution of the zero-padded frame with
+    // the taps over fft_len points
+    fn convolve(&self, mut frame: Vec<f64>) -> Result<Vec<f64>, FftError> {
+        frame.resize(self.fft_len, 0_f64);
+        let product: Vec<Complex64> = rfft(&frame)?
+            .iter()
+            .zip(&self.spectrum)
+            .map(|(x, h)| x * h)
+            .collect();
+        irfft(&product, self.fft_len)
+    }
+}
+
+// band_pass keeps the spectral content of x between low and high Hz
+// (inclusive) and removes everything else, using an ideal (brick-wall) mask
+// in the frequency domain. The mask is applied to both the positive and the
+// negative frequency of every bin, so the output stays real.
+pub fn band_pass(x: &[f64], sample_rate: f64, low: f64, high: f64) -> Result<Vec<f64>, FftError> {
+    if sample_rate <= 0_f64 {
+        return Err(FftError::InvalidParameter("sample rate must be positive"));
+    }
+    if !(0_f64 <= low && low <= high) {
+        return Err(FftError::InvalidParameter(
+            "band edges must satisfy 0 <= low <= high",
+        ));
+    }
+    let n = x.len();
+    let mut spectrum = fft(x)?;
+    for (k, bin) in spectrum.iter_mut().enumerate() {
+        let f = k.min(n - k) as f64 * sample_rate / n as f64;
+        if f < low || f > high {
+            *bin = Complex64::default();
+        }
+    }
+    ifft(&spectrum)
+}
+
+// fir_low_pass designs a linear-phase low-pass FIR filter with a
+// Kaiser-windowed sinc: cutoff is the middle of the transition band in
+// cycles per sample, and the window length and shape follow from the
+// stopband attenuation and transition width (see kaiser_from_spec). The
+// filter has an odd number of taps and a delay of half that, rounded down.
+pub fn fir_low_pass(
+    cutoff: f64,
+    stopband_db: f64,
+    transition_width: f64,
+) -> Result<Vec<f64>, FftError> {
+    if !(cutoff > 0_f64 && cutoff < 0.5) {
+        return Err(FftError::InvalidParameter(
+            "cutoff must be between 0 and 0.5 cycles per sample",
+        ));
+    }
+    let spec = kaiser_from_spec(stopband_db, transition_width)?;
+    let centre = (spec.len / 2) as f64;
+    Ok(spec
+        .window()
+        .iter()
+        .enumerate()
+        .map(|(i, w)| {
+            let t = i as f64 - centre;
+            let sinc = if t == 0_f64 {
+                2_f64 * cutoff
+            } else {
+                (2_f64 * PI * cutoff * t).sin() / (PI * t)
+            };
+            sinc * w
+        })
+        .collect())
+}
+
+// fir_band_pass designs a linear-phase FIR filter passing low to high
+// cycles per sample, as the difference of two fir_low_pass designs of the
+// same length
+pub fn fir_band_pass(
+    low: f64,
+    high: f64,
+    stopband_db: f64,
+    transition_width: f64,
+) -> Result<Vec<f64>, FftError> {
+    if low >= high {
+        return Err(FftError::InvalidParameter(
+            "band edges must satisfy low < high",
+        ));
+    }
+    let upper = fir_low_pass(high, stopband_db, transition_width)?;
+    let lower = fir_low_pass(low, stopband_db, transition_width)?;
+    Ok(upper.iter().zip(&lower).map(|(a, b)| a - b).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_band_pass_separates_tones() {
+        let n = 512;
+        let fs = 512_f64;
+        let low_tone = |i: usize| (2_f64 * PI * 10_f64 * i as f64 / fs).sin();
+        let high_tone = |i: usize| (2_f64 * PI * 100_f64 * i as f64 / fs).cos();
+        let x: Vec<f64> = (0..n).map(|i| low_tone(i) + high_tone(i)).collect();
+
+        let y = band_pass(&x, fs, 50.0, 150.0).unwrap();
+        for (i, v) in y.iter().enumerate() {
+            assert!((v - high_tone(i)).abs() < 1e-9);
+        }
+        assert!(matches!(
+            band_pass(&x, fs, 20.0, 10.0),
+            Err(FftError::InvalidParameter(_))
+        ));
+    }
+
+    #[test]
+    fn test_overlap_save_frames() {
+        let mut ols = OverlapSave::new(2);
+        assert_eq!(ols.frame(&[1.0, 2.0, 3.0]), vec![0.0, 0.0, 1.0, 2.0, 3.0]);
+        assert_eq!(ols.frame(&[4.0, 5.0, 6.0]), vec![2.0, 3.0, 4.0, 5.0, 6.0]);
+        ols.reset();
+        assert_eq!(ols.frame(&[7.0]), vec![0.0, 0.0, 7.0]);
+    }
+    #[test]
+    fn test_fft_fir_filter_streams_like_convolution() {
+        use crate::signal::{convolve, ConvolveMode};
+
+        let taps = fir_low_pass(0.1, 60.0, 0.05).unwrap();
+        let x: Vec<f64> = (0..1000).map(|i| ((i * 37) % 11) as f64 - 5.0).collect();
+        let mut expected = convolve(&x, &taps, ConvolveMode::Full).unwrap();
+        expected.truncate(x.len());
+        for method in [BlockMethod::OverlapAdd, BlockMethod::OverlapSave] {
+            let mut filter = FftFirFilter::new(&taps, 64).unwrap().with_method(method);
+            let mut y = Vec::new();
+            // Chunks shorter and longer than a block.
+            for chunk in x.chunks(45) {
+                y.extend(filter.process(chunk).unwrap());
+            }
+            assert_eq!(y.len(), x.len());
+            for (a, b) in y.iter().zip(&expected) {
+                assert!((a - b).abs() < 1e-9, "{:?}", method);
+            }
+            filter.reset();
+            let again = filter.process(&x[..100]).unwrap();
+            assert!(again
+                .iter()
+                .zip(&expected)
+                .all(|(a, b)| (a - b).abs() < 1e-9));
+        }
+        assert!(FftFirFilter::new(&[], 64).is_err());
+        assert!(FftFirFilter::new(&taps, 0).is_err());
+    }
+
+    #[test]
+    fn test_fir_low_pass_meets_spec() {
+        let h = fir_low_pass(0.1, 60.0, 0.05).unwrap();
+        assert_eq!(h.len(), 75);
+        let gain = |f: f64| {
+            let (re, im) = h
+                .iter()
+                .enumerate()
+                .fold((0_f64, 0_f64), |(re, im), (i, v)| {
+                    let phase = 2_f64 * PI * f * i as f64;
+                    (re + v * phase.cos(), im + v * phase.sin())
+                });
+            (re * re + im * im).sqrt()
+        };
+        for i in 0..=75 {
+            assert!((gain(i as f64 / 1000.0) - 1.0).abs() < 2e-3);
+        }
+        for i in 125..=500 {
+            assert!(20.0 * gain(i as f64 / 1000.0).log10() < -59.0);
+        }
+        let band = fir_band_pass(0.1, 0.3, 60.0, 0.05).unwrap();
+        assert!(band.iter().sum::<f64>().abs() < 1e-3);
+    }
+}
@@ -0,0 +1,262 @@
+use crate::error::FftError;
+use crate::frames::{frames, PadMode};
+use crate::pipeline::{Pipeline, Scale};
+
+// hz_to_bark converts a frequency in Hz to the Bark scale with Traunmüller's
+// formula, z = 26.81 f / (1960 + f) - 0.53
+pub fn hz_to_bark(f: f64) -> f64 {
+    26.81 * f / (1960_f64 + f) - 0.53
+}
+
+// bark_to_hz inverts hz_to_bark
+pub fn bark_to_hz(z: f64) -> f64 {
+    1960_f64 * (z + 0.53) / (26.28 - z)
+}
+
+// erb returns the equivalent rectangular bandwidth in Hz of the auditory
+// filter centred on f Hz (Glasberg and Moore)
+pub fn erb(f: f64) -> f64 {
+    24.7 * (4.37 * f / 1000_f64 + 1_f64)
+}
+
+// hz_to_erb_rate converts a frequency in Hz to the ERB-rate scale, the number
+// of ERBs below it
+pub fn hz_to_erb_rate(f: f64) -> f64 {
+    21.4 * (1_f64 + 4.37 * f / 1000_f64).log10()
+}
+
+// erb_rate_to_hz inverts hz_to_erb_rate
+pub fn erb_rate_to_hz(e: f64) -> f64 {
+    (10_f64.powf(e / 21.4) - 1_f64) * 1000_f64 / 4.37
+}
+
+fn check_range(sample_rate: f64, low: f64, high: f64) -> Result<(), FftError> {
+    if sample_rate <= 0_f64 {
+        return Err(FftError::InvalidParameter("sample rate must be positive"));
+    }
+    if low < 0_f64 || low >= high || high > sample_rate / 2_f64 {
+        return Err(FftError::InvalidParameter(
+            "band edges must satisfy 0 <= low < high <= sample_rate / 2",
+        ));
+    }
+    Ok(())
+}
+
+fn check_bank(
+    n_filters: usize,
+    frame_len: usize,
+    sample_rate: f64,
+    low: f64,
+    high: f64,
+) -> Result<(), FftError> {
+    check_range(sample_rate, low, high)?;
+    if n_filters == 0 || frame_len == 0 {
+        return Err(FftError::InvalidParameter(
+            "filter count and frame length must be greater than zero",
+        ));
+    }
+    Ok(())
+}
+
+// bark_filterbank returns n_filters triangular filters equally spaced on the
+// Bark scale between low and high Hz, sampled on the bins 0..=frame_len/2 of
+// a frame_len-point spectrum. Neighbouring filters overlap by half, so every
+// filter spans two Bark intervals and peaks at 1 on its centre frequency.
+pub fn bark_filterbank(
+    n_filters: usize,
+    frame_len: usize,
+    sample_rate: f64,
+    low: f64,
+    high: f64,
+) -> Result<Vec<Vec<f64>>, FftError> {
+    check_bank(n_filters, frame_len, sample_rate, low, high)?;
+    let (z_low, z_high) = (hz_to_bark(low), hz_to_bark(high));
+    let step = (z_high - z_low) / (n_filters + 1) as f64;
+    let bins = frame_len / 2 + 1;
+    Ok((0..n_filters)
+        .map(|m| {
+            let centre = z_low + (m + 1) as f64 * step;
+            (0..bins)
+                .map(|k| {
+                    let z = hz_to_bark(k as f64 * sample_rate / frame_len as f64);
+                    (1_f64 - (z - centre).abs() / step).max(0_f64)
+                })
+                .collect()
+        })
+        .collect())
+}
+
+// Order of the gammatone filters, the usual choice for auditory models.
+const GAMMATONE_ORDER: i32 = 4;
+
+// gammatone_filterbank returns n_filters fourth-order gammatone filters with
+// centre frequencies equally spaced on the ERB-rate scale from low to high Hz
+// inclusive, as power responses on the bins 0..=frame_len/2 of a
+// frame_len-point spectrum. The filters are applied in the frequency domain,
+// using the closed-form magnitude response
+// |H(f)|^2 = (1 + ((f - fc) / b)^2)^-4 with bandwidth b = 1.019 erb(fc).
+pub fn gammatone_filterbank(
+    n_filters: usize,
+    frame_len: usize,
+    sample_rate: f64,
+    low: f64,
+    high: f64,
+) -> Result<Vec<Vec<f64>>, FftError> {
+    check_bank(n_filters, frame_len, sample_rate, low, high)?;
+    let (e_low, e_high) = (hz_to_erb_rate(low), hz_to_erb_rate(high));
+    let step = if n_filters > 1 {
+        (e_high - e_low) / (n_filters - 1) as f64
+    } else {
+        0_f64
+    };
+    let bins = frame_len / 2 + 1;
+    Ok((0..n_filters)
+        .map(|m| {
+            let centre = erb_rate_to_hz(e_low + m as f64 * step);
+            let b = 1.019 * erb(centre);
+            (0..bins)
+                .map(|k| {
+                    let d = (k as f64 * sample_rate / frame_len as f64 - centre) / b;
+                    (1_f64 + d * d).powi(-GAMMATONE_ORDER)
+                })
+                .collect()
+        })
+        .collect())
+}
+
+// apply_filterbank returns the output of every filter for one spectrum, the
+// weighted sum of its bins
+pub fn apply_filterbank(filters: &[Vec<f64>], spectrum: &[f64]) -> Result<Vec<f64>, FftError> {
+    filters
+        .iter()
+        .map(|f| {
+            if f.len() != spectrum.len() {
+                return Err(FftError::LengthMismatch(f.len(), spectrum.len()));
+            }
+            Ok(f.iter().zip(spectrum.iter()).map(|(w, p)| w * p).sum())
+        })
+        .collect()
+}
+
+// bark_spectrogram returns, for every Hann-windowed frame of x (laid out as
+// for stft), the power collected by a bark_filterbank of n_filters filters
+// spanning 0 Hz to the Nyquist frequency
+pub fn bark_spectrogram(
+    x: &[f64],
+    sample_rate: f64,
+    frame_len: usize,
+    hop: usize,
+    n_filters: usize,
+) -> Result<Vec<Vec<f64>>, FftError> {
+    let filters = bark_filterbank(
+        n_filters,
+        frame_len,
+        sample_rate,
+        0_f64,
+        sample_rate / 2_f64,
+    )?;
+    let mut pipeline = Pipeline::builder(frame_len)
+        .hann()
+        .scale(Scale::Power)
+        .build()?;
+    frames(x, frame_len, hop, PadMode::Zero)?
+        .map(|frame| apply_filterbank(&filters, pipeline.process(&frame)?))
+        .collect()
+}
+
+// cochleagram returns, for every Hann-windowed frame of x (laid out as for
+// stft), the power at the output of a gammatone_filterbank of n_filters
+// channels between low and high Hz
+pub fn cochleagram(
+    x: &[f64],
+    sample_rate: f64,
+    frame_len: usize,
+    hop: usize,
+    n_filters: usize,
+    low: f64,
+    high: f64,
+) -> Result<Vec<Vec<f64>>, FftError> {
+    let filters = gammatone_filterbank(n_filters, frame_len, sample_rate, low, high)?;
+    let mut pipeline = Pipeline::builder(frame_len)
+        .hann()
+        .scale(Scale::Power)
+        .build()?;
+    frames(x, frame_len, hop, PadMode::Zero)?
+        .map(|frame| apply_filterbank(&filters, pipeline.process(&frame)?))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::PI;
+
+    #[test]
+    fn test_bark_scale() {
+        assert!((hz_to_bark(1000.0) - 8.53).abs() < 0.01);
+        for f in [100.0, 1000.0, 5000.0] {
+            assert!((bark_to_hz(hz_to_bark(f)) - f).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_erb_scale() {
+        assert!((erb(1000.0) - 132.639).abs() < 1e-3);
+        for f in [50.0, 1000.0, 7000.0] {
+            assert!((erb_rate_to_hz(hz_to_erb_rate(f)) - f).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_cochleagram_tracks_tone() {
+        let fs = 16000_f64;
+        let x: Vec<f64> = (0..4096)
+            .map(|i| (2_f64 * PI * 2000_f64 * i as f64 / fs).sin())
+            .collect();
+        let filters = gammatone_filterbank(32, 512, fs, 100.0, 8000.0).unwrap();
+        // Even the narrowest channel is wider than the bin spacing.
+        assert!(filters
+            .iter()
+            .all(|f| f.iter().cloned().fold(0_f64, f64::max) > 0.5));
+
+        let cochlea = cochleagram(&x, fs, 512, 256, 32, 100.0, 8000.0).unwrap();
+        let frame = &cochlea[cochlea.len() / 2];
+        let loudest = (0..32)
+            .max_by(|&a, &b| frame[a].total_cmp(&frame[b]))
+            .unwrap();
+        let step = (hz_to_erb_rate(8000.0) - hz_to_erb_rate(100.0)) / 31.0;
+        let centre = erb_rate_to_hz(hz_to_erb_rate(100.0) + loudest as f64 * step);
+        assert!((centre - 2000.0).abs() < erb(2000.0), "centre {}", centre);
+    }
+
+    #[test]
+    fn test_bark_filterbank_shape() {
+        let filters = bark_filterbank(20, 512, 16000.0, 0.0, 8000.0).unwrap();
+        assert_eq!(filters.len(), 20);
+        assert!(filters.iter().all(|f| f.len() == 257));
+        for f in &filters {
+            let peak = f.iter().cloned().fold(0_f64, f64::max);
+            assert!(peak > 0.5 && peak <= 1.0);
+        }
+        assert!(matches!(
+            bark_filterbank(20, 512, 16000.0, 0.0, 9000.0),
+            Err(FftError::InvalidParameter(_))
+        ));
+    }
+
+    #[test]
+    fn test_bark_spectrogram_tracks_tone() {
+        let fs = 16000_f64;
+        let x: Vec<f64> = (0..4096)
+            .map(|i| (2_f64 * PI * 1000_f64 * i as f64 / fs).sin())
+            .collect();
+        let spec = bark_spectrogram(&x, fs, 512, 256, 24).unwrap();
+        let frame = &spec[spec.len() / 2];
+        let loudest = (0..24)
+            .max_by(|&a, &b| frame[a].total_cmp(&frame[b]))
+            .unwrap();
+        let step = (hz_to_bark(8000.0) - hz_to_bark(0.0)) / 25.0;
+        let centre = hz_to_bark(0.0) + (loudest + 1) as f64 * step;
+        assert!((centre - hz_to_bark(1000.0)).abs() <= step);
+    }
+}
@@ -0,0 +1,254 @@
+use crate::error::FftError;
+use crate::frames::{frames, PadMode};
+use crate::pipeline::{Pipeline, Scale};
+
+// Fingerprinter holds the parameters of the constellation fingerprinter.
+//
+// Spectrogram frames of frame_len samples are taken every hop samples. A
+// time-frequency point is a peak when it is the maximum of the
+// (2 * time_radius + 1) x (2 * freq_radius + 1) box around it and lies at
+// least min_db above the floor of -100 dB. Every peak is paired with up to
+// fan_out later peaks at most max_dt frames ahead.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fingerprinter {
+    pub frame_len: usize,
+    pub hop: usize,
+    pub time_radius: usize,
+    pub freq_radius: usize,
+    pub min_db: f64,
+    pub fan_out: usize,
+    pub max_dt: usize,
+}
+
+impl Default for Fingerprinter {
+    fn default() -> Self {
+        Fingerprinter {
+            frame_len: 1024,
+            hop: 512,
+            time_radius: 10,
+            freq_radius: 10,
+            min_db: 40.0,
+            fan_out: 5,
+            max_dt: 64,
+        }
+    }
+}
+
+// Floor of the dB spectrogram searched for peaks.
+const FLOOR_DB: f64 = -100.0;
+
+// Peak is a point of the constellation: a local maximum of the spectrogram.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Peak {
+    pub frame: usize,
+    pub bin: usize,
+    pub db: f64,
+}
+
+// PairHash is the hash of a pair of peaks together with the frame of the
+// earlier (anchor) peak. Matching a query against a database amounts to
+// finding equal hashes whose anchor frames differ by a consistent offset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PairHash {
+    pub hash: u32,
+    pub anchor_frame: usize,
+}
+
+// constellation returns the local maxima of spectrogram (frames of dB values)
+// in frame, then bin order. Frames may differ in length; a neighbouring frame
+// is compared only over the bins it has.
+pub fn constellation(
+    spectrogram: &[Vec<f64>],
+    time_radius: usize,
+    freq_radius: usize,
+    min_db: f64,
+) -> Vec<Peak> {
+    let n_frames = spectrogram.len();
+    let mut peaks = Vec::new();
+    for (t, frame) in spectrogram.iter().enumerate() {
+        for (k, &value) in frame.iter().enumerate() {
+            if value < FLOOR_DB + min_db {
+                continue;
+            }
+            let (t_lo, t_hi) = (
+                t.saturating_sub(time_radius),
+                (t + time_radius).min(n_frames - 1),
+            );
+            let f_lo = k.saturating_sub(freq_radius);
+            let f_end = |other: &[f64]| (k + freq_radius + 1).min(other.len());
+            let is_max = spectrogram[t_lo..=t_hi].iter().all(|other| {
+                let end = f_end(other);
+                other[f_lo.min(end)..end].iter().all(|&v| v <= value)
+            });
+            // Plateaus keep only their first point.
+            let is_first = spectrogram[t_lo..=t].iter().enumerate().all(|(i, other)| {
+                let end = if t_lo + i == t { k } else { f_end(other) };
+                other[f_lo.min(end)..end].iter().all(|&v| v < value)
+            });
+            if is_max && is_first {
+                peaks.push(Peak {
+                    frame: t,
+                    bin: k,
+                    db: value,
+                });
+            }
+        }
+    }
+    peaks
+}
+
+// pair_hash packs the anchor bin (10 bits), the target bin (10 bits) and the
+// frame distance (12 bits) into 32 bits
+fn pair_hash(anchor: &Peak, target: &Peak) -> u32 {
+    let dt = (target.frame - anchor.frame) as u32;
+    ((anchor.bin as u32 & 0x3ff) << 22) | ((target.bin as u32 & 0x3ff) << 12) | (dt & 0xfff)
+}
+
+// pair_hashes pairs every peak (in frame order, as returned by
+// constellation) with the next fan_out peaks that lie 1 to max_dt frames
+// after it
+pub fn pair_hashes(peaks: &[Peak], fan_out: usize, max_dt: usize) -> Vec<PairHash> {
+    let mut hashes = Vec::new();
+    for (i, anchor) in peaks.iter().enumerate() {
+        peaks[i + 1..]
+            .iter()
+            .filter(|p| p.frame > anchor.frame)
+            .take_while(|p| p.frame - anchor.frame <= max_dt)
+            .take(fan_out)
+            .for_each(|target| {
+                hashes.push(PairHash {
+                    hash: pair_hash(anchor, target),
+                    anchor_frame: anchor.frame,
+                })
+            });
+    }
+    hashes
+}
+
+// fingerprint extracts the constellation of x from its Hann-windowed dB
+// magnitude spectrogram and returns the peaks and their pair hashes
+pub fn fingerprint(
+    x: &[f64],
+    params: &Fingerprinter,
+) -> Result<(Vec<Peak>, Vec<PairHash>), FftError> {
+    if params.fan_out == 0 || params.max_dt == 0 {
+        return Err(FftError::InvalidParameter(
+            "fan-out and maximum frame distance must be greater than zero",
+        ));
+    }
+    let mut pipeline = Pipeline::builder(params.frame_len)
+        .hann()
+        .scale(Scale::Magnitude)
+        .decibels(FLOOR_DB)
+        .build()?;
+    let spectrogram: Vec<Vec<f64>> = frames(x, params.frame_len, params.hop, PadMode::None)?
+        .map(|frame| pipeline.process(&frame).map(|s| s.to_vec()))
+        .collect::<Result<_, FftError>>()?;
+    let peaks = constellation(
+        &spectrogram,
+        params.time_radius,
+        params.freq_radius,
+        params.min_db,
+    );
+    let hashes = pair_hashes(&peaks, params.fan_out, params.max_dt);
+    Ok((peaks, hashes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+    use std::collections::HashMap;
+    use std::f64::consts::PI;
+
+    #[test]
+    fn test_constellation_finds_isolated_maxima() {
+        let mut spec = vec![vec![FLOOR_DB; 16]; 8];
+        spec[2][5] = 10.0;
+        spec[2][6] = 9.0;
+        spec[6][12] = 20.0;
+        spec[6][13] = 20.0;
+        let peaks = constellation(&spec, 2, 2, 40.0);
+        assert_eq!(peaks.len(), 2);
+        assert_eq!((peaks[0].frame, peaks[0].bin), (2, 5));
+        assert_eq!((peaks[1].frame, peaks[1].bin), (6, 12));
+
+        let hashes = pair_hashes(&peaks, 3, 10);
+        assert_eq!(hashes.len(), 1);
+        assert_eq!(hashes[0].hash, (5 << 22) | (12 << 12) | 4);
+        assert!(pair_hashes(&peaks, 3, 3).is_empty());
+
+        // Ragged and empty neighbours are compared over the bins they have.
+        let ragged = vec![
+            vec![],
+            vec![FLOOR_DB; 3],
+            vec![FLOOR_DB, FLOOR_DB, FLOOR_DB, 5.0],
+        ];
+        let peaks = constellation(&ragged, 2, 1, 40.0);
+        assert_eq!(peaks.len(), 1);
+        assert_eq!((peaks[0].frame, peaks[0].bin), (2, 3));
+    }
+
+    // melody returns a sequence of 100 ms tones over faint noise
+    fn melody(fs: f64, seed: u64) -> Vec<f64> {
+        let notes = [440.0, 660.0, 550.0, 880.0, 495.0, 740.0, 600.0, 990.0];
+        let mut rng = StdRng::seed_from_u64(seed);
+        let per_note = (fs * 0.1) as usize;
+        (0..notes.len() * per_note)
+            .map(|i| {
+                let f = notes[i / per_note];
+                (2_f64 * PI * f * i as f64 / fs).sin() + 0.01 * (rng.random::<f64>() - 0.5)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_fingerprint_matches_excerpt() {
+        let fs = 8000_f64;
+        let params = Fingerprinter {
+            frame_len: 256,
+            hop: 128,
+            time_radius: 3,
+            freq_radius: 8,
+            ..Fingerprinter::default()
+        };
+        let song = melody(fs, 1);
+        let (peaks, hashes) = fingerprint(&song, &params).unwrap();
+        assert!(!peaks.is_empty() && !hashes.is_empty());
+
+        // A noisy excerpt starting 10 frames in shares hashes with the song at
+        // a consistent anchor offset.
+        let excerpt: Vec<f64> = melody(fs, 2)[1280..].to_vec();
+        let (_, query) = fingerprint(&excerpt, &params).unwrap();
+        let mut offsets: HashMap<isize, usize> = HashMap::new();
+        for q in &query {
+            for h in hashes.iter().filter(|h| h.hash == q.hash) {
+                *offsets
+                    .entry(h.anchor_frame as isize - q.anchor_frame as isize)
+                    .or_default() += 1;
+            }
+        }
+        let (&best, _) = offsets.iter().max_by_key(|(_, &count)| count).unwrap();
+        assert_eq!(best, 10);
+    }
+
+    #[test]
+    fn test_fingerprint_invalid_params() {
+        let params = Fingerprinter {
+            fan_out: 0,
+            ..Fingerprinter::default()
+        };
+        assert!(matches!(
+            fingerprint(&[0.0; 4096], &params),
+            Err(FftError::InvalidParameter(_))
+        ));
+        let params = Fingerprinter {
+            frame_len: 1000,
+            ..Fingerprinter::default()
+        };
+        assert!(matches!(
+            fingerprint(&[0.0; 4096], &params),
+            Err(FftError::NotAPowerOfTwo(1000))
+        ));
+    }
+}
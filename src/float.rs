@@ -0,0 +1,71 @@
+use core::fmt::Debug;
+use num::complex::Complex;
+use num::traits::{Float, FloatConst, NumAssign};
+
+use crate::butterflies::{butterfly, has_butterfly};
+
+pub use num::complex::{Complex32, Complex64};
+
+// FftNum is the precision of the generic transforms of the fft and dft
+// modules, f64 or f32. Single precision halves memory and bandwidth at about
+// 1e-7 relative accuracy. Twiddle factors and other constants are computed
+// in f64 and rounded once, so f32 transforms lose no accuracy to them.
+pub trait FftNum: Float + FloatConst + NumAssign + Default + Debug + Send + Sync + 'static {
+    fn from_f64(v: f64) -> Self;
+
+    // butterfly transforms buffer with an unrolled kernel if one exists for
+    // its length in this precision, and tells whether it did
+    fn butterfly(_buffer: &mut [Complex<Self>]) -> bool {
+        false
+    }
+
+    // has_butterfly tells whether butterfly handles length n
+    fn has_butterfly(_n: usize) -> bool {
+        false
+    }
+
+    // radix4_pass runs one pass of the radix-4 kernel over blocks of length
+    // len with a vectorized kernel if one exists, and tells whether it did
+    fn radix4_pass(_buffer: &mut [Complex<Self>], _len: usize) -> bool {
+        false
+    }
+}
+
+impl FftNum for f32 {
+    fn from_f64(v: f64) -> Self {
+        v as f32
+    }
+}
+
+impl FftNum for f64 {
+    fn from_f64(v: f64) -> Self {
+        v
+    }
+
+    fn butterfly(buffer: &mut [Complex64]) -> bool {
+        has_butterfly(buffer.len()) && butterfly(buffer).is_ok()
+    }
+
+    fn has_butterfly(n: usize) -> bool {
+        has_butterfly(n)
+    }
+
+    #[cfg(feature = "simd")]
+    fn radix4_pass(buffer: &mut [Complex64], len: usize) -> bool {
+        crate::simd::radix4_pass(buffer, len)
+    }
+}
+
+// twiddle returns exp(2j pi num / den)
+pub(crate) fn twiddle<T: FftNum>(num: usize, den: usize) -> Complex<T> {
+    let w = Complex64::from_polar(
+        1_f64,
+        2_f64 * core::f64::consts::PI * num as f64 / den as f64,
+    );
+    Complex::new(T::from_f64(w.re), T::from_f64(w.im))
+}
+
+// from_usize converts a length or index
+pub(crate) fn from_usize<T: FftNum>(v: usize) -> T {
+    T::from_f64(v as f64)
+}
@@ -0,0 +1,170 @@
+use std::borrow::Cow;
+
+use crate::error::FftError;
+
+// PadMode selects how frames are laid out over a signal. None yields only the
+// frames that fit entirely inside the signal, starting at sample 0. Zero and
+// Reflect centre the first frame on sample 0 by padding frame_len / 2 samples
+// on both sides, and keep adding frames until the whole padded signal is
+// covered; Zero pads with zeros and Reflect mirrors the signal about its
+// first and last samples (without repeating them).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PadMode {
+    None,
+    Zero,
+    Reflect,
+}
+
+// Frames iterates over the frames of a signal. Frames lying entirely inside
+// the signal are borrowed from it; only frames overlapping the padding are
+// copied.
+#[derive(Debug, Clone)]
+pub struct Frames<'a> {
+    signal: &'a [f64],
+    frame_len: usize,
+    hop: usize,
+    pad_mode: PadMode,
+    next: usize,
+    count: usize,
+}
+
+// frame_count returns the number of frames frames yields for a signal of len
+// samples
+pub fn frame_count(len: usize, frame_len: usize, hop: usize, pad_mode: PadMode) -> usize {
+    match pad_mode {
+        PadMode::None if len < frame_len => 0,
+        PadMode::None => 1 + (len - frame_len) / hop,
+        PadMode::Zero | PadMode::Reflect => {
+            let padded = len + 2 * (frame_len / 2);
+            if padded <= frame_len {
+                1
+            } else {
+                1 + (padded - frame_len).div_ceil(hop)
+            }
+        }
+    }
+}
+
+// frames splits signal into frames of frame_len samples taken every hop
+// samples, padded according to pad_mode
+pub fn frames(
+    signal: &[f64],
+    frame_len: usize,
+    hop: usize,
+    pad_mode: PadMode,
+) -> Result<Frames<'_>, FftError> {
+    if frame_len == 0 {
+        return Err(FftError::InvalidParameter(
+            "frame length must be greater than zero",
+        ));
+    }
+    if hop == 0 {
+        return Err(FftError::InvalidParameter("hop must be greater than zero"));
+    }
+    if pad_mode == PadMode::Reflect && signal.is_empty() {
+        return Err(FftError::InvalidParameter(
+            "cannot reflect-pad an empty signal",
+        ));
+    }
+    Ok(Frames {
+        signal,
+        frame_len,
+        hop,
+        pad_mode,
+        next: 0,
+        count: frame_count(signal.len(), frame_len, hop, pad_mode),
+    })
+}
+
+impl Frames<'_> {
+    // sample returns the padded value at index i of the signal, which may lie
+    // before its start or past its end
+    fn sample(&self, i: isize) -> f64 {
+        let len = self.signal.len() as isize;
+        match self.pad_mode {
+            PadMode::Reflect if len == 1 => self.signal[0],
+            PadMode::Reflect => {
+                let period = 2 * (len - 1);
+                let j = i.rem_euclid(period);
+                self.signal[(if j < len { j } else { period - j }) as usize]
+            }
+            _ if (0..len).contains(&i) => self.signal[i as usize],
+            _ => 0_f64,
+        }
+    }
+}
+
+impl<'a> Iterator for Frames<'a> {
+    type Item = Cow<'a, [f64]>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.next >= self.count {
+            return None;
+        }
+        let pad = match self.pad_mode {
+            PadMode::None => 0,
+            PadMode::Zero | PadMode::Reflect => self.frame_len / 2,
+        };
+        let start = (self.next * self.hop) as isize - pad as isize;
+        self.next += 1;
+        let end = start + self.frame_len as isize;
+        if start >= 0 && end <= self.signal.len() as isize {
+            let signal: &'a [f64] = self.signal;
+            Some(Cow::Borrowed(&signal[start as usize..end as usize]))
+        } else {
+            Some(Cow::Owned((start..end).map(|i| self.sample(i)).collect()))
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.count - self.next;
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for Frames<'_> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frames_without_padding_are_borrowed() {
+        let x: Vec<f64> = (0..10).map(|i| i as f64).collect();
+        let f: Vec<_> = frames(&x, 4, 3, PadMode::None).unwrap().collect();
+        assert_eq!(f.len(), 3);
+        assert!(f.iter().all(|frame| matches!(frame, Cow::Borrowed(_))));
+        assert_eq!(&*f[2], &[6.0, 7.0, 8.0, 9.0]);
+        assert_eq!(frames(&x, 16, 3, PadMode::None).unwrap().len(), 0);
+    }
+
+    #[test]
+    fn test_frames_padding() {
+        let x = [1.0, 2.0, 3.0, 4.0, 5.0];
+        let zero: Vec<_> = frames(&x, 4, 2, PadMode::Zero).unwrap().collect();
+        assert_eq!(zero.len(), frame_count(5, 4, 2, PadMode::Zero));
+        assert_eq!(&*zero[0], &[0.0, 0.0, 1.0, 2.0]);
+        assert!(matches!(zero[1], Cow::Borrowed(_)));
+        assert_eq!(&*zero[3], &[5.0, 0.0, 0.0, 0.0]);
+
+        let reflect: Vec<_> = frames(&x, 4, 2, PadMode::Reflect).unwrap().collect();
+        assert_eq!(&*reflect[0], &[3.0, 2.0, 1.0, 2.0]);
+        assert_eq!(&*reflect[3], &[5.0, 4.0, 3.0, 2.0]);
+    }
+
+    #[test]
+    fn test_frames_invalid_params() {
+        assert!(matches!(
+            frames(&[0.0; 8], 0, 1, PadMode::None),
+            Err(FftError::InvalidParameter(_))
+        ));
+        assert!(matches!(
+            frames(&[0.0; 8], 4, 0, PadMode::Zero),
+            Err(FftError::InvalidParameter(_))
+        ));
+        assert!(matches!(
+            frames(&[], 4, 2, PadMode::Reflect),
+            Err(FftError::InvalidParameter(_))
+        ));
+    }
+}
@@ -0,0 +1,77 @@
+use core::f64::consts::PI;
+use num::complex::Complex64;
+use num::traits::Float;
+
+use crate::error::FftError;
+
+// goertzel returns bin target_bin of the FFT of x, fft(x)[target_bin], in
+// O(N) time and O(1) memory. A handful of bins, e.g. the eight DTMF tones,
+// is cheaper this way than through a full transform.
+pub fn goertzel(x: &[f64], target_bin: usize) -> Result<Complex64, FftError> {
+    if target_bin >= x.len() {
+        return Err(FftError::InvalidParameter(
+            "target bin must be below the signal length",
+        ));
+    }
+    Ok(goertzel_at(
+        x,
+        2_f64 * PI * target_bin as f64 / x.len() as f64,
+    ))
+}
+
+// goertzel_hz returns the DFT of x at frequency Hz, which need not fall on a
+// bin, in the exp(+) convention of fft
+pub fn goertzel_hz(x: &[f64], frequency: f64, sample_rate: f64) -> Result<Complex64, FftError> {
+    if sample_rate.is_nan() || sample_rate <= 0_f64 {
+        return Err(FftError::InvalidParameter("sample rate must be positive"));
+    }
+    if !frequency.is_finite() {
+        return Err(FftError::InvalidParameter("frequency must be finite"));
+    }
+    Ok(goertzel_at(x, 2_f64 * PI * frequency / sample_rate))
+}
+
+// goertzel_at runs the recursion s[n] = x[n] + 2 cos(w) s[n-1] - s[n-2] one
+// step past the end of x, after which s[N] - exp(-jw) s[N-1] is
+// sum_n x[n] exp(jw (N - n)). Rotating by exp(-jwN) gives the exp(-) DFT,
+// whose conjugate is the exp(+) one for real x.
+fn goertzel_at(x: &[f64], omega: f64) -> Complex64 {
+    let coeff = 2_f64 * Float::cos(omega);
+    let (mut s1, mut s2) = (0_f64, 0_f64);
+    for &v in x {
+        let s0 = v + coeff * s1 - s2;
+        s2 = s1;
+        s1 = s0;
+    }
+    let last = coeff * s1 - s2;
+    let y = Complex64::new(last, 0_f64) - Complex64::from_polar(s1, -omega);
+    (Complex64::from_polar(1_f64, -omega * x.len() as f64) * y).conj()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fft::fft;
+
+    #[test]
+    fn test_goertzel_matches_fft() {
+        let x: Vec<f64> = (0..205).map(|i| ((i * 37) % 11) as f64 - 5_f64).collect();
+        let spectrum = fft(&x).unwrap();
+        for k in [0, 1, 18, 102, 204] {
+            assert!((goertzel(&x, k).unwrap() - spectrum[k]).norm() < 1e-9);
+        }
+        assert!(goertzel(&x, 205).is_err());
+
+        // A 697 Hz DTMF row tone sampled at 8 kHz: its frequency is far off
+        // the bins of 205 samples, and goertzel_hz finds it exactly.
+        let fs = 8000_f64;
+        let tone: Vec<f64> = (0..205)
+            .map(|i| (2_f64 * PI * 697_f64 * i as f64 / fs).cos())
+            .collect();
+        let on = goertzel_hz(&tone, 697_f64, fs).unwrap().norm();
+        let off = goertzel_hz(&tone, 1209_f64, fs).unwrap().norm();
+        assert!((on - 102.5).abs() < 1_f64);
+        assert!(off < 0.05 * on);
+        assert!(goertzel_hz(&tone, 697_f64, 0_f64).is_err());
+    }
+}
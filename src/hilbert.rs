@@ -0,0 +1,114 @@
+use num::complex::{Complex, Complex64};
+use std::f64::consts::PI;
+
+use crate::error::FftError;
+use crate::fft::{fft, ifft_complex};
+
+// analytic_signal computes the analytic signal x + j * H{x} of a real signal,
+// where H is the Hilbert transform, by suppressing the negative-frequency half
+// of its spectrum and doubling the positive half.
+pub fn analytic_signal(x: &[f64]) -> Result<Vec<Complex64>, FftError> {
+    let n = x.len();
+    let mut spectrum = fft(x)?;
+    // fft uses the exp(+j) kernel, so positive frequencies occupy the upper
+    // half of the bins and negative frequencies the lower half. DC (and
+    // Nyquist for even n) are kept as they are.
+    for (k, bin) in spectrum.iter_mut().enumerate() {
+        if k == 0 || 2 * k == n {
+            continue;
+        }
+        *bin *= if 2 * k > n {
+            Complex::new(2_f64, 0_f64)
+        } else {
+            Complex64::default()
+        };
+    }
+    ifft_complex(&spectrum)
+}
+
+// hilbert returns the Hilbert transform of x, the imaginary part of its
+// analytic signal
+pub fn hilbert(x: &[f64]) -> Result<Vec<f64>, FftError> {
+    Ok(analytic_signal(x)?.iter().map(|c| c.im).collect())
+}
+
+// envelope returns the instantaneous amplitude of x, the magnitude of its
+// analytic signal
+pub fn envelope(x: &[f64]) -> Result<Vec<f64>, FftError> {
+    Ok(analytic_signal(x)?.iter().map(|c| c.norm()).collect())
+}
+
+// instantaneous_frequency returns the instantaneous frequency of x in Hz,
+// the phase increment between consecutive samples of its analytic signal. The
+// first sample has no predecessor and repeats the second value.
+pub fn instantaneous_frequency(x: &[f64], sample_rate: f64) -> Result<Vec<f64>, FftError> {
+    let z = analytic_signal(x)?;
+    let scale = sample_rate / (2_f64 * PI);
+    let mut f: Vec<f64> = z
+        .windows(2)
+        .map(|w| (w[1] * w[0].conj()).arg() * scale)
+        .collect();
+    if let Some(&first) = f.first() {
+        f.insert(0, first);
+    }
+    Ok(f)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_analytic_signal_of_cosine() {
+        let n = 256;
+        let w = 2_f64 * PI * 8_f64 / n as f64;
+        let x: Vec<f64> = (0..n).map(|i| (w * i as f64).cos()).collect();
+        let z = analytic_signal(&x).unwrap();
+        for (i, c) in z.iter().enumerate() {
+            let expected = Complex64::from_polar(1.0, w * i as f64);
+            assert!((c - expected).norm() < 1e-9, "index {}: {}", i, c);
+        }
+    }
+
+    #[test]
+    fn test_hilbert_of_sine_is_negative_cosine() {
+        let n = 128;
+        let w = 2_f64 * PI * 5_f64 / n as f64;
+        let x: Vec<f64> = (0..n).map(|i| (w * i as f64).sin()).collect();
+        let h = hilbert(&x).unwrap();
+        for (i, v) in h.iter().enumerate() {
+            assert!((v + (w * i as f64).cos()).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_instantaneous_frequency_of_tone() {
+        let fs = 512_f64;
+        let x: Vec<f64> = (0..512)
+            .map(|i| (2_f64 * PI * 64_f64 * i as f64 / fs).cos())
+            .collect();
+        let f = instantaneous_frequency(&x, fs).unwrap();
+        assert_eq!(f.len(), 512);
+        for v in f.iter() {
+            assert!((v - 64_f64).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_envelope_of_am_signal() {
+        let n = 1024;
+        let x: Vec<f64> = (0..n)
+            .map(|i| {
+                let t = i as f64 / n as f64;
+                let am = 1_f64 + 0.5 * (2_f64 * PI * 4_f64 * t).cos();
+                am * (2_f64 * PI * 128_f64 * t).cos()
+            })
+            .collect();
+        let e = envelope(&x).unwrap();
+        for (i, v) in e.iter().enumerate() {
+            let t = i as f64 / n as f64;
+            let am = 1_f64 + 0.5 * (2_f64 * PI * 4_f64 * t).cos();
+            assert!((v - am).abs() < 1e-9);
+        }
+    }
+}
@@ -0,0 +1,160 @@
+use crate::error::FftError;
+use crate::stft::stft;
+use crate::window::hann;
+
+// spectral_kurtosis estimates the spectral kurtosis of x for every
+// non-redundant bin of a Hann-windowed STFT with the given frame length and
+// hop:
+//
+//   SK(k) = <|X(k)|^4> / <|X(k)|^2>^2 - 2
+//
+// averaged over frames. Stationary Gaussian noise gives values around zero,
+// while bins excited by impulsive, intermittent events (bearing or gear
+// faults) give large positive values. The DC and Nyquist bins are real-valued,
+// so they sit around one for Gaussian noise instead.
+pub fn spectral_kurtosis(x: &[f64], frame_len: usize, hop: usize) -> Result<Vec<f64>, FftError> {
+    let frames = stft(x, frame_len, hop, &hann(frame_len))?;
+    let half = frame_len / 2 + 1;
+    let mut m2 = vec![0_f64; half];
+    let mut m4 = vec![0_f64; half];
+    for frame in frames.iter() {
+        for k in 0..half {
+            let p = frame[k].norm_sqr();
+            m2[k] += p;
+            m4[k] += p * p;
+        }
+    }
+    let n = frames.len() as f64;
+    Ok(m2
+        .iter()
+        .zip(m4.iter())
+        .map(|(&s2, &s4)| {
+            let (s2, s4) = (s2 / n, s4 / n);
+            if s2 > 0_f64 {
+                s4 / (s2 * s2) - 2_f64
+            } else {
+                0_f64
+            }
+        })
+        .collect())
+}
+
+// KurtogramLevel is one row of a kurtogram: the spectral kurtosis obtained
+// with a given frame length (and hence frequency resolution).
+#[derive(Debug, Clone, PartialEq)]
+pub struct KurtogramLevel {
+    pub frame_len: usize,
+    pub kurtosis: Vec<f64>,
+}
+
+// KurtogramPeak locates the maximum of a kurtogram. frequency is normalized
+// to cycles per sample (0 to 0.5), so it is comparable across levels.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct KurtogramPeak {
+    pub frame_len: usize,
+    pub bin: usize,
+    pub frequency: f64,
+    pub kurtosis: f64,
+}
+
+// kurtogram computes the spectral kurtosis of x for each of the given frame
+// lengths, with a hop of a quarter frame. Short frames resolve short
+// transients but coarse frequency bands, long frames the opposite; the
+// maximum over the map points at the band and resolution that best expose
+// impulsive content, which is the usual choice of demodulation band for
+// envelope analysis.
+pub fn kurtogram(x: &[f64], frame_lens: &[usize]) -> Result<Vec<KurtogramLevel>, FftError> {
+    frame_lens
+        .iter()
+        .map(|&frame_len| {
+            let hop = (frame_len / 4).max(1);
+            Ok(KurtogramLevel {
+                frame_len,
+                kurtosis: spectral_kurtosis(x, frame_len, hop)?,
+            })
+        })
+        .collect()
+}
+
+// kurtogram_peak returns the largest spectral kurtosis in the kurtogram,
+// ignoring the DC and Nyquist bins, or None if it has no such bins.
+pub fn kurtogram_peak(levels: &[KurtogramLevel]) -> Option<KurtogramPeak> {
+    levels
+        .iter()
+        .flat_map(|level| {
+            let last = level.kurtosis.len().saturating_sub(1);
+            level
+                .kurtosis
+                .iter()
+                .enumerate()
+                .filter(move |(k, _)| *k != 0 && *k != last)
+                .map(move |(bin, &kurtosis)| KurtogramPeak {
+                    frame_len: level.frame_len,
+                    bin,
+                    frequency: bin as f64 / level.frame_len as f64,
+                    kurtosis,
+                })
+        })
+        .max_by(|a, b| a.kurtosis.total_cmp(&b.kurtosis))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+    use std::f64::consts::PI;
+
+    // gaussian draws normally distributed samples with the Box-Muller method
+    fn gaussian(n: usize, seed: u64) -> Vec<f64> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        (0..n)
+            .map(|_| {
+                let u1 = 1_f64 - rng.random::<f64>();
+                let u2 = rng.random::<f64>();
+                (-2_f64 * u1.ln()).sqrt() * (2_f64 * PI * u2).cos()
+            })
+            .collect()
+    }
+
+    // bursts adds short decaying resonances at frequency f (cycles per sample)
+    // every period samples
+    fn with_bursts(mut x: Vec<f64>, f: f64, period: usize) -> Vec<f64> {
+        for start in (0..x.len()).step_by(period) {
+            for i in 0..64.min(x.len() - start) {
+                let decay = (-(i as f64) / 12_f64).exp();
+                x[start + i] += 8_f64 * decay * (2_f64 * PI * f * i as f64).sin();
+            }
+        }
+        x
+    }
+
+    #[test]
+    fn test_spectral_kurtosis_gaussian_noise_is_flat() {
+        let x = gaussian(1 << 15, 5);
+        let sk = spectral_kurtosis(&x, 64, 16).unwrap();
+        assert_eq!(sk.len(), 33);
+        for &v in &sk[1..32] {
+            assert!(v.abs() < 0.5, "kurtosis {}", v);
+        }
+    }
+
+    #[test]
+    fn test_spectral_kurtosis_detects_transients() {
+        let x = with_bursts(gaussian(1 << 15, 6), 0.3, 1500);
+        let sk = spectral_kurtosis(&x, 64, 16).unwrap();
+        let peak = (1..32).max_by(|&a, &b| sk[a].total_cmp(&sk[b])).unwrap();
+        assert!((peak as f64 / 64_f64 - 0.3).abs() < 2_f64 / 64_f64);
+        assert!(sk[peak] > 2.0);
+    }
+
+    #[test]
+    fn test_kurtogram_peak() {
+        let x = with_bursts(gaussian(1 << 15, 7), 0.2, 1500);
+        let levels = kurtogram(&x, &[16, 32, 64, 128, 256]).unwrap();
+        assert_eq!(levels.len(), 5);
+        assert_eq!(levels[2].kurtosis.len(), 33);
+        let peak = kurtogram_peak(&levels).unwrap();
+        assert!((peak.frequency - 0.2).abs() < 0.05, "{:?}", peak);
+        assert!(kurtogram_peak(&[]).is_none());
+    }
+}
@@ -1,7 +1,140 @@
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+#[cfg(feature = "window")]
+pub mod adaptive;
+#[cfg(feature = "std")]
+pub mod analyzer;
+#[cfg(feature = "io")]
+pub mod archive;
+#[cfg(feature = "arrow")]
+pub mod arrow;
+#[cfg(feature = "std")]
+pub mod averaging;
+#[cfg(feature = "std")]
+pub mod axis;
+#[cfg(feature = "std")]
+pub mod backend;
+#[cfg(feature = "window")]
+pub mod bispectrum;
+pub mod butterflies;
+#[cfg(feature = "std")]
+pub mod cancel;
+#[cfg(feature = "io")]
+pub mod cast;
+#[cfg(feature = "std")]
+pub mod cepstrum;
+#[cfg(feature = "std")]
+pub mod cfar;
+#[cfg(feature = "std")]
+pub mod cfo;
+#[cfg(feature = "std")]
+pub mod codelet;
+#[cfg(feature = "window")]
+pub mod contrast;
+#[cfg(feature = "cuda")]
+pub mod cuda;
+#[cfg(feature = "window")]
+pub mod cyclo;
+pub mod dct;
+#[cfg(feature = "std")]
+pub mod delay;
+#[cfg(feature = "std")]
+pub mod demod;
+#[cfg(feature = "window")]
+pub mod denoise;
+#[cfg(feature = "std")]
+pub mod denormal;
 pub mod dft;
+pub mod dht;
+#[cfg(feature = "window")]
+pub mod dominant;
+#[cfg(feature = "std")]
+pub mod doppler;
+#[cfg(feature = "std")]
+pub mod edit;
+#[cfg(feature = "std")]
+pub mod emphasis;
+#[cfg(feature = "window")]
+pub mod envelope;
+#[cfg(feature = "std")]
+pub mod erasure;
 pub mod error;
 pub mod fft;
+pub mod fft2;
+pub mod fftn;
+#[cfg(feature = "fftw")]
+pub mod fftw;
+#[cfg(feature = "window")]
+pub mod filter;
+#[cfg(feature = "window")]
+pub mod filterbank;
+#[cfg(feature = "window")]
+pub mod fingerprint;
+pub mod float;
+#[cfg(feature = "std")]
+pub mod frames;
+pub mod goertzel;
+#[cfg(feature = "std")]
+pub mod hilbert;
+#[cfg(feature = "window")]
+pub mod kurtosis;
+#[cfg(feature = "std")]
+pub mod mask;
+#[cfg(feature = "ndarray")]
+pub mod ndarray;
+#[cfg(feature = "std")]
+pub mod noise;
+#[cfg(feature = "std")]
+pub mod ntt;
+#[cfg(feature = "std")]
+pub mod ofdm;
+#[cfg(feature = "std")]
+pub mod order;
+#[cfg(feature = "rayon")]
+pub mod parallel;
+#[cfg(feature = "std")]
+pub mod parseval;
+#[cfg(feature = "std")]
+pub mod pfa;
+#[cfg(feature = "window")]
+pub mod pipeline;
+#[cfg(feature = "std")]
+pub mod planner;
+#[cfg(feature = "std")]
+pub mod poly;
+#[cfg(feature = "window")]
+pub mod psd;
+#[cfg(feature = "std")]
+pub mod quaternion;
+#[cfg(feature = "std")]
+pub mod regrid;
+#[cfg(feature = "window")]
+pub mod resample;
+pub mod samples;
+#[cfg(feature = "std")]
+pub mod segmented;
+#[cfg(feature = "std")]
+pub mod signal;
+#[cfg(feature = "simd")]
+pub mod simd;
+pub mod sliding;
+pub mod small;
+#[cfg(feature = "std")]
+pub mod spectrum;
+#[cfg(feature = "std")]
+pub mod stft;
+#[cfg(feature = "async")]
+pub mod stream;
+#[cfg(feature = "async")]
+pub mod task;
+pub(crate) mod trace;
 pub mod utils;
-
-use num::complex::{Complex, Complex64};
-use std::f64::consts::PI;
+pub mod validate;
+#[cfg(feature = "accelerate")]
+pub mod vdsp;
+#[cfg(feature = "window")]
+pub mod window;
+#[cfg(feature = "std")]
+pub mod winograd;
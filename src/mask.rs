@@ -0,0 +1,184 @@
+use num::complex::Complex64;
+
+use crate::error::FftError;
+
+// Time-frequency masks have the layout of stft output: one row of bins per
+// frame, with gains usually between 0 and 1.
+
+fn check_shape<T, U>(a: &[Vec<T>], b: &[Vec<U>]) -> Result<(), FftError> {
+    if a.len() != b.len() {
+        return Err(FftError::LengthMismatch(a.len(), b.len()));
+    }
+    for (ra, rb) in a.iter().zip(b) {
+        if ra.len() != rb.len() {
+            return Err(FftError::LengthMismatch(ra.len(), rb.len()));
+        }
+    }
+    Ok(())
+}
+
+// ratio returns p / total, or share where total is zero
+fn ratio(p: f64, total: f64, share: f64) -> f64 {
+    if total > 0_f64 {
+        p / total
+    } else {
+        share
+    }
+}
+
+// ideal_ratio_mask returns (|S|^2 / (|S|^2 + |N|^2))^exponent for the
+// magnitudes of a target S and the interference N, exponent 0.5 being the
+// usual choice for training targets. Bins where both are zero get an equal
+// share.
+pub fn ideal_ratio_mask(
+    target: &[Vec<f64>],
+    interference: &[Vec<f64>],
+    exponent: f64,
+) -> Result<Vec<Vec<f64>>, FftError> {
+    check_shape(target, interference)?;
+    Ok(target
+        .iter()
+        .zip(interference)
+        .map(|(s, n)| {
+            s.iter()
+                .zip(n)
+                .map(|(s, n)| {
+                    let (ps, pn) = (s * s, n * n);
+                    ratio(ps, ps + pn, 0.5).powf(exponent)
+                })
+                .collect()
+        })
+        .collect())
+}
+
+// wiener_masks returns one mask per source, |S_i|^2 / sum_j |S_j|^2, from
+// magnitude estimates of every source, e.g. the outputs of a separation
+// model. The masks of a bin sum to one; bins where every estimate is zero
+// are shared equally.
+pub fn wiener_masks(estimates: &[Vec<Vec<f64>>]) -> Result<Vec<Vec<Vec<f64>>>, FftError> {
+    let Some(first) = estimates.first() else {
+        return Ok(Vec::new());
+    };
+    for estimate in estimates {
+        check_shape(first, estimate)?;
+    }
+    let share = 1_f64 / estimates.len() as f64;
+    let total: Vec<Vec<f64>> = first
+        .iter()
+        .enumerate()
+        .map(|(t, row)| {
+            (0..row.len())
+                .map(|k| estimates.iter().map(|e| e[t][k] * e[t][k]).sum())
+                .collect()
+        })
+        .collect();
+    Ok(estimates
+        .iter()
+        .map(|e| {
+            e.iter()
+                .zip(&total)
+                .map(|(row, total)| {
+                    row.iter()
+                        .zip(total)
+                        .map(|(v, &p)| ratio(v * v, p, share))
+                        .collect()
+                })
+                .collect()
+        })
+        .collect())
+}
+
+// binary_mask turns a soft mask into gains of 0 and 1, keeping the bins at or
+// above threshold
+pub fn binary_mask(mask: &[Vec<f64>], threshold: f64) -> Vec<Vec<f64>> {
+    mask.iter()
+        .map(|row| {
+            row.iter()
+                .map(|&m| if m >= threshold { 1_f64 } else { 0_f64 })
+                .collect()
+        })
+        .collect()
+}
+
+// smooth_mask averages every gain over the bins within time_radius frames
+// and freq_radius bins of it, clipped at the edges, which suppresses the
+// isolated bins that cause musical noise. The rows must have equal length.
+pub fn smooth_mask(
+    mask: &[Vec<f64>],
+    time_radius: usize,
+    freq_radius: usize,
+) -> Result<Vec<Vec<f64>>, FftError> {
+    let bins = mask.first().map_or(0, Vec::len);
+    if let Some(row) = mask.iter().find(|row| row.len() != bins) {
+        return Err(FftError::LengthMismatch(bins, row.len()));
+    }
+    let frames = mask.len();
+    Ok((0..frames)
+        .map(|t| {
+            let rows = t.saturating_sub(time_radius)..(t + time_radius + 1).min(frames);
+            (0..bins)
+                .map(|k| {
+                    let cols = k.saturating_sub(freq_radius)..(k + freq_radius + 1).min(bins);
+                    let count = (rows.len() * cols.len()) as f64;
+                    let sum: f64 = mask[rows.clone()]
+                        .iter()
+                        .map(|row| row[cols.clone()].iter().sum::<f64>())
+                        .sum();
+                    sum / count
+                })
+                .collect()
+        })
+        .collect())
+}
+
+// apply_mask multiplies every bin of the short-time spectra by its gain
+pub fn apply_mask(spectra: &mut [Vec<Complex64>], mask: &[Vec<f64>]) -> Result<(), FftError> {
+    check_shape(spectra, mask)?;
+    for (row, gains) in spectra.iter_mut().zip(mask) {
+        for (bin, g) in row.iter_mut().zip(gains) {
+            *bin *= g;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ratio_and_wiener_masks() {
+        let target = vec![vec![3_f64, 0_f64, 1_f64]];
+        let noise = vec![vec![4_f64, 0_f64, 0_f64]];
+        let irm = ideal_ratio_mask(&target, &noise, 1_f64).unwrap();
+        assert_eq!(irm, vec![vec![9_f64 / 25_f64, 0.5, 1_f64]]);
+
+        let masks = wiener_masks(&[target.clone(), noise.clone()]).unwrap();
+        assert_eq!(masks[0], irm);
+        for (a, b) in masks[0][0].iter().zip(&masks[1][0]) {
+            assert!((a + b - 1_f64).abs() < 1e-12);
+        }
+        assert_eq!(binary_mask(&irm, 0.5), vec![vec![0_f64, 1_f64, 1_f64]]);
+
+        let mut spectra = vec![vec![Complex64::new(2_f64, 2_f64); 3]];
+        apply_mask(&mut spectra, &binary_mask(&irm, 0.5)).unwrap();
+        assert_eq!(spectra[0][0], Complex64::default());
+        assert_eq!(spectra[0][2], Complex64::new(2_f64, 2_f64));
+        assert!(matches!(
+            ideal_ratio_mask(&target, &[vec![1_f64]], 1_f64),
+            Err(FftError::LengthMismatch(3, 1))
+        ));
+    }
+
+    #[test]
+    fn test_smooth_mask() {
+        let mut mask = vec![vec![0_f64; 3]; 3];
+        mask[1][1] = 9_f64;
+        let smooth = smooth_mask(&mask, 1, 1).unwrap();
+        assert_eq!(smooth[1][1], 1_f64);
+        // The corner averages over a 2 x 2 neighbourhood.
+        assert_eq!(smooth[0][0], 2.25);
+        assert_eq!(smooth_mask(&mask, 0, 0).unwrap(), mask);
+        assert!(smooth_mask(&[vec![1_f64], vec![]], 1, 1).is_err());
+    }
+}
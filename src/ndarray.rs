@@ -0,0 +1,153 @@
+use ndarray::{Array, ArrayBase, Axis, Data, DataMut, Dimension};
+use num::complex::Complex;
+
+use crate::error::FftError;
+use crate::fft::fft_in_place;
+use crate::float::{from_usize, FftNum};
+
+// Transforms along one axis of ndarray arrays of any dimension and memory
+// layout, views and transposes included: every lane along axis, e.g. each
+// column of a matrix for Axis(0), is transformed on its own. The results
+// match fftn::fft_axis and fftn::ifft_axis on the flattened data.
+
+fn check_axis(ndim: usize, axis: Axis) -> Result<(), FftError> {
+    if axis.index() >= ndim {
+        return Err(FftError::InvalidParameter(
+            "axis out of range for the array",
+        ));
+    }
+    Ok(())
+}
+
+// fft_axis transforms a real array along axis, returning an array of the
+// same shape with complex elements
+pub fn fft_axis<T, S, D>(
+    arr: &ArrayBase<S, D>,
+    axis: Axis,
+) -> Result<Array<Complex<T>, D>, FftError>
+where
+    T: FftNum,
+    S: Data<Elem = T>,
+    D: Dimension,
+{
+    let mut out = arr.mapv(|v| Complex::new(v, T::zero()));
+    fft_axis_mut(&mut out, axis)?;
+    Ok(out)
+}
+
+// fft_axis_complex transforms a complex array along axis
+pub fn fft_axis_complex<T, S, D>(
+    arr: &ArrayBase<S, D>,
+    axis: Axis,
+) -> Result<Array<Complex<T>, D>, FftError>
+where
+    T: FftNum,
+    S: Data<Elem = Complex<T>>,
+    D: Dimension,
+{
+    let mut out = arr.to_owned();
+    fft_axis_mut(&mut out, axis)?;
+    Ok(out)
+}
+
+// fft_axis_mut transforms a complex array, e.g. an ArrayViewMut, in place
+// along axis
+pub fn fft_axis_mut<T, S, D>(arr: &mut ArrayBase<S, D>, axis: Axis) -> Result<(), FftError>
+where
+    T: FftNum,
+    S: DataMut<Elem = Complex<T>>,
+    D: Dimension,
+{
+    check_axis(arr.ndim(), axis)?;
+    let mut line = Vec::with_capacity(arr.len_of(axis));
+    for mut lane in arr.lanes_mut(axis) {
+        line.clear();
+        line.extend(lane.iter().copied());
+        fft_in_place(&mut line)?;
+        for (dst, &v) in lane.iter_mut().zip(&line) {
+            *dst = v;
+        }
+    }
+    Ok(())
+}
+
+// ifft_axis inverts fft_axis, keeping the full complex result
+pub fn ifft_axis<T, S, D>(
+    arr: &ArrayBase<S, D>,
+    axis: Axis,
+) -> Result<Array<Complex<T>, D>, FftError>
+where
+    T: FftNum,
+    S: Data<Elem = Complex<T>>,
+    D: Dimension,
+{
+    let mut out = arr.to_owned();
+    ifft_axis_mut(&mut out, axis)?;
+    Ok(out)
+}
+
+// ifft_axis_mut inverts fft_axis_mut in place, dividing by the length of
+// axis
+pub fn ifft_axis_mut<T, S, D>(arr: &mut ArrayBase<S, D>, axis: Axis) -> Result<(), FftError>
+where
+    T: FftNum,
+    S: DataMut<Elem = Complex<T>>,
+    D: Dimension,
+{
+    check_axis(arr.ndim(), axis)?;
+    // conjugate, transform forward, conjugate again and divide
+    arr.mapv_inplace(|c| c.conj());
+    fft_axis_mut(arr, axis)?;
+    let divisor = from_usize::<T>(arr.len_of(axis).max(1));
+    arr.mapv_inplace(|c| c.conj() / divisor);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fftn;
+    use ndarray::{Array2, Array3};
+    use num::complex::Complex64;
+
+    fn close(a: &[Complex64], b: &[Complex64]) -> bool {
+        a.len() == b.len() && a.iter().zip(b).all(|(a, b)| (a - b).norm() < 1e-9)
+    }
+
+    #[test]
+    fn test_fft_axis_matches_slices() {
+        let m = Array2::from_shape_fn((4, 6), |(r, c)| ((r * 6 + c) * 37 % 11) as f64);
+        let flat = m.as_slice().unwrap();
+        for axis in 0..2 {
+            let spectrum = fft_axis(&m, Axis(axis)).unwrap();
+            assert_eq!(spectrum.shape(), m.shape());
+            let expected = fftn::fft_axis(flat, &[4, 6], axis).unwrap();
+            assert!(close(spectrum.as_slice().unwrap(), &expected));
+            let back = ifft_axis(&spectrum, Axis(axis)).unwrap();
+            assert!(back.iter().zip(&m).all(|(a, b)| (a.re - b).abs() < 1e-9));
+        }
+        assert!(matches!(
+            fft_axis(&m, Axis(2)),
+            Err(FftError::InvalidParameter(_))
+        ));
+    }
+
+    #[test]
+    fn test_fft_axis_on_views() {
+        // A transposed view is not in standard layout; transforming its rows
+        // is transforming the columns of the original.
+        let a = Array3::from_shape_fn((2, 3, 5), |(i, j, k)| {
+            Complex64::new((i * 15 + j * 5 + k) as f64, (j * k) as f64)
+        });
+        let rows_of_transpose = fft_axis_complex(&a.view().reversed_axes(), Axis(2)).unwrap();
+        let columns = fft_axis_complex(&a, Axis(0)).unwrap();
+        assert_eq!(rows_of_transpose, columns.reversed_axes());
+
+        let mut b = a.clone();
+        fft_axis_mut(&mut b.view_mut(), Axis(1)).unwrap();
+        let expected = fftn::fftn_axes(a.as_slice().unwrap(), &[2, 3, 5], &[1]).unwrap();
+        assert!(close(b.as_slice().unwrap(), &expected));
+        ifft_axis_mut(&mut b.view_mut(), Axis(1)).unwrap();
+        assert!(close(b.as_slice().unwrap(), a.as_slice().unwrap()));
+    }
+}
@@ -0,0 +1,143 @@
+use crate::error::FftError;
+
+// NoiseFloor holds the parameters of the running-percentile noise floor
+// estimator.
+//
+// For every bin, the percentile-th quantile (0.5 for the median) of the power
+// in the bins within half_width of it is taken; narrow peaks occupy few bins
+// of that neighbourhood and barely move it. The resulting curve is then
+// averaged over bins within smooth of each bin.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NoiseFloor {
+    pub half_width: usize,
+    pub percentile: f64,
+    pub smooth: usize,
+}
+
+impl Default for NoiseFloor {
+    fn default() -> Self {
+        NoiseFloor {
+            half_width: 16,
+            percentile: 0.5,
+            smooth: 4,
+        }
+    }
+}
+
+// quantile returns the q-th quantile of sorted values, interpolating linearly
+// between neighbouring ranks
+fn quantile(sorted: &[f64], q: f64) -> f64 {
+    let pos = q * (sorted.len() - 1) as f64;
+    let lo = pos.floor() as usize;
+    let hi = pos.ceil() as usize;
+    sorted[lo] + (pos - lo as f64) * (sorted[hi] - sorted[lo])
+}
+
+// noise_floor returns a per-bin estimate of the noise floor of a power
+// spectrum, with the neighbourhoods clipped at both ends of the spectrum
+pub fn noise_floor(power: &[f64], params: &NoiseFloor) -> Result<Vec<f64>, FftError> {
+    if !(0_f64..=1_f64).contains(&params.percentile) {
+        return Err(FftError::InvalidParameter("percentile must lie in [0, 1]"));
+    }
+    let n = power.len();
+    if n == 0 {
+        return Ok(Vec::new());
+    }
+    let mut sorted = Vec::with_capacity(2 * params.half_width + 1);
+    let raw: Vec<f64> = (0..n)
+        .map(|k| {
+            let (lo, hi) = (
+                k.saturating_sub(params.half_width),
+                (k + params.half_width).min(n - 1),
+            );
+            sorted.clear();
+            sorted.extend_from_slice(&power[lo..=hi]);
+            sorted.sort_by(f64::total_cmp);
+            quantile(&sorted, params.percentile)
+        })
+        .collect();
+    Ok((0..n)
+        .map(|k| {
+            let (lo, hi) = (
+                k.saturating_sub(params.smooth),
+                (k + params.smooth).min(n - 1),
+            );
+            raw[lo..=hi].iter().sum::<f64>() / (hi - lo + 1) as f64
+        })
+        .collect())
+}
+
+// snr_db returns the ratio in dB between every bin of a power spectrum and
+// its noise floor; bins with a zero floor report infinity
+pub fn snr_db(power: &[f64], floor: &[f64]) -> Result<Vec<f64>, FftError> {
+    if power.len() != floor.len() {
+        return Err(FftError::LengthMismatch(power.len(), floor.len()));
+    }
+    Ok(power
+        .iter()
+        .zip(floor.iter())
+        .map(|(p, f)| 10_f64 * (p / f).log10())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    #[test]
+    fn test_noise_floor_ignores_peaks() {
+        let mut rng = StdRng::seed_from_u64(5);
+        // Exponentially distributed noise power of mean 2, median 2 ln 2.
+        let mut power: Vec<f64> = (0..1024)
+            .map(|_| -2_f64 * (1_f64 - rng.random::<f64>()).ln())
+            .collect();
+        power[300] = 1e4;
+        power[700] = 1e3;
+        let floor = noise_floor(&power, &NoiseFloor::default()).unwrap();
+        assert_eq!(floor.len(), 1024);
+        for k in [50, 300, 700, 1000] {
+            assert!(
+                (floor[k] - 2_f64 * 2_f64.ln()).abs() < 0.5,
+                "bin {}: {}",
+                k,
+                floor[k]
+            );
+        }
+        let snr = snr_db(&power, &floor).unwrap();
+        assert!(snr[300] > 35.0);
+    }
+
+    #[test]
+    fn test_noise_floor_follows_slope() {
+        let power: Vec<f64> = (0..256).map(|k| 1_f64 + k as f64).collect();
+        let params = NoiseFloor {
+            half_width: 4,
+            percentile: 0.5,
+            smooth: 0,
+        };
+        let floor = noise_floor(&power, &params).unwrap();
+        assert_eq!(&floor[4..252], &power[4..252]);
+        let low = NoiseFloor {
+            percentile: 0.0,
+            ..params
+        };
+        assert_eq!(noise_floor(&power, &low).unwrap()[100], 97.0);
+    }
+
+    #[test]
+    fn test_noise_floor_invalid_params() {
+        let params = NoiseFloor {
+            percentile: 1.5,
+            ..NoiseFloor::default()
+        };
+        assert!(matches!(
+            noise_floor(&[1.0; 8], &params),
+            Err(FftError::InvalidParameter(_))
+        ));
+        assert!(matches!(
+            snr_db(&[1.0; 8], &[1.0; 4]),
+            Err(FftError::LengthMismatch(8, 4))
+        ));
+    }
+}
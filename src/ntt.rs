@@ -0,0 +1,159 @@
+use crate::error::FftError;
+
+// Number-theoretic transform: the analogue of the FFT over the prime field
+// GF(p) with p = 119 * 2^23 + 1, which has roots of unity of every power of
+// two order up to 2^23. Products of polynomials with integer coefficients
+// below p come out exact, with no rounding error.
+pub const MODULUS: u64 = 998_244_353;
+
+// GENERATOR is a primitive root modulo MODULUS.
+pub const GENERATOR: u64 = 3;
+
+// MAX_LEN is the longest transform the field supports.
+pub const MAX_LEN: usize = 1 << 23;
+
+// mod_mul returns a * b modulo MODULUS for any a and b
+pub fn mod_mul(a: u64, b: u64) -> u64 {
+    (a as u128 * b as u128 % MODULUS as u128) as u64
+}
+
+// mod_pow returns base^exp modulo MODULUS
+pub fn mod_pow(base: u64, mut exp: u64) -> u64 {
+    let mut base = base % MODULUS;
+    let mut acc = 1_u64;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            acc = mod_mul(acc, base);
+        }
+        base = mod_mul(base, base);
+        exp >>= 1;
+    }
+    acc
+}
+
+// mod_inv returns the inverse of a non-zero a modulo MODULUS
+pub fn mod_inv(a: u64) -> u64 {
+    mod_pow(a, MODULUS - 2)
+}
+
+// root_of_unity returns a primitive root of unity of order n, a power of two
+// up to MAX_LEN
+pub fn root_of_unity(n: usize) -> u64 {
+    mod_pow(GENERATOR, (MODULUS - 1) / n as u64)
+}
+
+fn check_len(n: usize) -> Result<(), FftError> {
+    if !n.is_power_of_two() {
+        return Err(FftError::NotAPowerOfTwo(n));
+    }
+    if n > MAX_LEN {
+        return Err(FftError::SizeTooLarge(n));
+    }
+    Ok(())
+}
+
+fn reduce(a: &mut [u64]) {
+    a.iter_mut().for_each(|v| *v %= MODULUS);
+}
+
+// transform runs an in-place iterative radix-2 transform with root w of
+// order a.len()
+fn transform(a: &mut [u64], w: u64) {
+    let n = a.len();
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            a.swap(i, j);
+        }
+    }
+    let mut len = 2;
+    while len <= n {
+        let step = mod_pow(w, (n / len) as u64);
+        for start in (0..n).step_by(len) {
+            let mut twiddle = 1_u64;
+            for k in 0..len / 2 {
+                let u = a[start + k];
+                let v = mod_mul(a[start + k + len / 2], twiddle);
+                a[start + k] = (u + v) % MODULUS;
+                a[start + k + len / 2] = (u + MODULUS - v) % MODULUS;
+                twiddle = mod_mul(twiddle, step);
+            }
+        }
+        len <<= 1;
+    }
+}
+
+// ntt transforms a in place: a[k] becomes sum a[j] w^(j k) with w =
+// root_of_unity(a.len()), i.e. the values of the polynomial with
+// coefficients a at the powers of w. Inputs are first reduced modulo
+// MODULUS.
+pub fn ntt(a: &mut [u64]) -> Result<(), FftError> {
+    check_len(a.len())?;
+    reduce(a);
+    transform(a, root_of_unity(a.len()));
+    Ok(())
+}
+
+// intt inverts ntt in place
+pub fn intt(a: &mut [u64]) -> Result<(), FftError> {
+    check_len(a.len())?;
+    reduce(a);
+    transform(a, mod_inv(root_of_unity(a.len())));
+    let scale = mod_inv(a.len() as u64);
+    a.iter_mut().for_each(|v| *v = mod_mul(*v, scale));
+    Ok(())
+}
+
+// ntt_convolve returns the exact linear convolution of a and b modulo
+// MODULUS; coefficients of any size are reduced first
+pub fn ntt_convolve(a: &[u64], b: &[u64]) -> Result<Vec<u64>, FftError> {
+    if a.is_empty() || b.is_empty() {
+        return Ok(Vec::new());
+    }
+    let len = a.len() + b.len() - 1;
+    let n = len.next_power_of_two();
+    let mut fa = vec![0_u64; n];
+    let mut fb = vec![0_u64; n];
+    fa[..a.len()].copy_from_slice(a);
+    fb[..b.len()].copy_from_slice(b);
+    ntt(&mut fa)?;
+    ntt(&mut fb)?;
+    for (x, y) in fa.iter_mut().zip(&fb) {
+        *x = mod_mul(*x, *y);
+    }
+    intt(&mut fa)?;
+    fa.truncate(len);
+    Ok(fa)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ntt_roundtrip_and_convolution() {
+        let a: Vec<u64> = (0..64).map(|i| (i * i * 7919 + 13) % MODULUS).collect();
+        let mut b = a.clone();
+        ntt(&mut b).unwrap();
+        intt(&mut b).unwrap();
+        assert_eq!(a, b);
+
+        // (1 + 2x + 3x^2)(4 + 5x) = 4 + 13x + 22x^2 + 15x^3
+        assert_eq!(ntt_convolve(&[1, 2, 3], &[4, 5]).unwrap(), [4, 13, 22, 15]);
+        let big = u64::MAX % MODULUS;
+        assert_eq!(
+            ntt_convolve(&[u64::MAX], &[2]).unwrap(),
+            [big * 2 % MODULUS]
+        );
+        assert!(matches!(
+            ntt(&mut [0_u64; 12]),
+            Err(FftError::NotAPowerOfTwo(12))
+        ));
+    }
+}
@@ -0,0 +1,161 @@
+use num::complex::Complex64;
+
+use crate::error::FftError;
+use crate::fft::{fft_complex, ifft_complex};
+
+// Ofdm describes an OFDM symbol layout: the FFT size, the cyclic prefix
+// length in samples, and the FFT bins that carry data (in the order in which
+// data symbols are mapped onto them). Bins not listed are left empty, which
+// is how DC and guard bands are expressed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ofdm {
+    pub fft_len: usize,
+    pub cp_len: usize,
+    pub subcarriers: Vec<usize>,
+}
+
+impl Ofdm {
+    // new returns a layout that carries data on every bin
+    pub fn new(fft_len: usize, cp_len: usize) -> Self {
+        Ofdm {
+            fft_len,
+            cp_len,
+            subcarriers: (0..fft_len).collect(),
+        }
+    }
+
+    // symbol_len is the number of time-domain samples per OFDM symbol,
+    // including the cyclic prefix
+    pub fn symbol_len(&self) -> usize {
+        self.fft_len + self.cp_len
+    }
+
+    fn validate(&self) -> Result<(), FftError> {
+        if self.cp_len > self.fft_len {
+            return Err(FftError::InvalidParameter(
+                "cyclic prefix must not be longer than the FFT",
+            ));
+        }
+        if self.subcarriers.is_empty() {
+            return Err(FftError::InvalidParameter("no data subcarriers"));
+        }
+        if self.subcarriers.iter().any(|&k| k >= self.fft_len) {
+            return Err(FftError::InvalidParameter(
+                "subcarrier index outside the FFT",
+            ));
+        }
+        Ok(())
+    }
+}
+
+// ofdm_modulate maps data symbols onto the subcarriers of consecutive OFDM
+// symbols, transforms each with an IFFT and prepends the cyclic prefix. The
+// number of data symbols must be a multiple of the number of subcarriers.
+pub fn ofdm_modulate(symbols: &[Complex64], ofdm: &Ofdm) -> Result<Vec<Complex64>, FftError> {
+    ofdm.validate()?;
+    let per_symbol = ofdm.subcarriers.len();
+    if !symbols.len().is_multiple_of(per_symbol) {
+        return Err(FftError::LengthMismatch(
+            symbols.len().div_ceil(per_symbol) * per_symbol,
+            symbols.len(),
+        ));
+    }
+
+    let mut out = Vec::with_capacity(symbols.len() / per_symbol * ofdm.symbol_len());
+    for chunk in symbols.chunks(per_symbol) {
+        let mut bins = vec![Complex64::default(); ofdm.fft_len];
+        for (&k, &s) in ofdm.subcarriers.iter().zip(chunk.iter()) {
+            bins[k] = s;
+        }
+        let time = ifft_complex(&bins)?;
+        out.extend_from_slice(&time[ofdm.fft_len - ofdm.cp_len..]);
+        out.extend_from_slice(&time);
+    }
+    Ok(out)
+}
+
+// ofdm_demodulate strips the cyclic prefix from every OFDM symbol, transforms
+// it with an FFT and collects the data subcarriers. The input must contain a
+// whole number of OFDM symbols and start on a symbol boundary.
+pub fn ofdm_demodulate(samples: &[Complex64], ofdm: &Ofdm) -> Result<Vec<Complex64>, FftError> {
+    ofdm.validate()?;
+    let symbol_len = ofdm.symbol_len();
+    if !samples.len().is_multiple_of(symbol_len) {
+        return Err(FftError::LengthMismatch(
+            samples.len().div_ceil(symbol_len) * symbol_len,
+            samples.len(),
+        ));
+    }
+
+    let mut out = Vec::with_capacity(samples.len() / symbol_len * ofdm.subcarriers.len());
+    for symbol in samples.chunks(symbol_len) {
+        let bins = fft_complex(&symbol[ofdm.cp_len..])?;
+        out.extend(ofdm.subcarriers.iter().map(|&k| bins[k]));
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn qpsk(n: usize) -> Vec<Complex64> {
+        (0..n)
+            .map(|i| {
+                let re = if i % 2 == 0 { 1.0 } else { -1.0 };
+                let im = if (i / 2) % 2 == 0 { 1.0 } else { -1.0 };
+                Complex64::new(re, im)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_ofdm_round_trip() {
+        let ofdm = Ofdm {
+            fft_len: 64,
+            cp_len: 16,
+            // Leave DC and the band edges empty.
+            subcarriers: (1..27).chain(38..64).collect(),
+        };
+        let symbols = qpsk(ofdm.subcarriers.len() * 3);
+        let tx = ofdm_modulate(&symbols, &ofdm).unwrap();
+        assert_eq!(tx.len(), 3 * ofdm.symbol_len());
+
+        let rx = ofdm_demodulate(&tx, &ofdm).unwrap();
+        assert_eq!(rx.len(), symbols.len());
+        for (a, b) in rx.iter().zip(symbols.iter()) {
+            assert!((a - b).norm() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_ofdm_cyclic_prefix() {
+        let ofdm = Ofdm::new(16, 4);
+        let tx = ofdm_modulate(&qpsk(16), &ofdm).unwrap();
+        // The prefix repeats the tail of the symbol.
+        for i in 0..4 {
+            assert!((tx[i] - tx[16 + i]).norm() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_ofdm_invalid_input() {
+        let ofdm = Ofdm::new(16, 4);
+        assert!(matches!(
+            ofdm_modulate(&qpsk(10), &ofdm),
+            Err(FftError::LengthMismatch(16, 10))
+        ));
+        assert!(matches!(
+            ofdm_demodulate(&qpsk(21), &ofdm),
+            Err(FftError::LengthMismatch(40, 21))
+        ));
+        let bad = Ofdm {
+            subcarriers: vec![16],
+            ..Ofdm::new(16, 4)
+        };
+        assert!(matches!(
+            ofdm_modulate(&qpsk(1), &bad),
+            Err(FftError::InvalidParameter(_))
+        ));
+    }
+}
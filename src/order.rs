@@ -0,0 +1,200 @@
+use crate::error::FftError;
+use crate::fft::fft;
+
+// OrderSpectrum is the one-sided amplitude spectrum of an angularly resampled
+// signal, indexed by shaft order (cycles per revolution) instead of Hz.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrderSpectrum {
+    pub orders: Vec<f64>,
+    pub amplitudes: Vec<f64>,
+}
+
+// speed_from_tachometer turns tachometer pulse positions (sample indices, in
+// increasing order) into a per-sample shaft speed profile in rpm. The speed
+// between two pulses is 60 / (pulses_per_rev * interval) and is linearly
+// interpolated between pulse midpoints; before the first and after the last
+// midpoint it is held constant.
+pub fn speed_from_tachometer(
+    pulses: &[usize],
+    pulses_per_rev: usize,
+    sample_rate: f64,
+    len: usize,
+) -> Result<Vec<f64>, FftError> {
+    if pulses.len() < 2 {
+        return Err(FftError::InvalidParameter(
+            "at least two tachometer pulses are required",
+        ));
+    }
+    if pulses.windows(2).any(|w| w[1] <= w[0]) {
+        return Err(FftError::InvalidParameter(
+            "tachometer pulses must be strictly increasing",
+        ));
+    }
+    if pulses_per_rev == 0 || sample_rate <= 0_f64 {
+        return Err(FftError::InvalidParameter(
+            "pulses per revolution and sample rate must be positive",
+        ));
+    }
+
+    let points: Vec<(f64, f64)> = pulses
+        .windows(2)
+        .map(|w| {
+            let interval = (w[1] - w[0]) as f64 / sample_rate;
+            let mid = (w[0] + w[1]) as f64 / 2_f64;
+            (mid, 60_f64 / (pulses_per_rev as f64 * interval))
+        })
+        .collect();
+
+    let mut j = 0;
+    Ok((0..len)
+        .map(|i| {
+            let t = i as f64;
+            while j + 1 < points.len() && points[j + 1].0 <= t {
+                j += 1;
+            }
+            let (t0, r0) = points[j];
+            match points.get(j + 1) {
+                Some(&(t1, r1)) if t > t0 => r0 + (r1 - r0) * (t - t0) / (t1 - t0),
+                _ => r0,
+            }
+        })
+        .collect())
+}
+
+// angular_resample resamples x, taken at sample_rate with a per-sample shaft
+// speed profile rpm, to samples_per_rev samples per shaft revolution. The
+// shaft angle is obtained by trapezoidal integration of the speed and x is
+// linearly interpolated at the instants of constant angle increment, so
+// components locked to the shaft become stationary regardless of speed
+// changes.
+pub fn angular_resample(
+    x: &[f64],
+    sample_rate: f64,
+    rpm: &[f64],
+    samples_per_rev: usize,
+) -> Result<Vec<f64>, FftError> {
+    if x.len() != rpm.len() {
+        return Err(FftError::LengthMismatch(x.len(), rpm.len()));
+    }
+    if sample_rate <= 0_f64 || samples_per_rev == 0 {
+        return Err(FftError::InvalidParameter(
+            "sample rate and samples per revolution must be positive",
+        ));
+    }
+    if rpm.iter().any(|&r| r <= 0_f64) {
+        return Err(FftError::InvalidParameter("shaft speed must be positive"));
+    }
+    if x.len() < 2 {
+        return Ok(x.to_vec());
+    }
+
+    // Shaft angle in revolutions at every input sample.
+    let mut angle = vec![0_f64; x.len()];
+    for i in 1..x.len() {
+        angle[i] = angle[i - 1] + (rpm[i - 1] + rpm[i]) / 2_f64 / 60_f64 / sample_rate;
+    }
+
+    let step = 1_f64 / samples_per_rev as f64;
+    let total = (angle[x.len() - 1] / step).floor() as usize + 1;
+    let mut i = 0;
+    Ok((0..total)
+        .map(|j| {
+            let target = j as f64 * step;
+            while i + 2 < x.len() && angle[i + 1] < target {
+                i += 1;
+            }
+            let frac = (target - angle[i]) / (angle[i + 1] - angle[i]);
+            x[i] + (x[i + 1] - x[i]) * frac.clamp(0_f64, 1_f64)
+        })
+        .collect())
+}
+
+// order_spectrum computes the order spectrum of x: x is angularly resampled to
+// samples_per_rev samples per revolution, truncated to the largest power of
+// two number of samples and transformed. The order resolution is
+// samples_per_rev / n and the highest order is samples_per_rev / 2.
+pub fn order_spectrum(
+    x: &[f64],
+    sample_rate: f64,
+    rpm: &[f64],
+    samples_per_rev: usize,
+) -> Result<OrderSpectrum, FftError> {
+    let resampled = angular_resample(x, sample_rate, rpm, samples_per_rev)?;
+    if resampled.is_empty() {
+        return Err(FftError::NotAPowerOfTwo(0));
+    }
+    let n = 1 << resampled.len().ilog2();
+    let spectrum = fft(&resampled[..n])?;
+    let half = n / 2 + 1;
+    let amplitudes = spectrum[..half]
+        .iter()
+        .enumerate()
+        .map(|(k, c)| {
+            let scale = if k == 0 || 2 * k == n { 1_f64 } else { 2_f64 };
+            scale * c.norm() / n as f64
+        })
+        .collect();
+    let orders = (0..half)
+        .map(|k| k as f64 * samples_per_rev as f64 / n as f64)
+        .collect();
+    Ok(OrderSpectrum { orders, amplitudes })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::PI;
+
+    // run_up returns a speed profile ramping from 600 to 1800 rpm and a signal
+    // with a third-order component locked to the shaft
+    fn run_up(fs: f64, len: usize) -> (Vec<f64>, Vec<f64>) {
+        let rpm: Vec<f64> = (0..len)
+            .map(|i| 600_f64 + 1200_f64 * i as f64 / len as f64)
+            .collect();
+        let mut angle = 0_f64;
+        let mut x = Vec::with_capacity(len);
+        for i in 0..len {
+            if i > 0 {
+                angle += (rpm[i - 1] + rpm[i]) / 2_f64 / 60_f64 / fs;
+            }
+            x.push((2_f64 * PI * 3_f64 * angle).sin());
+        }
+        (rpm, x)
+    }
+
+    #[test]
+    fn test_order_spectrum_of_run_up() {
+        let fs = 4096_f64;
+        let (rpm, x) = run_up(fs, 8 * 4096);
+        let spectrum = order_spectrum(&x, fs, &rpm, 32).unwrap();
+        let peak = (1..spectrum.amplitudes.len())
+            .max_by(|&a, &b| spectrum.amplitudes[a].total_cmp(&spectrum.amplitudes[b]))
+            .unwrap();
+        assert!((spectrum.orders[peak] - 3.0).abs() < 0.05);
+        assert!(spectrum.amplitudes[peak] > 0.8);
+    }
+
+    #[test]
+    fn test_angular_resample_constant_speed() {
+        // At 60 rpm and 8 Hz, one revolution is exactly 8 input samples.
+        let x: Vec<f64> = (0..33).map(|i| i as f64).collect();
+        let rpm = vec![60_f64; 33];
+        let y = angular_resample(&x, 8.0, &rpm, 4).unwrap();
+        assert_eq!(y.len(), 17);
+        for (j, v) in y.iter().enumerate() {
+            assert!((v - 2.0 * j as f64).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_speed_from_tachometer() {
+        // One pulse per revolution every 100 samples at 1 kHz is 600 rpm.
+        let pulses: Vec<usize> = (0..10).map(|i| i * 100).collect();
+        let rpm = speed_from_tachometer(&pulses, 1, 1000.0, 1000).unwrap();
+        assert!(rpm.iter().all(|r| (r - 600.0).abs() < 1e-9));
+        assert!(matches!(
+            speed_from_tachometer(&[5], 1, 1000.0, 10),
+            Err(FftError::InvalidParameter(_))
+        ));
+    }
+}
@@ -0,0 +1,129 @@
+use num::complex::Complex;
+use rayon::prelude::*;
+
+use crate::error::FftError;
+use crate::fft::fft_in_place;
+use crate::float::{twiddle, FftNum};
+use crate::validate::default_validation;
+
+// Transforms shorter than PARALLEL_MIN run on the calling thread; below it
+// handing work to other threads costs more than the transform.
+pub const PARALLEL_MIN: usize = 1 << 16;
+
+// fft_parallel computes the same transform as fft_complex on the threads of
+// the current rayon pool, the global one unless called inside
+// ThreadPool::install. Power-of-two inputs of at least PARALLEL_MIN samples
+// are split into their even and odd samples until there is one sub-transform
+// per thread; the sub-transforms run as rayon tasks and the butterflies
+// combining them are shared out in blocks. Other inputs are transformed
+// serially.
+pub fn fft_parallel<T: FftNum>(x: &[Complex<T>]) -> Result<Vec<Complex<T>>, FftError> {
+    fft_parallel_with(x, rayon::current_num_threads())
+}
+
+// fft_parallel_with is fft_parallel splitting the work for at most threads
+// threads
+pub fn fft_parallel_with<T: FftNum>(
+    x: &[Complex<T>],
+    threads: usize,
+) -> Result<Vec<Complex<T>>, FftError> {
+    if threads == 0 {
+        return Err(FftError::InvalidParameter("threads must be positive"));
+    }
+    let x = default_validation().check(x)?;
+    let n = x.len();
+    if threads == 1 || n < PARALLEL_MIN || !n.is_power_of_two() {
+        let mut buffer = x.into_owned();
+        fft_in_place(&mut buffer)?;
+        return Ok(buffer);
+    }
+    // A power of two of at most threads sub-transforms, each of at least
+    // PARALLEL_MIN / 2 samples.
+    let mut depth = 0;
+    while 2 << depth <= threads && n >> (depth + 1) >= PARALLEL_MIN / 2 {
+        depth += 1;
+    }
+    let mut out = vec![Complex::default(); n];
+    split(&x, &mut out, depth, threads)?;
+    Ok(out)
+}
+
+// split writes the transform of x to out, halving it depth more times
+fn split<T: FftNum>(
+    x: &[Complex<T>],
+    out: &mut [Complex<T>],
+    depth: u32,
+    threads: usize,
+) -> Result<(), FftError> {
+    if depth == 0 {
+        out.copy_from_slice(x);
+        return fft_in_place(out);
+    }
+    let half = x.len() / 2;
+    let even: Vec<Complex<T>> = x.iter().step_by(2).copied().collect();
+    let odd: Vec<Complex<T>> = x.iter().skip(1).step_by(2).copied().collect();
+    let (lo, hi) = out.split_at_mut(half);
+    let (even_result, odd_result) = rayon::join(
+        || split(&even, lo, depth - 1, threads / 2),
+        || split(&odd, hi, depth - 1, threads / 2),
+    );
+    even_result?;
+    odd_result?;
+    combine(lo, hi, threads);
+    Ok(())
+}
+
+// combine turns the transforms of the even and odd samples in lo and hi into
+// the two halves of the full transform, one block of butterflies per thread
+fn combine<T: FftNum>(lo: &mut [Complex<T>], hi: &mut [Complex<T>], threads: usize) {
+    let half = lo.len();
+    let n = 2 * half;
+    let block = half.div_ceil(threads.max(1));
+    lo.par_chunks_mut(block)
+        .zip(hi.par_chunks_mut(block))
+        .enumerate()
+        .for_each(|(b, (lo, hi))| {
+            for (j, (e, o)) in lo.iter_mut().zip(hi.iter_mut()).enumerate() {
+                let t = *o * twiddle::<T>(b * block + j, n);
+                *o = *e - t;
+                *e += t;
+            }
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fft::fft_complex;
+    use num::complex::Complex64;
+
+    #[test]
+    fn test_fft_parallel_matches_serial() {
+        let n = 1 << 18;
+        let x: Vec<Complex64> = (0..n)
+            .map(|i| Complex64::new(((i * 7919) % 1013) as f64, ((i * 31) % 17) as f64))
+            .collect();
+        let expected = fft_complex(&x).unwrap();
+        for threads in [2, 3, 8] {
+            let y = fft_parallel_with(&x, threads).unwrap();
+            let err = y
+                .iter()
+                .zip(&expected)
+                .fold(0_f64, |m, (a, b)| m.max((a - b).norm()));
+            assert!(err < 1e-6 * n as f64, "{} threads: {}", threads, err);
+        }
+        // Inside a pool, the transform is split for that pool's threads.
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(4)
+            .build()
+            .unwrap();
+        assert_eq!(
+            pool.install(|| fft_parallel(&x)).unwrap(),
+            fft_parallel_with(&x, 4).unwrap()
+        );
+
+        let small = &x[..1000];
+        assert_eq!(fft_parallel(small).unwrap(), fft_complex(small).unwrap());
+        assert!(fft_parallel_with(small, 0).is_err());
+    }
+}
@@ -0,0 +1,194 @@
+use num::complex::Complex64;
+
+use crate::error::FftError;
+
+// Normalization is the scaling a forward transform applies to its output.
+// Backward is the unscaled transform computed by fft, Ortho scales by
+// 1 / sqrt(n) and Forward by 1 / n.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Normalization {
+    Backward,
+    Ortho,
+    Forward,
+}
+
+impl Normalization {
+    // energy_scale returns the factor relating the energy of an n-point
+    // spectrum to the energy of the signal, sum |x|^2 = scale * sum |X|^2
+    pub fn energy_scale(&self, n: usize) -> f64 {
+        match self {
+            Normalization::Backward => 1_f64 / n as f64,
+            Normalization::Ortho => 1_f64,
+            Normalization::Forward => n as f64,
+        }
+    }
+}
+
+// Parseval compares the energy of a signal with the energy of its spectrum.
+// relative_error is |time - frequency| / time (or the absolute difference for
+// a zero-energy signal).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Parseval {
+    pub time: f64,
+    pub frequency: f64,
+    pub relative_error: f64,
+}
+
+impl Parseval {
+    fn new(time: f64, frequency: f64) -> Self {
+        let diff = (time - frequency).abs();
+        Parseval {
+            time,
+            frequency,
+            relative_error: if time > 0_f64 { diff / time } else { diff },
+        }
+    }
+
+    pub fn holds(&self, tolerance: f64) -> bool {
+        self.relative_error <= tolerance
+    }
+}
+
+// time_energy returns sum |x|^2
+pub fn time_energy(x: &[f64]) -> f64 {
+    x.iter().map(|v| v * v).sum()
+}
+
+// time_energy_complex returns sum |x|^2 of a complex signal
+pub fn time_energy_complex(x: &[Complex64]) -> f64 {
+    x.iter().map(|c| c.norm_sqr()).sum()
+}
+
+// spectrum_energy returns the time-domain energy implied by a full n-point
+// spectrum under the given normalization, zero for an empty one
+pub fn spectrum_energy(spectrum: &[Complex64], normalization: Normalization) -> f64 {
+    if spectrum.is_empty() {
+        return 0_f64;
+    }
+    normalization.energy_scale(spectrum.len()) * time_energy_complex(spectrum)
+}
+
+// one_sided_energy returns the time-domain energy implied by the bins
+// 0..=n/2 of the spectrum of a real n-point signal; every bin other than DC
+// and (for even n) Nyquist stands for itself and its mirror image
+pub fn one_sided_energy(
+    half: &[Complex64],
+    n: usize,
+    normalization: Normalization,
+) -> Result<f64, FftError> {
+    if n == 0 {
+        return Err(FftError::InvalidParameter("signal must not be empty"));
+    }
+    if half.len() != n / 2 + 1 {
+        return Err(FftError::LengthMismatch(n / 2 + 1, half.len()));
+    }
+    let sum: f64 = half
+        .iter()
+        .enumerate()
+        .map(|(k, c)| {
+            let mirrored = k != 0 && 2 * k != n;
+            c.norm_sqr() * if mirrored { 2_f64 } else { 1_f64 }
+        })
+        .sum();
+    Ok(normalization.energy_scale(n) * sum)
+}
+
+// parseval compares the energy of a real signal with that of its full
+// spectrum
+pub fn parseval(
+    x: &[f64],
+    spectrum: &[Complex64],
+    normalization: Normalization,
+) -> Result<Parseval, FftError> {
+    if spectrum.len() != x.len() {
+        return Err(FftError::LengthMismatch(x.len(), spectrum.len()));
+    }
+    if x.is_empty() {
+        return Err(FftError::InvalidParameter("signal must not be empty"));
+    }
+    Ok(Parseval::new(
+        time_energy(x),
+        spectrum_energy(spectrum, normalization),
+    ))
+}
+
+// check_parseval verifies that a real signal and its full spectrum carry the
+// same energy within a relative tolerance, returning the comparison on
+// success
+pub fn check_parseval(
+    x: &[f64],
+    spectrum: &[Complex64],
+    normalization: Normalization,
+    tolerance: f64,
+) -> Result<Parseval, FftError> {
+    let check = parseval(x, spectrum, normalization)?;
+    if !check.holds(tolerance) {
+        return Err(FftError::InvalidParameter(
+            "spectrum energy does not match signal energy",
+        ));
+    }
+    Ok(check)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fft::fft;
+
+    #[test]
+    fn test_parseval_holds_for_fft() {
+        let x = crate::utils::generate_random_values();
+        let spectrum = fft(&x).unwrap();
+        let check = check_parseval(&x, &spectrum, Normalization::Backward, 1e-12).unwrap();
+        assert!((check.time - time_energy(&x)).abs() < 1e-9);
+
+        let half = one_sided_energy(&spectrum[..513], 1024, Normalization::Backward).unwrap();
+        assert!((half - check.time).abs() < 1e-9 * check.time);
+    }
+
+    #[test]
+    fn test_parseval_normalizations() {
+        let x = [1.0, -2.0, 3.0, 0.5];
+        let spectrum = fft(&x).unwrap();
+        let ortho: Vec<Complex64> = spectrum.iter().map(|c| c / 2.0).collect();
+        let forward: Vec<Complex64> = spectrum.iter().map(|c| c / 4.0).collect();
+        assert!(parseval(&x, &ortho, Normalization::Ortho)
+            .unwrap()
+            .holds(1e-12));
+        assert!(parseval(&x, &forward, Normalization::Forward)
+            .unwrap()
+            .holds(1e-12));
+        // The wrong normalization is caught.
+        assert!(!parseval(&x, &ortho, Normalization::Backward)
+            .unwrap()
+            .holds(0.1));
+    }
+
+    #[test]
+    fn test_parseval_detects_corruption() {
+        let x = [1.0, 2.0, 3.0, 4.0];
+        let mut spectrum = fft(&x).unwrap();
+        spectrum[1] *= 1.5;
+        assert!(matches!(
+            check_parseval(&x, &spectrum, Normalization::Backward, 1e-6),
+            Err(FftError::InvalidParameter(_))
+        ));
+        assert!(matches!(
+            parseval(&x, &spectrum[..2], Normalization::Backward),
+            Err(FftError::LengthMismatch(4, 2))
+        ));
+    }
+
+    #[test]
+    fn test_parseval_rejects_empty_input() {
+        assert!(matches!(
+            parseval(&[], &[], Normalization::Backward),
+            Err(FftError::InvalidParameter(_))
+        ));
+        assert!(matches!(
+            one_sided_energy(&[Complex64::default()], 0, Normalization::Ortho),
+            Err(FftError::InvalidParameter(_))
+        ));
+        assert_eq!(spectrum_energy(&[], Normalization::Backward), 0.0);
+    }
+}
@@ -0,0 +1,133 @@
+use num::complex::Complex64;
+
+use crate::butterflies::{butterfly, has_butterfly};
+use crate::error::FftError;
+use crate::fft::fft_complex;
+use crate::winograd::{has_winograd, winograd};
+
+// coprime_factors splits n into its prime powers, e.g. 1008 into [16, 9, 7];
+// any two of them are coprime
+pub fn coprime_factors(n: usize) -> Vec<usize> {
+    let mut factors = Vec::new();
+    let mut rest = n;
+    let mut p = 2;
+    while p * p <= rest {
+        let mut power = 1;
+        while rest.is_multiple_of(p) {
+            rest /= p;
+            power *= p;
+        }
+        if power > 1 {
+            factors.push(power);
+        }
+        p += 1;
+    }
+    if rest > 1 {
+        factors.push(rest);
+    }
+    factors
+}
+
+// pfa computes the Fourier transform of x with the prime-factor (Good-Thomas)
+// algorithm. The length is split into coprime factors n1 * n2; re-indexing
+// the input as x[(n2 a + n1 b) mod N] and the output by the Chinese
+// remainder theorem turns the transform into an exact n1 x n2
+// two-dimensional one with no twiddle factors between the stages. The prime
+// power lengths left at the end go through a Winograd kernel for the small
+// primes, a butterfly or, for the other lengths, fft_complex.
+pub fn pfa(x: &[Complex64]) -> Result<Vec<Complex64>, FftError> {
+    let factors = coprime_factors(x.len());
+    if factors.len() < 2 {
+        return leaf(x);
+    }
+    let n1 = factors[0];
+    let n2 = x.len() / n1;
+    let n = x.len();
+
+    // rows[a] holds x[(n2 a + n1 b) mod N] for b < n2
+    let mut rows = Vec::with_capacity(n1);
+    for a in 0..n1 {
+        let row: Vec<Complex64> = (0..n2).map(|b| x[(n2 * a + n1 * b) % n]).collect();
+        rows.push(pfa(&row)?);
+    }
+
+    // Output k is the one with k mod n1 = k1 and k mod n2 = k2.
+    let e1 = n2 * inverse_mod(n2, n1) % n;
+    let e2 = n1 * inverse_mod(n1, n2) % n;
+    let mut out = vec![Complex64::default(); n];
+    let mut column = vec![Complex64::default(); n1];
+    for k2 in 0..n2 {
+        for (a, row) in rows.iter().enumerate() {
+            column[a] = row[k2];
+        }
+        for (k1, v) in leaf(&column)?.into_iter().enumerate() {
+            out[(k1 * e1 + k2 * e2) % n] = v;
+        }
+    }
+    Ok(out)
+}
+
+fn leaf(x: &[Complex64]) -> Result<Vec<Complex64>, FftError> {
+    let n = x.len();
+    if has_winograd(n) {
+        let mut out = x.to_vec();
+        winograd(&mut out)?;
+        Ok(out)
+    } else if has_butterfly(n) {
+        let mut out = x.to_vec();
+        butterfly(&mut out)?;
+        Ok(out)
+    } else {
+        fft_complex(x)
+    }
+}
+
+// inverse_mod returns the inverse of a modulo m, for coprime a and m
+fn inverse_mod(a: usize, m: usize) -> usize {
+    if m == 1 {
+        return 0;
+    }
+    let (mut r0, mut r1) = (a as i64 % m as i64, m as i64);
+    let (mut s0, mut s1) = (1_i64, 0_i64);
+    while r1 != 0 {
+        let q = r0 / r1;
+        (r0, r1) = (r1, r0 - q * r1);
+        (s0, s1) = (s1, s0 - q * s1);
+    }
+    s0.rem_euclid(m as i64) as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dft::dft_complex;
+
+    fn random_complex(n: usize) -> Vec<Complex64> {
+        let values = crate::utils::generate_random_values();
+        (0..n)
+            .map(|i| Complex64::new(values[i % 1024], values[(7 * i + 3) % 1024]))
+            .collect()
+    }
+
+    #[test]
+    fn test_coprime_factors() {
+        assert_eq!(coprime_factors(1008), [16, 9, 7]);
+        assert_eq!(coprime_factors(2520), [8, 9, 5, 7]);
+        assert_eq!(coprime_factors(1024), [1024]);
+        assert_eq!(coprime_factors(1), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn test_pfa_matches_dft() {
+        // 1331 = 11^3 and the 729 of 1458 = 2 * 3^6 are prime-power leaves.
+        for n in [6, 360, 1008, 1430, 1331, 1458] {
+            let x = random_complex(n);
+            let expected = dft_complex(&x).unwrap();
+            for (a, b) in pfa(&x).unwrap().iter().zip(expected.iter()) {
+                assert!((a - b).norm() < 1e-8, "length {}", n);
+            }
+        }
+        let x = random_complex(1024);
+        assert_eq!(pfa(&x).unwrap(), fft_complex(&x).unwrap());
+    }
+}
@@ -0,0 +1,264 @@
+use num::complex::Complex64;
+
+use crate::emphasis::check_coefficient;
+use crate::error::FftError;
+use crate::planner::{Algorithm, DataFlow, FftPlan, OutputOrder, Strategy};
+use crate::trace;
+use crate::window::hann;
+
+// Scale is the quantity a pipeline reports for every bin.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scale {
+    Magnitude,
+    Power,
+}
+
+// PipelineBuilder configures a Pipeline. Frames are not windowed unless a
+// window is set, pre-emphasis is off, and values stay linear unless decibels
+// is called.
+#[derive(Debug, Clone)]
+pub struct PipelineBuilder {
+    frame_len: usize,
+    window: Option<Vec<f64>>,
+    pre_emphasis: Option<f64>,
+    scale: Scale,
+    floor_db: Option<f64>,
+}
+
+impl PipelineBuilder {
+    // window multiplies every frame by window, which must have the frame
+    // length
+    pub fn window(mut self, window: Vec<f64>) -> Self {
+        self.window = Some(window);
+        self
+    }
+
+    // hann windows every frame with a periodic Hann window
+    pub fn hann(self) -> Self {
+        let window = hann(self.frame_len);
+        self.window(window)
+    }
+
+    // pre_emphasis filters every frame with y[n] = x[n] - a x[n-1] before
+    // windowing. The filter restarts on every frame, with the first sample
+    // taken as its own predecessor, so overlapping frames need no shared
+    // state.
+    pub fn pre_emphasis(mut self, a: f64) -> Self {
+        self.pre_emphasis = Some(a);
+        self
+    }
+
+    pub fn scale(mut self, scale: Scale) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    // decibels converts the output to dB (20 log10 of magnitudes, 10 log10 of
+    // powers), clamped from below at floor_db so silent bins stay finite
+    pub fn decibels(mut self, floor_db: f64) -> Self {
+        self.floor_db = Some(floor_db);
+        self
+    }
+
+    pub fn build(self) -> Result<Pipeline, FftError> {
+        if let Some(window) = &self.window {
+            if window.len() != self.frame_len {
+                return Err(FftError::LengthMismatch(self.frame_len, window.len()));
+            }
+        }
+        if let Some(a) = self.pre_emphasis {
+            check_coefficient(a)?;
+        }
+        if self.floor_db.is_some_and(|f| f.is_nan()) {
+            return Err(FftError::InvalidParameter("dB floor must not be NaN"));
+        }
+        // The in-place decimation-in-time flow needs no scratch, so frames
+        // never allocate whatever the size.
+        let plan = FftPlan::with_strategy(
+            self.frame_len,
+            Strategy {
+                algorithm: Algorithm::DecimationInTime,
+                data_flow: DataFlow::InPlace,
+                output_order: OutputOrder::Natural,
+            },
+        )?;
+        Ok(Pipeline {
+            plan,
+            window: self.window,
+            pre_emphasis: self.pre_emphasis.unwrap_or(0_f64),
+            scale: self.scale,
+            floor_db: self.floor_db,
+            buffer: vec![Complex64::default(); self.frame_len],
+            output: vec![0_f64; self.frame_len / 2 + 1],
+        })
+    }
+}
+
+// Pipeline turns real frames into one-sided magnitude or power spectra in a
+// single pass: pre-emphasis and windowing happen while the frame is loaded into the transform
+// buffer, and magnitude and dB conversion while the bins are read out. The
+// transform and output buffers are allocated once, at build time.
+#[derive(Debug, Clone)]
+pub struct Pipeline {
+    plan: FftPlan,
+    window: Option<Vec<f64>>,
+    pre_emphasis: f64,
+    scale: Scale,
+    floor_db: Option<f64>,
+    buffer: Vec<Complex64>,
+    output: Vec<f64>,
+}
+
+impl Pipeline {
+    // builder starts a pipeline for frames of frame_len samples, which must be
+    // a power of two
+    pub fn builder(frame_len: usize) -> PipelineBuilder {
+        PipelineBuilder {
+            frame_len,
+            window: None,
+            pre_emphasis: None,
+            scale: Scale::Magnitude,
+            floor_db: None,
+        }
+    }
+
+    pub fn frame_len(&self) -> usize {
+        self.plan.len()
+    }
+
+    // bins returns the number of values produced per frame, frame_len / 2 + 1
+    pub fn bins(&self) -> usize {
+        self.output.len()
+    }
+
+    // process runs one frame through the pipeline and returns the bins
+    // 0..=frame_len/2; the slice is overwritten by the next call
+    pub fn process(&mut self, frame: &[f64]) -> Result<&[f64], FftError> {
+        if frame.len() != self.plan.len() {
+            return Err(FftError::LengthMismatch(self.plan.len(), frame.len()));
+        }
+        trace::span!("pipeline", len = frame.len());
+        let mut prev = frame[0];
+        for (i, (b, &s)) in self.buffer.iter_mut().zip(frame).enumerate() {
+            let w = self.window.as_ref().map_or(1_f64, |w| w[i]);
+            *b = Complex64::new((s - self.pre_emphasis * prev) * w, 0_f64);
+            prev = s;
+        }
+        self.plan.process(&mut self.buffer)?;
+
+        let db_factor = match self.scale {
+            Scale::Magnitude => 20_f64,
+            Scale::Power => 10_f64,
+        };
+        for (o, c) in self.output.iter_mut().zip(self.buffer.iter()) {
+            let value = match self.scale {
+                Scale::Magnitude => c.norm(),
+                Scale::Power => c.norm_sqr(),
+            };
+            *o = match self.floor_db {
+                Some(floor) => (db_factor * value.log10()).max(floor),
+                None => value,
+            };
+        }
+        Ok(&self.output)
+    }
+
+    // process_into runs one frame through the pipeline and copies the bins to
+    // out, which must hold bins() values
+    pub fn process_into(&mut self, frame: &[f64], out: &mut [f64]) -> Result<(), FftError> {
+        if out.len() != self.output.len() {
+            return Err(FftError::LengthMismatch(self.output.len(), out.len()));
+        }
+        out.copy_from_slice(self.process(frame)?);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::emphasis::pre_emphasis;
+    use crate::fft::fft;
+    use std::f64::consts::PI;
+
+    #[test]
+    fn test_pipeline_matches_separate_steps() {
+        let x = crate::utils::generate_random_values();
+        let frame = &x[..256];
+        let window = hann(256);
+        let windowed: Vec<f64> = frame
+            .iter()
+            .zip(window.iter())
+            .map(|(s, w)| s * w)
+            .collect();
+        let expected: Vec<f64> = fft(&windowed).unwrap()[..129]
+            .iter()
+            .map(|c| c.norm_sqr())
+            .collect();
+
+        let mut pipeline = Pipeline::builder(256)
+            .hann()
+            .scale(Scale::Power)
+            .build()
+            .unwrap();
+        assert_eq!(pipeline.bins(), 129);
+        let power = pipeline.process(frame).unwrap();
+        for (p, e) in power.iter().zip(expected.iter()) {
+            assert!((p - e).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_pipeline_decibels() {
+        let n = 64;
+        let frame: Vec<f64> = (0..n)
+            .map(|i| (2_f64 * PI * 8_f64 * i as f64 / n as f64).cos())
+            .collect();
+        let mut pipeline = Pipeline::builder(n).decibels(-120.0).build().unwrap();
+        let mut out = vec![0_f64; pipeline.bins()];
+        pipeline.process_into(&frame, &mut out).unwrap();
+        // A unit cosine on a bin peaks at n / 2 with no window.
+        assert!((out[8] - 20_f64 * 32_f64.log10()).abs() < 1e-9);
+        assert!(out.iter().all(|&v| v >= -120.0));
+        assert!((out[3] + 120.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_pipeline_pre_emphasis() {
+        let x = crate::utils::generate_random_values();
+        let frame = &x[..64];
+        let mut emphasized = pre_emphasis(frame, 0.9).unwrap();
+        emphasized[0] = 0.1 * frame[0];
+        let expected: Vec<f64> = fft(&emphasized).unwrap()[..33]
+            .iter()
+            .map(|c| c.norm())
+            .collect();
+
+        let mut pipeline = Pipeline::builder(64).pre_emphasis(0.9).build().unwrap();
+        let magnitude = pipeline.process(frame).unwrap();
+        for (m, e) in magnitude.iter().zip(expected.iter()) {
+            assert!((m - e).abs() < 1e-9);
+        }
+        assert!(matches!(
+            Pipeline::builder(64).pre_emphasis(1.5).build(),
+            Err(FftError::InvalidParameter(_))
+        ));
+    }
+
+    #[test]
+    fn test_pipeline_invalid_input() {
+        assert!(matches!(
+            Pipeline::builder(12).build(),
+            Err(FftError::NotAPowerOfTwo(12))
+        ));
+        assert!(matches!(
+            Pipeline::builder(16).window(hann(8)).build(),
+            Err(FftError::LengthMismatch(16, 8))
+        ));
+        let mut pipeline = Pipeline::builder(16).build().unwrap();
+        assert!(matches!(
+            pipeline.process(&[0.0; 8]),
+            Err(FftError::LengthMismatch(16, 8))
+        ));
+    }
+}
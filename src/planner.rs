@@ -0,0 +1,1056 @@
+use num::complex::{Complex, Complex64};
+use std::collections::HashMap;
+use std::f64::consts::PI;
+use std::mem::size_of;
+use std::sync::{Arc, Mutex, TryLockError};
+
+use crate::cancel::CancellationToken;
+use crate::dft::dft_complex;
+use crate::error::FftError;
+use crate::fft::{bit_reverse, transform};
+use crate::small;
+use crate::trace;
+use crate::validate::{default_validation, Validation};
+
+// Algorithm is the factorization used by a plan. Decimation in time splits
+// the input into even and odd samples and needs its input in bit-reversed
+// order; decimation in frequency splits the output into even and odd bins and
+// produces its output in bit-reversed order. Both are radix-2 and need a
+// power-of-two length; MixedRadix plans the other lengths with the
+// mixed-radix and Bluestein kernels of fft, in place and in natural order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Algorithm {
+    DecimationInTime,
+    DecimationInFrequency,
+    MixedRadix,
+}
+
+// DataFlow describes where the butterflies write. InPlace plans work inside
+// the caller's buffer and need a bit-reversal permutation to get natural
+// order; OutOfPlace plans (Stockham autosort) ping-pong between the buffer
+// and a scratch buffer of the same length and sort the output as they go.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DataFlow {
+    InPlace,
+    OutOfPlace,
+}
+
+// OutputOrder is the order of the bins a plan leaves in the buffer.
+// BitReversed skips the final permutation, which is all a caller needs when
+// the spectrum is only multiplied pointwise and transformed back by a
+// decimation-in-time inverse, as in fast convolution.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputOrder {
+    Natural,
+    BitReversed,
+}
+
+// Strategy is the decision taken by the planner for one transform size.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Strategy {
+    pub algorithm: Algorithm,
+    pub data_flow: DataFlow,
+    pub output_order: OutputOrder,
+}
+
+impl Strategy {
+    // kernel names the butterfly flow of the strategy, for tracing
+    #[cfg(feature = "tracing")]
+    fn kernel(&self) -> &'static str {
+        match (self.algorithm, self.data_flow) {
+            (Algorithm::DecimationInTime, _) => "dit",
+            (Algorithm::DecimationInFrequency, DataFlow::InPlace) => "dif",
+            (Algorithm::DecimationInFrequency, DataFlow::OutOfPlace) => "stockham",
+            (Algorithm::MixedRadix, _) => "mixed_radix",
+        }
+    }
+}
+
+// Sizes from which natural-order plans switch to the out-of-place Stockham
+// flow: beyond this the scattered accesses of the bit-reversal permutation
+// cost more than the extra scratch buffer.
+const OUT_OF_PLACE_THRESHOLD: usize = 1 << 14;
+
+// FftPlanner chooses a strategy for every transform size and builds plans
+// with precomputed twiddle factors.
+#[derive(Debug, Clone)]
+pub struct FftPlanner {
+    output_order: OutputOrder,
+    validation: Validation,
+    cache: HashMap<usize, Arc<FftPlan>>,
+}
+
+impl Default for FftPlanner {
+    fn default() -> Self {
+        FftPlanner::new()
+    }
+}
+
+impl FftPlanner {
+    // new returns a planner producing natural-order output, validating
+    // input according to default_validation
+    pub fn new() -> Self {
+        FftPlanner {
+            output_order: OutputOrder::Natural,
+            validation: default_validation(),
+            cache: HashMap::new(),
+        }
+    }
+
+    // output_order sets the bin order the caller needs from its plans
+    pub fn output_order(mut self, order: OutputOrder) -> Self {
+        self.output_order = order;
+        self.cache.clear();
+        self
+    }
+
+    // validation sets how its plans treat NaN and infinite input values
+    pub fn validation(mut self, mode: Validation) -> Self {
+        self.validation = mode;
+        self.cache.clear();
+        self
+    }
+
+    // strategy returns the strategy a plan of length len would use. Callers
+    // that accept bit-reversed output get an in-place decimation-in-frequency
+    // plan with no permutation at all. Natural order is produced by an
+    // in-place decimation-in-time plan for small sizes and by the
+    // out-of-place Stockham flow from OUT_OF_PLACE_THRESHOLD upwards. Lengths
+    // that are not powers of two always get a natural-order MixedRadix plan.
+    pub fn strategy(&self, len: usize) -> Strategy {
+        match self.output_order {
+            _ if !len.is_power_of_two() => Strategy {
+                algorithm: Algorithm::MixedRadix,
+                data_flow: DataFlow::InPlace,
+                output_order: OutputOrder::Natural,
+            },
+            OutputOrder::BitReversed => Strategy {
+                algorithm: Algorithm::DecimationInFrequency,
+                data_flow: DataFlow::InPlace,
+                output_order: OutputOrder::BitReversed,
+            },
+            OutputOrder::Natural if len >= OUT_OF_PLACE_THRESHOLD => Strategy {
+                algorithm: Algorithm::DecimationInFrequency,
+                data_flow: DataFlow::OutOfPlace,
+                output_order: OutputOrder::Natural,
+            },
+            OutputOrder::Natural => Strategy {
+                algorithm: Algorithm::DecimationInTime,
+                data_flow: DataFlow::InPlace,
+                output_order: OutputOrder::Natural,
+            },
+        }
+    }
+
+    // plan builds a plan for transforms of length len
+    pub fn plan(&self, len: usize) -> Result<FftPlan, FftError> {
+        Ok(FftPlan::with_strategy(len, self.strategy(len))?.with_validation(self.validation))
+    }
+
+    // plan_cached returns the plan for transforms of length len, building it
+    // on first use only. Callers transforming many frames of a few sizes keep
+    // one planner and share the returned plans, so twiddle factors are
+    // computed once per size.
+    pub fn plan_cached(&mut self, len: usize) -> Result<Arc<FftPlan>, FftError> {
+        if let Some(plan) = self.cache.get(&len) {
+            return Ok(Arc::clone(plan));
+        }
+        let plan = Arc::new(self.plan(len)?);
+        self.cache.insert(len, Arc::clone(&plan));
+        Ok(plan)
+    }
+
+    // memory_usage returns the bytes a plan of length len would occupy,
+    // without building it; see FftPlan::memory_usage
+    pub fn memory_usage(&self, len: usize) -> Result<usize, FftError> {
+        if len == 0 {
+            return Err(FftError::InvalidParameter("len must be greater than zero"));
+        }
+        Ok(memory_usage(len, self.strategy(len)))
+    }
+
+    // estimated_flops returns the floating-point operations of one transform
+    // of length len; see FftPlan::estimated_flops
+    pub fn estimated_flops(&self, len: usize) -> Result<usize, FftError> {
+        if len == 0 {
+            return Err(FftError::InvalidParameter("len must be greater than zero"));
+        }
+        Ok(estimated_flops(len))
+    }
+}
+
+fn scratch_len(len: usize, strategy: Strategy) -> usize {
+    match strategy.data_flow {
+        DataFlow::InPlace => 0,
+        DataFlow::OutOfPlace => len,
+    }
+}
+
+// uses_small_kernel tells whether a plan runs the heap-free kernels of the
+// small module, which bring their own twiddle table
+fn uses_small_kernel(len: usize, strategy: Strategy) -> bool {
+    len <= small::MAX_LEN && strategy.algorithm == Algorithm::DecimationInTime
+}
+
+// has_twiddles tells whether a plan keeps a twiddle table; MixedRadix plans
+// use the kernels of fft, which compute their own
+fn has_twiddles(len: usize, strategy: Strategy) -> bool {
+    !uses_small_kernel(len, strategy) && strategy.algorithm != Algorithm::MixedRadix
+}
+
+// memory_usage saturates at usize::MAX for sizes no plan can have
+fn memory_usage(len: usize, strategy: Strategy) -> usize {
+    let twiddles = if has_twiddles(len, strategy) {
+        len / 2
+    } else {
+        0
+    };
+    let values = twiddles + scratch_len(len, strategy);
+    values
+        .saturating_mul(size_of::<Complex64>())
+        .saturating_add(size_of::<FftPlan>())
+}
+
+// Every radix-2 butterfly is one complex multiplication (6 flops) and two
+// complex additions (4 flops), and a transform has len / 2 of them in each
+// of its log2(len) passes. Other lengths are counted as the next power of two.
+fn estimated_flops(len: usize) -> usize {
+    let passes = len
+        .checked_next_power_of_two()
+        .map_or(usize::BITS, usize::trailing_zeros);
+    len.saturating_mul(5 * passes as usize)
+}
+
+// Progress reports how far a transform has got: pass of passes butterfly
+// passes are complete.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Progress {
+    pub pass: usize,
+    pub passes: usize,
+}
+
+impl Progress {
+    // percent returns the completed share of the transform in percent
+    pub fn percent(&self) -> f64 {
+        if self.passes == 0 {
+            return 100_f64;
+        }
+        100_f64 * self.pass as f64 / self.passes as f64
+    }
+}
+
+type AfterPass<'a> = dyn FnMut(usize) -> Result<(), FftError> + 'a;
+
+// FftPlan is a forward transform of a fixed length with its twiddle factors
+// W^j = exp(2j pi j / len), j < len / 2, and its scratch buffer allocated
+// once.
+#[derive(Debug)]
+pub struct FftPlan {
+    len: usize,
+    strategy: Strategy,
+    twiddles: Vec<Complex64>,
+    validation: Validation,
+    scratch: Mutex<Vec<Complex64>>,
+}
+
+impl Clone for FftPlan {
+    fn clone(&self) -> Self {
+        FftPlan {
+            len: self.len,
+            strategy: self.strategy,
+            twiddles: self.twiddles.clone(),
+            validation: self.validation,
+            scratch: Mutex::new(vec![Complex64::default(); self.scratch_len()]),
+        }
+    }
+}
+
+impl FftPlan {
+    // with_strategy builds a plan that uses the given strategy regardless of
+    // the planner heuristics
+    pub fn with_strategy(len: usize, strategy: Strategy) -> Result<Self, FftError> {
+        if strategy.algorithm == Algorithm::MixedRadix {
+            if len == 0 {
+                return Err(FftError::InvalidParameter("len must be greater than zero"));
+            }
+            if strategy.data_flow != DataFlow::InPlace
+                || strategy.output_order != OutputOrder::Natural
+            {
+                return Err(FftError::InvalidParameter(
+                    "mixed-radix plans are in place and in natural order",
+                ));
+            }
+        } else if !len.is_power_of_two() {
+            return Err(FftError::NotAPowerOfTwo(len));
+        }
+        if strategy.data_flow == DataFlow::OutOfPlace
+            && (strategy.algorithm != Algorithm::DecimationInFrequency
+                || strategy.output_order != OutputOrder::Natural)
+        {
+            return Err(FftError::InvalidParameter(
+                "out-of-place plans are natural-order decimation in frequency",
+            ));
+        }
+        if strategy.algorithm == Algorithm::DecimationInTime
+            && strategy.output_order != OutputOrder::Natural
+        {
+            return Err(FftError::InvalidParameter(
+                "decimation-in-time plans produce natural-order output",
+            ));
+        }
+        // Buffers of len values (and the plan's scratch) must be addressable.
+        if len
+            .checked_mul(size_of::<Complex64>())
+            .is_none_or(|bytes| bytes > isize::MAX as usize)
+        {
+            return Err(FftError::SizeTooLarge(len));
+        }
+        trace::span!("plan", len, algorithm = strategy.kernel());
+        let twiddles = if has_twiddles(len, strategy) {
+            twiddles(len)
+        } else {
+            Vec::new()
+        };
+        Ok(FftPlan {
+            len,
+            strategy,
+            twiddles,
+            validation: default_validation(),
+            scratch: Mutex::new(vec![Complex64::default(); scratch_len(len, strategy)]),
+        })
+    }
+
+    // with_validation sets how process and its variants treat NaN and
+    // infinite values in the buffer before transforming it
+    pub fn with_validation(mut self, mode: Validation) -> Self {
+        self.validation = mode;
+        self
+    }
+
+    pub fn validation(&self) -> Validation {
+        self.validation
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn strategy(&self) -> Strategy {
+        self.strategy
+    }
+
+    // scratch_len returns the length of the scratch buffer process_with_scratch
+    // needs; in-place plans need none
+    pub fn scratch_len(&self) -> usize {
+        scratch_len(self.len, self.strategy)
+    }
+
+    // memory_usage returns the bytes held by the plan, its twiddle factors
+    // and its scratch buffer
+    pub fn memory_usage(&self) -> usize {
+        memory_usage(self.len, self.strategy)
+    }
+
+    // estimated_flops returns the real floating-point operations of one
+    // transform, 5 len log2(len) for radix-2, ignoring the permutation
+    pub fn estimated_flops(&self) -> usize {
+        estimated_flops(self.len)
+    }
+
+    // process transforms buffer in place using the plan's scratch buffer, so
+    // repeated calls do not allocate. A call finding the buffer in use by
+    // another thread sharing the plan allocates its own instead of waiting.
+    pub fn process(&self, buffer: &mut [Complex64]) -> Result<(), FftError> {
+        match self.scratch.try_lock() {
+            Ok(mut scratch) => self.process_with_scratch(buffer, &mut scratch),
+            Err(TryLockError::Poisoned(poisoned)) => {
+                self.process_with_scratch(buffer, &mut poisoned.into_inner())
+            }
+            Err(TryLockError::WouldBlock) => {
+                let mut scratch = vec![Complex64::default(); self.scratch_len()];
+                self.process_with_scratch(buffer, &mut scratch)
+            }
+        }
+    }
+
+    // process_with_scratch transforms buffer in place using the caller's
+    // scratch space of at least scratch_len values, so that threads sharing
+    // one plan behind an Arc can each bring their own scratch.
+    pub fn process_with_scratch(
+        &self,
+        buffer: &mut [Complex64],
+        scratch: &mut [Complex64],
+    ) -> Result<(), FftError> {
+        self.run(buffer, scratch, &mut |_| Ok(()))
+    }
+
+    // process_real_into transforms the real input into the caller's buffer
+    // of len values, using scratch of at least scratch_len values; nothing
+    // is allocated, so it can run where allocation is forbidden
+    pub fn process_real_into(
+        &self,
+        input: &[f64],
+        buffer: &mut [Complex64],
+        scratch: &mut [Complex64],
+    ) -> Result<(), FftError> {
+        if input.len() != self.len {
+            return Err(FftError::LengthMismatch(self.len, input.len()));
+        }
+        if buffer.len() != self.len {
+            return Err(FftError::LengthMismatch(self.len, buffer.len()));
+        }
+        for (b, &x) in buffer.iter_mut().zip(input) {
+            *b = Complex64::new(x, 0_f64);
+        }
+        self.process_with_scratch(buffer, scratch)
+    }
+
+    // bins runs process_real_into and returns an iterator over the bins
+    // kept in buffer, without allocating
+    pub fn bins<'b>(
+        &self,
+        input: &[f64],
+        buffer: &'b mut [Complex64],
+        scratch: &mut [Complex64],
+    ) -> Result<std::iter::Copied<std::slice::Iter<'b, Complex64>>, FftError> {
+        self.process_real_into(input, buffer, scratch)?;
+        Ok(buffer.iter().copied())
+    }
+
+    // for_each_bin runs process_real_into and hands every bin with its index
+    // to f, without allocating
+    pub fn for_each_bin(
+        &self,
+        input: &[f64],
+        buffer: &mut [Complex64],
+        scratch: &mut [Complex64],
+        mut f: impl FnMut(usize, Complex64),
+    ) -> Result<(), FftError> {
+        self.process_real_into(input, buffer, scratch)?;
+        buffer.iter().enumerate().for_each(|(k, &c)| f(k, c));
+        Ok(())
+    }
+
+    // process_with_progress is process_with_scratch reporting to progress
+    // after each of the log2(len) butterfly passes, so that long transforms
+    // can show their status
+    pub fn process_with_progress(
+        &self,
+        buffer: &mut [Complex64],
+        scratch: &mut [Complex64],
+        progress: &mut dyn FnMut(Progress),
+    ) -> Result<(), FftError> {
+        let passes = self.passes();
+        self.run(buffer, scratch, &mut |pass| {
+            progress(Progress { pass, passes });
+            Ok(())
+        })
+    }
+
+    // process_cancellable is process_with_scratch checking token between
+    // passes; a cancelled transform returns FftError::Cancelled and leaves
+    // buffer unspecified
+    pub fn process_cancellable(
+        &self,
+        buffer: &mut [Complex64],
+        scratch: &mut [Complex64],
+        token: &CancellationToken,
+    ) -> Result<(), FftError> {
+        token.check()?;
+        self.run(buffer, scratch, &mut |_| token.check())
+    }
+
+    // verify transforms a pseudo-random input with the plan and with the
+    // direct dft_complex and reports the largest deviation between the two
+    // spectra. The input is fixed, with values in [-1, 1), so a failure
+    // reproduces on every run. The reference costs O(len^2).
+    pub fn verify(&self, tolerance: f64) -> Result<Verification, FftError> {
+        let mut state = 0x853c_49e6_748f_ea9b_u64;
+        let input: Vec<Complex64> = (0..self.len)
+            .map(|_| Complex64::new(unit_noise(&mut state), unit_noise(&mut state)))
+            .collect();
+        let reference = dft_complex(&input)?;
+        let mut buffer = input;
+        self.process(&mut buffer)?;
+        if self.strategy.output_order == OutputOrder::BitReversed {
+            bit_reverse(&mut buffer);
+        }
+        let max_deviation = buffer
+            .iter()
+            .zip(&reference)
+            .map(|(a, b)| (a - b).norm())
+            .fold(0_f64, f64::max);
+        Ok(Verification {
+            max_deviation,
+            tolerance,
+        })
+    }
+
+    // passes returns the number of butterfly passes of a transform; a
+    // MixedRadix transform counts as one
+    pub fn passes(&self) -> usize {
+        if self.strategy.algorithm == Algorithm::MixedRadix {
+            return 1;
+        }
+        self.len.trailing_zeros() as usize
+    }
+
+    // run transforms buffer, calling after_pass with the number of passes
+    // completed so far; an error from after_pass aborts the transform and
+    // leaves buffer unspecified
+    fn run(
+        &self,
+        buffer: &mut [Complex64],
+        scratch: &mut [Complex64],
+        after_pass: &mut AfterPass,
+    ) -> Result<(), FftError> {
+        if buffer.len() != self.len {
+            return Err(FftError::LengthMismatch(self.len, buffer.len()));
+        }
+        if scratch.len() < self.scratch_len() {
+            return Err(FftError::LengthMismatch(self.scratch_len(), scratch.len()));
+        }
+        self.validation.apply(buffer)?;
+        trace::span!(
+            "execute",
+            len = self.len,
+            algorithm = self.strategy.kernel()
+        );
+        match (self.strategy.algorithm, self.strategy.data_flow) {
+            (Algorithm::DecimationInTime, _) if uses_small_kernel(self.len, self.strategy) => {
+                small::transform(buffer);
+                (1..=self.passes()).try_for_each(after_pass)
+            }
+            (Algorithm::DecimationInTime, _) => {
+                bit_reverse(buffer);
+                self.dit(buffer, after_pass)
+            }
+            (Algorithm::DecimationInFrequency, DataFlow::InPlace) => {
+                self.dif(buffer, after_pass)?;
+                if self.strategy.output_order == OutputOrder::Natural {
+                    bit_reverse(buffer);
+                }
+                Ok(())
+            }
+            (Algorithm::DecimationInFrequency, DataFlow::OutOfPlace) => {
+                self.stockham(buffer, &mut scratch[..self.len], after_pass)
+            }
+            (Algorithm::MixedRadix, _) => {
+                transform(buffer)?;
+                after_pass(1)
+            }
+        }
+    }
+
+    // dit runs the decimation-in-time butterflies on bit-reversed input
+    fn dit(&self, buffer: &mut [Complex64], after_pass: &mut AfterPass) -> Result<(), FftError> {
+        let n = self.len;
+        let mut size = 2;
+        while size <= n {
+            let half = size / 2;
+            let step = n / size;
+            for block in buffer.chunks_exact_mut(size) {
+                let (lo, hi) = block.split_at_mut(half);
+                for j in 0..half {
+                    let v = hi[j] * self.twiddles[j * step];
+                    hi[j] = lo[j] - v;
+                    lo[j] += v;
+                }
+            }
+            after_pass(size.trailing_zeros() as usize)?;
+            size *= 2;
+        }
+        Ok(())
+    }
+
+    // dif runs the decimation-in-frequency butterflies, leaving the output in
+    // bit-reversed order
+    fn dif(&self, buffer: &mut [Complex64], after_pass: &mut AfterPass) -> Result<(), FftError> {
+        let n = self.len;
+        let mut size = n;
+        while size >= 2 {
+            let half = size / 2;
+            let step = n / size;
+            for block in buffer.chunks_exact_mut(size) {
+                let (lo, hi) = block.split_at_mut(half);
+                for j in 0..half {
+                    let u = lo[j];
+                    lo[j] = u + hi[j];
+                    hi[j] = (u - hi[j]) * self.twiddles[j * step];
+                }
+            }
+            after_pass((n / size).trailing_zeros() as usize + 1)?;
+            size /= 2;
+        }
+        Ok(())
+    }
+
+    // stockham runs the self-sorting decimation-in-frequency passes, reading
+    // from one buffer and writing to the other, and leaves the result in
+    // buffer
+    fn stockham(
+        &self,
+        buffer: &mut [Complex64],
+        scratch: &mut [Complex64],
+        after_pass: &mut AfterPass,
+    ) -> Result<(), FftError> {
+        let n = self.len;
+        let mut stride = 1;
+        let mut size = n;
+        let mut in_buffer = true;
+        while size >= 2 {
+            let half = size / 2;
+            let (src, dst): (&[Complex64], &mut [Complex64]) = if in_buffer {
+                (buffer, scratch)
+            } else {
+                (scratch, buffer)
+            };
+            for p in 0..half {
+                let w = self.twiddles[p * stride];
+                for q in 0..stride {
+                    let a = src[q + stride * p];
+                    let b = src[q + stride * (p + half)];
+                    dst[q + stride * 2 * p] = a + b;
+                    dst[q + stride * (2 * p + 1)] = (a - b) * w;
+                }
+            }
+            in_buffer = !in_buffer;
+            after_pass(stride.trailing_zeros() as usize + 1)?;
+            stride *= 2;
+            size /= 2;
+        }
+        if !in_buffer {
+            buffer.copy_from_slice(scratch);
+        }
+        Ok(())
+    }
+}
+
+// Twiddle tables longer than TWIDDLE_CHUNK are built chunk by chunk: every
+// W^j with j = c * TWIDDLE_CHUNK + r is the product of two exactly computed
+// factors, W^(c * TWIDDLE_CHUNK) and W^r. That needs len / 2 / TWIDDLE_CHUNK
+// + TWIDDLE_CHUNK evaluations of exp instead of len / 2, which keeps planning
+// of transforms with billions of points fast, while every value is within a
+// few ulps of exp(2j pi j / len).
+const TWIDDLE_CHUNK: usize = 1024;
+
+// twiddles returns W^j = exp(2j pi j / len) for j < len / 2
+fn twiddles(len: usize) -> Vec<Complex64> {
+    let w = |j: usize| Complex::new(0_f64, 2_f64 * PI * (j as f64 / len as f64)).exp();
+    let count = len / 2;
+    if count <= TWIDDLE_CHUNK {
+        return (0..count).map(w).collect();
+    }
+    let fine: Vec<Complex64> = (0..TWIDDLE_CHUNK).map(w).collect();
+    let mut out = Vec::with_capacity(count);
+    for c in (0..count).step_by(TWIDDLE_CHUNK) {
+        let coarse = w(c);
+        out.extend(fine.iter().map(|f| coarse * f));
+    }
+    out
+}
+
+// Verification is the outcome of FftPlan::verify.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Verification {
+    pub max_deviation: f64,
+    pub tolerance: f64,
+}
+
+impl Verification {
+    pub fn passed(&self) -> bool {
+        self.max_deviation <= self.tolerance
+    }
+}
+
+// unit_noise advances the splitmix64 generator state and maps its output to
+// [-1, 1)
+fn unit_noise(state: &mut u64) -> f64 {
+    *state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^= z >> 31;
+    (z >> 11) as f64 / (1_u64 << 52) as f64 - 1_f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fft::fft_complex;
+
+    fn random_complex(len: usize) -> Vec<Complex64> {
+        let values = crate::utils::generate_random_values();
+        (0..len)
+            .map(|i| Complex64::new(values[i % 1024], values[(i * 7 + 3) % 1024]))
+            .collect()
+    }
+
+    fn assert_close(a: &[Complex64], b: &[Complex64]) {
+        assert_eq!(a.len(), b.len());
+        for i in 0..a.len() {
+            assert!(
+                (a[i] - b[i]).norm() < 1e-9,
+                "bin {}: {} vs {}",
+                i,
+                a[i],
+                b[i]
+            );
+        }
+    }
+
+    #[test]
+    fn test_planner_heuristics() {
+        let planner = FftPlanner::new();
+        assert_eq!(
+            planner.strategy(1024).algorithm,
+            Algorithm::DecimationInTime
+        );
+        assert_eq!(planner.strategy(1024).data_flow, DataFlow::InPlace);
+        assert_eq!(planner.strategy(1 << 16).data_flow, DataFlow::OutOfPlace);
+
+        let unordered = FftPlanner::new().output_order(OutputOrder::BitReversed);
+        let s = unordered.strategy(1 << 16);
+        assert_eq!(s.algorithm, Algorithm::DecimationInFrequency);
+        assert_eq!(s.data_flow, DataFlow::InPlace);
+        assert_eq!(s.output_order, OutputOrder::BitReversed);
+    }
+
+    #[test]
+    fn test_all_strategies_match_fft() {
+        let strategies = [
+            (Algorithm::DecimationInTime, DataFlow::InPlace),
+            (Algorithm::DecimationInFrequency, DataFlow::InPlace),
+            (Algorithm::DecimationInFrequency, DataFlow::OutOfPlace),
+        ];
+        for len in [1, 2, 4, 8, 64, 512] {
+            let input = random_complex(len);
+            let expected = fft_complex(&input).unwrap();
+            for &(algorithm, data_flow) in &strategies {
+                let strategy = Strategy {
+                    algorithm,
+                    data_flow,
+                    output_order: OutputOrder::Natural,
+                };
+                let plan = FftPlan::with_strategy(len, strategy).unwrap();
+                let mut buffer = input.clone();
+                plan.process(&mut buffer).unwrap();
+                assert_close(&buffer, &expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_bit_reversed_output() {
+        let input = random_complex(256);
+        let mut expected = fft_complex(&input).unwrap();
+        bit_reverse(&mut expected);
+
+        let plan = FftPlanner::new()
+            .output_order(OutputOrder::BitReversed)
+            .plan(256)
+            .unwrap();
+        let mut buffer = input.clone();
+        plan.process(&mut buffer).unwrap();
+        assert_close(&buffer, &expected);
+    }
+
+    #[test]
+    fn test_plan_shared_between_threads() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<FftPlan>();
+
+        let plan = std::sync::Arc::new(FftPlanner::new().plan(1 << 14).unwrap());
+        assert_eq!(plan.scratch_len(), 1 << 14);
+        let input = random_complex(1 << 14);
+        let expected = fft_complex(&input).unwrap();
+        std::thread::scope(|s| {
+            for _ in 0..4 {
+                let plan = std::sync::Arc::clone(&plan);
+                let (input, expected) = (&input, &expected);
+                s.spawn(move || {
+                    let mut scratch = vec![Complex64::default(); plan.scratch_len()];
+                    let mut buffer = input.clone();
+                    plan.process_with_scratch(&mut buffer, &mut scratch)
+                        .unwrap();
+                    assert_close(&buffer, expected);
+                });
+            }
+        });
+
+        let mut buffer = input.clone();
+        assert!(matches!(
+            plan.process_with_scratch(&mut buffer, &mut []),
+            Err(FftError::LengthMismatch(16384, 0))
+        ));
+    }
+
+    #[test]
+    fn test_plan_progress() {
+        for planner in [
+            FftPlanner::new(),
+            FftPlanner::new().output_order(OutputOrder::BitReversed),
+        ] {
+            for len in [256, 1 << 14] {
+                let plan = planner.plan(len).unwrap();
+                let mut reports = Vec::new();
+                let mut buffer = random_complex(len);
+                let mut scratch = vec![Complex64::default(); plan.scratch_len()];
+                plan.process_with_progress(&mut buffer, &mut scratch, &mut |p| reports.push(p))
+                    .unwrap();
+                let passes = plan.passes();
+                let expected: Vec<Progress> =
+                    (1..=passes).map(|pass| Progress { pass, passes }).collect();
+                assert_eq!(reports, expected);
+                assert_eq!(reports.last().unwrap().percent(), 100.0);
+            }
+        }
+    }
+
+    #[test]
+    fn test_plan_cancellation() {
+        let plan = FftPlanner::new().plan(1024).unwrap();
+        let token = CancellationToken::new();
+        let input = random_complex(1024);
+        let mut buffer = input.clone();
+        plan.process_cancellable(&mut buffer, &mut [], &token)
+            .unwrap();
+        assert_close(&buffer, &fft_complex(&input).unwrap());
+
+        // Cancel from the progress callback of another transform sharing the
+        // token, half way through.
+        let mut passes = 0;
+        let result = plan.run(&mut buffer, &mut [], &mut |pass| {
+            passes = pass;
+            if pass == 5 {
+                token.cancel();
+            }
+            token.check()
+        });
+        assert!(matches!(result, Err(FftError::Cancelled)));
+        assert_eq!(passes, 5);
+        assert!(matches!(
+            plan.process_cancellable(&mut buffer, &mut [], &token),
+            Err(FftError::Cancelled)
+        ));
+    }
+
+    #[test]
+    fn test_large_plan_twiddles_and_limits() {
+        let len = 1 << 20;
+        let table = twiddles(len);
+        assert_eq!(table.len(), len / 2);
+        for j in [0, 1, 1023, 1024, 1025, 300_000, len / 2 - 1] {
+            let exact = Complex::new(0_f64, 2_f64 * PI * j as f64 / len as f64).exp();
+            assert!((table[j] - exact).norm() < 1e-15, "twiddle {}", j);
+        }
+        // Sizes whose buffers cannot be addressed are refused before any
+        // allocation.
+        assert!(matches!(
+            FftPlanner::new().plan(1 << (usize::BITS - 2)),
+            Err(FftError::SizeTooLarge(_))
+        ));
+    }
+
+    #[test]
+    fn test_any_length_plans() {
+        for order in [OutputOrder::Natural, OutputOrder::BitReversed] {
+            let planner = FftPlanner::new().output_order(order);
+            for len in [3, 12, 100, 97, 1000] {
+                let plan = planner.plan(len).unwrap();
+                assert_eq!(plan.strategy().algorithm, Algorithm::MixedRadix);
+                assert_eq!(plan.strategy().output_order, OutputOrder::Natural);
+                let input = random_complex(len);
+                let mut buffer = input.clone();
+                plan.process(&mut buffer).unwrap();
+                assert_close(&buffer, &fft_complex(&input).unwrap());
+                assert!(plan.verify(1e-9).unwrap().passed());
+            }
+        }
+    }
+
+    #[test]
+    fn test_shared_plan_scratch() {
+        // Threads sharing a Stockham plan either take its scratch buffer or
+        // allocate their own; every result must be right either way.
+        let plan = Arc::new(FftPlanner::new().plan(1 << 14).unwrap());
+        assert_eq!(plan.scratch_len(), 1 << 14);
+        let input = random_complex(1 << 14);
+        let expected = fft_complex(&input).unwrap();
+        std::thread::scope(|s| {
+            for _ in 0..4 {
+                s.spawn(|| {
+                    for _ in 0..3 {
+                        let mut buffer = input.clone();
+                        plan.process(&mut buffer).unwrap();
+                        assert_close(&buffer, &expected);
+                    }
+                });
+            }
+        });
+        let copy = (*plan).clone();
+        let mut buffer = input.clone();
+        copy.process(&mut buffer).unwrap();
+        assert_close(&buffer, &expected);
+    }
+
+    #[test]
+    fn test_tiny_plans_use_small_kernel() {
+        for len in [1, 2, 16, 64] {
+            let plan = FftPlanner::new().plan(len).unwrap();
+            assert_eq!(plan.memory_usage(), size_of::<FftPlan>());
+            let input = random_complex(len);
+            let mut buffer = input.clone();
+            plan.process(&mut buffer).unwrap();
+            assert_close(&buffer, &fft_complex(&input).unwrap());
+        }
+        let plan = FftPlanner::new().plan(128).unwrap();
+        assert!(plan.memory_usage() > size_of::<FftPlan>());
+    }
+
+    #[test]
+    fn test_plan_costs() {
+        let planner = FftPlanner::new();
+        let small = planner.plan(1024).unwrap();
+        assert_eq!(small.estimated_flops(), 5 * 1024 * 10);
+        assert_eq!(
+            small.memory_usage(),
+            size_of::<FftPlan>() + 512 * size_of::<Complex64>()
+        );
+        // Stockham plans also count their scratch buffer.
+        let large = planner.plan(1 << 16).unwrap();
+        assert_eq!(
+            large.memory_usage(),
+            size_of::<FftPlan>() + (3 << 15) * size_of::<Complex64>()
+        );
+        assert_eq!(planner.memory_usage(1 << 16).unwrap(), large.memory_usage());
+        assert_eq!(planner.estimated_flops(1).unwrap(), 0);
+        // Other lengths keep neither twiddles nor scratch.
+        assert_eq!(planner.memory_usage(1000).unwrap(), size_of::<FftPlan>());
+        assert_eq!(planner.estimated_flops(1000).unwrap(), 5 * 1000 * 10);
+        assert!(matches!(
+            planner.memory_usage(0),
+            Err(FftError::InvalidParameter(_))
+        ));
+    }
+
+    #[test]
+    fn test_plan_invalid_input() {
+        assert!(matches!(
+            FftPlanner::new().plan(0),
+            Err(FftError::InvalidParameter(_))
+        ));
+        assert!(matches!(
+            FftPlan::with_strategy(12, FftPlanner::new().strategy(16)),
+            Err(FftError::NotAPowerOfTwo(12))
+        ));
+        let plan = FftPlanner::new().plan(8).unwrap();
+        let mut buffer = vec![Complex64::default(); 4];
+        assert!(matches!(
+            plan.process(&mut buffer),
+            Err(FftError::LengthMismatch(8, 4))
+        ));
+        let strategy = Strategy {
+            algorithm: Algorithm::DecimationInTime,
+            data_flow: DataFlow::InPlace,
+            output_order: OutputOrder::BitReversed,
+        };
+        assert!(matches!(
+            FftPlan::with_strategy(8, strategy),
+            Err(FftError::InvalidParameter(_))
+        ));
+    }
+    #[test]
+    fn test_bins_into_caller_storage() {
+        let x = crate::utils::generate_random_values();
+        let plan = FftPlanner::new().plan(1024).unwrap();
+        let mut buffer = vec![Complex64::default(); 1024];
+        let mut scratch = vec![Complex64::default(); plan.scratch_len()];
+        let expected = crate::fft::fft(&x).unwrap();
+        let peak = plan
+            .bins(&x, &mut buffer, &mut scratch)
+            .unwrap()
+            .skip(1)
+            .take(511)
+            .map(|c| c.norm())
+            .fold(0_f64, f64::max);
+        let expected_peak = expected[1..512]
+            .iter()
+            .map(|c| c.norm())
+            .fold(0_f64, f64::max);
+        assert!((peak - expected_peak).abs() < 1e-6);
+
+        let mut seen = 0;
+        plan.for_each_bin(&x, &mut buffer, &mut scratch, |k, c| {
+            assert!((c - expected[k]).norm() < 1e-6);
+            seen += 1;
+        })
+        .unwrap();
+        assert_eq!(seen, 1024);
+        assert!(matches!(
+            plan.process_real_into(&x[..10], &mut buffer, &mut scratch),
+            Err(FftError::LengthMismatch(1024, 10))
+        ));
+    }
+
+    #[test]
+    fn test_plan_validation() {
+        let mut buffer = vec![Complex64::new(1_f64, 0_f64); 64];
+        buffer[5] = Complex64::new(f64::NAN, 0_f64);
+        let plan = FftPlanner::new()
+            .validation(Validation::Error)
+            .plan(64)
+            .unwrap();
+        assert_eq!(plan.validation(), Validation::Error);
+        assert!(matches!(
+            plan.process(&mut buffer.clone()),
+            Err(FftError::NonFinite(5))
+        ));
+
+        let plan = plan.with_validation(Validation::SanitizeToZero);
+        plan.process(&mut buffer).unwrap();
+        assert!((buffer[0] - Complex64::new(63_f64, 0_f64)).norm() < 1e-9);
+        assert!(buffer.iter().all(|c| c.is_finite()));
+    }
+
+    #[test]
+    fn test_plan_verify() {
+        for order in [OutputOrder::Natural, OutputOrder::BitReversed] {
+            let plan = FftPlanner::new().output_order(order).plan(256).unwrap();
+            let report = plan.verify(1e-9).unwrap();
+            assert!(report.passed(), "{:?}: {:?}", order, report);
+            assert!(report.max_deviation > 0_f64);
+        }
+        // A tolerance below the rounding error of the plan must fail.
+        let mut plan = FftPlanner::new().plan(256).unwrap();
+        let deviation = plan.verify(1e-9).unwrap().max_deviation;
+        assert!(!plan.verify(deviation / 2_f64).unwrap().passed());
+        // So must a plan with a wrong twiddle factor.
+        plan.twiddles[3] = -plan.twiddles[3];
+        let report = plan.verify(1e-9).unwrap();
+        assert!(!report.passed(), "{:?}", report);
+        assert!(report.max_deviation > 1e-3);
+    }
+
+    #[test]
+    fn test_plan_cached_reuses_plans() {
+        let mut planner = FftPlanner::new();
+        let a = planner.plan_cached(512).unwrap();
+        let b = planner.plan_cached(512).unwrap();
+        assert!(Arc::ptr_eq(&a, &b));
+        assert!(!Arc::ptr_eq(&a, &planner.plan_cached(256).unwrap()));
+        assert_eq!(
+            planner.plan_cached(100).unwrap().strategy().algorithm,
+            Algorithm::MixedRadix
+        );
+        assert!(planner.plan_cached(0).is_err());
+
+        let mut planner = planner.output_order(OutputOrder::BitReversed);
+        let c = planner.plan_cached(512).unwrap();
+        assert_eq!(c.strategy().output_order, OutputOrder::BitReversed);
+    }
+}
@@ -0,0 +1,508 @@
+use num::complex::Complex64;
+use std::fmt::Debug;
+use std::ops::{Add, Mul, Sub};
+
+use crate::error::FftError;
+use crate::fft::{fft_complex, ifft_complex, transform_pow2};
+use crate::ntt::{mod_inv, ntt_convolve, MODULUS};
+
+// Polynomials are coefficient vectors in increasing order of degree, over
+// either field the crate has a fast convolution for: Complex64 through the
+// FFT, with rounding error, or ModP through the NTT, exactly.
+
+// Field is a coefficient field with an FFT-like product of polynomials.
+pub trait Field:
+    Copy + PartialEq + Debug + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self>
+{
+    fn zero() -> Self;
+
+    fn one() -> Self;
+
+    fn from_usize(n: usize) -> Self;
+
+    // inv returns the multiplicative inverse of a non-zero element
+    fn inv(self) -> Self;
+
+    fn is_zero(self) -> bool {
+        self == Self::zero()
+    }
+
+    // convolve returns the product of two non-empty polynomials
+    fn convolve(a: &[Self], b: &[Self]) -> Result<Vec<Self>, FftError>;
+}
+
+// Products with a factor shorter than this are cheaper directly.
+const DIRECT_LEN: usize = 16;
+
+fn direct<F: Field>(a: &[F], b: &[F]) -> Vec<F> {
+    let mut out = vec![F::zero(); a.len() + b.len() - 1];
+    for (i, &x) in a.iter().enumerate() {
+        for (j, &y) in b.iter().enumerate() {
+            out[i + j] = out[i + j] + x * y;
+        }
+    }
+    out
+}
+
+impl Field for Complex64 {
+    fn zero() -> Self {
+        Complex64::new(0_f64, 0_f64)
+    }
+
+    fn one() -> Self {
+        Complex64::new(1_f64, 0_f64)
+    }
+
+    fn from_usize(n: usize) -> Self {
+        Complex64::new(n as f64, 0_f64)
+    }
+
+    fn inv(self) -> Self {
+        Complex64::new(1_f64, 0_f64) / self
+    }
+
+    fn convolve(a: &[Self], b: &[Self]) -> Result<Vec<Self>, FftError> {
+        if a.len().min(b.len()) < DIRECT_LEN {
+            return Ok(direct(a, b));
+        }
+        let len = a.len() + b.len() - 1;
+        let n = len.next_power_of_two();
+        let pad = |x: &[Complex64]| {
+            let mut p = x.to_vec();
+            p.resize(n, Complex64::default());
+            fft_complex(&p)
+        };
+        let (fa, fb) = (pad(a)?, pad(b)?);
+        let product: Vec<Complex64> = fa.iter().zip(&fb).map(|(x, y)| x * y).collect();
+        let mut out = ifft_complex(&product)?;
+        out.truncate(len);
+        Ok(out)
+    }
+}
+
+// ModP is an element of GF(p) for the NTT modulus p = 998244353, always
+// reduced below p.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Hash)]
+pub struct ModP(u64);
+
+impl ModP {
+    pub fn new(v: u64) -> Self {
+        ModP(v % MODULUS)
+    }
+
+    pub fn value(self) -> u64 {
+        self.0
+    }
+}
+
+impl Add for ModP {
+    type Output = ModP;
+
+    fn add(self, other: ModP) -> ModP {
+        ModP((self.0 + other.0) % MODULUS)
+    }
+}
+
+impl Sub for ModP {
+    type Output = ModP;
+
+    fn sub(self, other: ModP) -> ModP {
+        ModP((self.0 + MODULUS - other.0) % MODULUS)
+    }
+}
+
+impl Mul for ModP {
+    type Output = ModP;
+
+    fn mul(self, other: ModP) -> ModP {
+        ModP(self.0 * other.0 % MODULUS)
+    }
+}
+
+impl Field for ModP {
+    fn zero() -> Self {
+        ModP(0)
+    }
+
+    fn one() -> Self {
+        ModP(1)
+    }
+
+    fn from_usize(n: usize) -> Self {
+        ModP::new(n as u64)
+    }
+
+    fn inv(self) -> Self {
+        ModP(mod_inv(self.0))
+    }
+
+    fn convolve(a: &[Self], b: &[Self]) -> Result<Vec<Self>, FftError> {
+        if a.len().min(b.len()) < DIRECT_LEN {
+            return Ok(direct(a, b));
+        }
+        let raw = |x: &[ModP]| x.iter().map(|v| v.0).collect::<Vec<u64>>();
+        Ok(ntt_convolve(&raw(a), &raw(b))?
+            .into_iter()
+            .map(ModP)
+            .collect())
+    }
+}
+
+// multiply returns the product of two polynomials
+pub fn multiply<F: Field>(a: &[F], b: &[F]) -> Result<Vec<F>, FftError> {
+    if a.is_empty() || b.is_empty() {
+        return Ok(Vec::new());
+    }
+    F::convolve(a, b)
+}
+
+// poly_mul returns the product of two real polynomials, i.e. the linear
+// convolution of a and b, of length a.len() + b.len() - 1. Both are
+// zero-padded to the next power of two at least that long, so the circular
+// product of the FFTs does not wrap around, and the result is truncated back.
+// It cannot fail, and a product with an empty polynomial is empty.
+pub fn poly_mul(a: &[f64], b: &[f64]) -> Vec<f64> {
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+    let len = a.len() + b.len() - 1;
+    let n = len.next_power_of_two();
+    let pad = |x: &[f64]| {
+        let mut p: Vec<Complex64> = x.iter().map(|&v| Complex64::new(v, 0_f64)).collect();
+        p.resize(n, Complex64::default());
+        transform_pow2(&mut p);
+        p
+    };
+    let (fa, fb) = (pad(a), pad(b));
+    // invert by conjugating, transforming forward, and dividing by n; the
+    // conjugate of the real result is itself
+    let mut product: Vec<Complex64> = fa.iter().zip(&fb).map(|(x, y)| (x * y).conj()).collect();
+    transform_pow2(&mut product);
+    product[..len].iter().map(|c| c.re / n as f64).collect()
+}
+
+// poly_inv_series returns the first n coefficients of the power series
+// 1 / a, which exists when a[0] is non-zero. Newton's iteration
+// g <- g (2 - a g) doubles the number of correct coefficients per step, so
+// the cost is a few products of length n.
+pub fn poly_inv_series<F: Field>(a: &[F], n: usize) -> Result<Vec<F>, FftError> {
+    match a.first() {
+        Some(a0) if !a0.is_zero() => {
+            let mut g = vec![a0.inv()];
+            let mut len = 1;
+            while len < n {
+                len = (2 * len).min(n);
+                let mut ag = multiply(&a[..a.len().min(len)], &g)?;
+                ag.truncate(len);
+                // 2 - a g
+                ag.iter_mut().for_each(|v| *v = F::zero() - *v);
+                ag[0] = ag[0] + F::from_usize(2);
+                g = multiply(&g, &ag)?;
+                g.truncate(len);
+            }
+            g.truncate(n);
+            Ok(g)
+        }
+        _ => Err(FftError::InvalidParameter(
+            "series must have a non-zero constant term",
+        )),
+    }
+}
+
+// poly_div divides a by b, returning the quotient and the remainder, of
+// degree below that of b. The quotient comes from the reversed polynomials,
+// rev(q) = rev(a) / rev(b) mod x^(deg a - deg b + 1), with the series
+// inverse of rev(b) from poly_inv_series, so division costs a constant
+// number of fast products.
+pub fn poly_div<F: Field>(a: &[F], b: &[F]) -> Result<(Vec<F>, Vec<F>), FftError> {
+    let b = trim(b);
+    if b.is_empty() {
+        return Err(FftError::InvalidParameter(
+            "division by the zero polynomial",
+        ));
+    }
+    let a = trim(a);
+    if a.len() < b.len() {
+        return Ok((Vec::new(), a.to_vec()));
+    }
+    let q_len = a.len() - b.len() + 1;
+    let reversed = |x: &[F]| x.iter().rev().copied().collect::<Vec<F>>();
+    let rb = reversed(b);
+    let inv = poly_inv_series(&rb[..rb.len().min(q_len)], q_len)?;
+    let ra = reversed(&a[a.len() - q_len..]);
+    let mut q = multiply(&ra, &inv)?;
+    q.truncate(q_len);
+    q.reverse();
+    let bq = multiply(b, &q)?;
+    let r: Vec<F> = (0..b.len() - 1).map(|k| a[k] - bq[k]).collect();
+    Ok((q, trim(&r).to_vec()))
+}
+
+// trim drops the zero coefficients of the highest degrees
+fn trim<F: Field>(a: &[F]) -> &[F] {
+    let len = a.iter().rposition(|c| !c.is_zero()).map_or(0, |i| i + 1);
+    &a[..len]
+}
+
+// remainder returns a modulo the monic polynomial m, by long division for
+// short operands and by poly_div otherwise
+fn remainder<F: Field>(a: &[F], m: &[F]) -> Result<Vec<F>, FftError> {
+    let d = m.len() - 1;
+    if a.len() > d + DIRECT_LEN && d >= DIRECT_LEN {
+        let (_, mut r) = poly_div(a, m)?;
+        r.resize(d, F::zero());
+        return Ok(r);
+    }
+    let mut r = a.to_vec();
+    while r.len() > d {
+        let lead = r[r.len() - 1];
+        let shift = r.len() - 1 - d;
+        for (k, &c) in m.iter().enumerate() {
+            r[shift + k] = r[shift + k] - lead * c;
+        }
+        r.pop();
+    }
+    Ok(r)
+}
+
+// evaluate_at returns the value of the polynomial at x (Horner's rule)
+pub fn evaluate_at<F: Field>(coeffs: &[F], x: F) -> F {
+    coeffs.iter().rev().fold(F::zero(), |acc, &c| acc * x + c)
+}
+
+// SubproductTree holds the products prod (x - x_i) over the points of every
+// node of a balanced binary tree whose leaves are the points. Evaluating a
+// polynomial at all points reduces it modulo the products down the tree, and
+// interpolating combines the Lagrange weights up the tree, both with
+// O(log n) levels of fast products. Over Complex64 the products of
+// clustered points have large coefficients and the remainders lose digits
+// as the degree grows; well spread points (e.g. on the unit circle) and
+// moderate degrees keep the error small, and ModP is exact.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubproductTree<F> {
+    root: Node<F>,
+    len: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Node<F> {
+    product: Vec<F>,
+    children: Option<Box<(Node<F>, Node<F>)>>,
+}
+
+impl<F: Field> Node<F> {
+    fn build(points: &[F]) -> Result<Self, FftError> {
+        if points.len() == 1 {
+            return Ok(Node {
+                product: vec![F::zero() - points[0], F::one()],
+                children: None,
+            });
+        }
+        let (left, right) = points.split_at(points.len() / 2);
+        let (left, right) = (Node::build(left)?, Node::build(right)?);
+        Ok(Node {
+            product: multiply(&left.product, &right.product)?,
+            children: Some(Box::new((left, right))),
+        })
+    }
+
+    fn evaluate(&self, f: &[F], out: &mut Vec<F>) -> Result<(), FftError> {
+        let r = remainder(f, &self.product)?;
+        match &self.children {
+            None => out.push(r.first().copied().unwrap_or(F::zero())),
+            Some(children) => {
+                children.0.evaluate(&r, out)?;
+                children.1.evaluate(&r, out)?;
+            }
+        }
+        Ok(())
+    }
+
+    // combine returns sum w_i prod_{j != i} (x - x_j) over the points of the
+    // node
+    fn combine(&self, weights: &[F]) -> Result<Vec<F>, FftError> {
+        match &self.children {
+            None => Ok(vec![weights[0]]),
+            Some(children) => {
+                let (left, right) = (&children.0, &children.1);
+                let split = left.product.len() - 1;
+                let a = multiply(&left.combine(&weights[..split])?, &right.product)?;
+                let b = multiply(&right.combine(&weights[split..])?, &left.product)?;
+                let mut sum = vec![F::zero(); a.len().max(b.len())];
+                for (i, v) in a.iter().enumerate() {
+                    sum[i] = sum[i] + *v;
+                }
+                for (i, v) in b.iter().enumerate() {
+                    sum[i] = sum[i] + *v;
+                }
+                Ok(sum)
+            }
+        }
+    }
+}
+
+impl<F: Field> SubproductTree<F> {
+    pub fn new(points: &[F]) -> Result<Self, FftError> {
+        if points.is_empty() {
+            return Err(FftError::InvalidParameter("no evaluation points"));
+        }
+        Ok(SubproductTree {
+            root: Node::build(points)?,
+            len: points.len(),
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    // product returns prod (x - x_i) over all points
+    pub fn product(&self) -> &[F] {
+        &self.root.product
+    }
+
+    // evaluate returns the polynomial at every point
+    pub fn evaluate(&self, coeffs: &[F]) -> Result<Vec<F>, FftError> {
+        let mut out = Vec::with_capacity(self.len);
+        self.root.evaluate(coeffs, &mut out)?;
+        Ok(out)
+    }
+
+    // interpolate returns the polynomial of degree below len() taking values
+    // at the points; the points must be distinct
+    pub fn interpolate(&self, values: &[F]) -> Result<Vec<F>, FftError> {
+        if values.len() != self.len {
+            return Err(FftError::LengthMismatch(self.len, values.len()));
+        }
+        // The Lagrange denominators are the derivative of the product at the
+        // points.
+        let m = self.product();
+        let derivative: Vec<F> = (1..m.len()).map(|k| m[k] * F::from_usize(k)).collect();
+        let denominators = self.evaluate(&derivative)?;
+        if denominators.iter().any(|d| d.is_zero()) {
+            return Err(FftError::InvalidParameter(
+                "interpolation points must be distinct",
+            ));
+        }
+        let weights: Vec<F> = values
+            .iter()
+            .zip(&denominators)
+            .map(|(&v, &d)| v * d.inv())
+            .collect();
+        self.root.combine(&weights)
+    }
+}
+
+// evaluate returns the polynomial at every point using a subproduct tree
+pub fn evaluate<F: Field>(coeffs: &[F], points: &[F]) -> Result<Vec<F>, FftError> {
+    SubproductTree::new(points)?.evaluate(coeffs)
+}
+
+// interpolate returns the polynomial of degree below points.len() through
+// (points[i], values[i])
+pub fn interpolate<F: Field>(points: &[F], values: &[F]) -> Result<Vec<F>, FftError> {
+    SubproductTree::new(points)?.interpolate(values)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_multipoint_mod_p_is_exact() {
+        let coeffs: Vec<ModP> = (0..100).map(|i| ModP::new(i * i * 31 + 5)).collect();
+        let points: Vec<ModP> = (0..100).map(|i| ModP::new(3 * i + 1)).collect();
+        let values = evaluate(&coeffs, &points).unwrap();
+        for (x, v) in points.iter().zip(&values) {
+            assert_eq!(*v, evaluate_at(&coeffs, *x));
+        }
+        assert_eq!(interpolate(&points, &values).unwrap(), coeffs);
+        let repeated = [ModP::new(1), ModP::new(2), ModP::new(1)];
+        assert!(matches!(
+            interpolate(&repeated, &repeated),
+            Err(FftError::InvalidParameter(_))
+        ));
+    }
+
+    #[test]
+    fn test_multipoint_complex() {
+        let values = crate::utils::generate_random_values();
+        let coeffs: Vec<Complex64> = (0..32)
+            .map(|i| Complex64::new(values[2 * i], values[2 * i + 1]))
+            .collect();
+        // Points on the unit circle keep the problem well conditioned.
+        let points: Vec<Complex64> = (0..32)
+            .map(|i| Complex64::from_polar(1.0, 0.1 + i as f64 * std::f64::consts::PI / 16.0))
+            .collect();
+        let tree = SubproductTree::new(&points).unwrap();
+        let y = tree.evaluate(&coeffs).unwrap();
+        for (x, v) in points.iter().zip(&y) {
+            assert!((v - evaluate_at(&coeffs, *x)).norm() < 1e-6);
+        }
+        for (a, b) in tree.interpolate(&y).unwrap().iter().zip(&coeffs) {
+            assert!((a - b).norm() < 1e-6);
+        }
+    }
+    #[test]
+    fn test_poly_mul() {
+        // (1 + 2x)(3 - x + x^2) = 3 + 5x - x^2 + 2x^3
+        let p = poly_mul(&[1_f64, 2_f64], &[3_f64, -1_f64, 1_f64]);
+        let expected = [3_f64, 5_f64, -1_f64, 2_f64];
+        assert_eq!(p.len(), 4);
+        assert!(p.iter().zip(&expected).all(|(a, b)| (a - b).abs() < 1e-12));
+
+        let a: Vec<f64> = (0..100).map(|i| ((i * 37) % 11) as f64).collect();
+        let b: Vec<f64> = (0..29).map(|i| ((i * 5) % 7) as f64 - 3_f64).collect();
+        let p = poly_mul(&a, &b);
+        assert_eq!(p.len(), 128);
+        for (k, v) in p.iter().enumerate() {
+            let direct: f64 = (0..=k)
+                .filter(|&i| i < a.len() && k - i < b.len())
+                .map(|i| a[i] * b[k - i])
+                .sum();
+            assert!((v - direct).abs() < 1e-9);
+        }
+        assert!(poly_mul(&[], &[1_f64]).is_empty());
+        assert!(poly_mul(&[1_f64], &[]).is_empty());
+    }
+
+    #[test]
+    fn test_inverse_series_and_division() {
+        // 1 / (1 - x) = 1 + x + x^2 + ...
+        let one_minus_x = [ModP::new(1), ModP::new(MODULUS - 1)];
+        assert_eq!(
+            poly_inv_series(&one_minus_x, 50).unwrap(),
+            vec![ModP::new(1); 50]
+        );
+        assert!(matches!(
+            poly_inv_series(&[ModP::new(0), ModP::new(1)], 4),
+            Err(FftError::InvalidParameter(_))
+        ));
+
+        let a: Vec<ModP> = (0..300).map(|i| ModP::new(i * 7 + 3)).collect();
+        let b: Vec<ModP> = (0..70).map(|i| ModP::new(i * i + 1)).collect();
+        let (q, r) = poly_div(&a, &b).unwrap();
+        assert_eq!(q.len(), 231);
+        assert!(r.len() < b.len());
+        let mut back = multiply(&b, &q).unwrap();
+        for (i, v) in r.iter().enumerate() {
+            back[i] = back[i] + *v;
+        }
+        assert_eq!(back, a);
+
+        let complex = |v: &[f64]| {
+            v.iter()
+                .map(|&x| Complex64::new(x, 0.0))
+                .collect::<Vec<_>>()
+        };
+        // x^2 - 1 = (x - 1)(x + 1)
+        let (q, r) = poly_div(&complex(&[-1.0, 0.0, 1.0]), &complex(&[-1.0, 1.0])).unwrap();
+        assert!((q[0] - Complex64::new(1.0, 0.0)).norm() < 1e-12);
+        assert!((q[1] - Complex64::new(1.0, 0.0)).norm() < 1e-12);
+        assert!(r.iter().all(|c| c.norm() < 1e-12));
+    }
+}
@@ -0,0 +1,262 @@
+use num::complex::Complex64;
+
+use crate::axis::FrequencyAxis;
+use crate::backend::{FftBackend, ScalarBackend};
+use crate::error::FftError;
+use crate::regrid::{regrid, Regrid};
+use crate::window::hann;
+
+// Psd is a one-sided power spectral density estimate: density[i] is the power
+// per Hz at frequencies[i], so integrating it over frequency gives the
+// variance of the signal.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Psd {
+    pub frequencies: Vec<f64>,
+    pub density: Vec<f64>,
+}
+
+impl Psd {
+    // regrid returns the estimate at new frequencies, e.g. to compare it with
+    // one of another resolution; Regrid::BandAverage preserves band power
+    pub fn regrid(&self, frequencies: &[f64], mode: Regrid) -> Result<Psd, FftError> {
+        Ok(Psd {
+            frequencies: frequencies.to_vec(),
+            density: regrid(&self.frequencies, &self.density, frequencies, mode)?,
+        })
+    }
+}
+
+// welch estimates the power spectral density of x with Welch's method:
+// Hann-windowed segments of segment_len samples taken every hop samples are
+// transformed, their squared magnitudes averaged and scaled to a one-sided
+// density in units^2 / Hz.
+pub fn welch(x: &[f64], sample_rate: f64, segment_len: usize, hop: usize) -> Result<Psd, FftError> {
+    welch_with(&ScalarBackend::default(), x, sample_rate, segment_len, hop)
+}
+
+// welch_with computes welch with the transforms of the given backend
+pub fn welch_with(
+    backend: &dyn FftBackend,
+    x: &[f64],
+    sample_rate: f64,
+    segment_len: usize,
+    hop: usize,
+) -> Result<Psd, FftError> {
+    if sample_rate <= 0_f64 {
+        return Err(FftError::InvalidParameter("sample rate must be positive"));
+    }
+    if hop == 0 {
+        return Err(FftError::InvalidParameter("hop must be greater than zero"));
+    }
+    if x.len() < segment_len || segment_len == 0 {
+        return Err(FftError::LengthMismatch(segment_len, x.len()));
+    }
+
+    let window = hann(segment_len);
+    let window_power: f64 = window.iter().map(|w| w * w).sum();
+    let half = segment_len / 2 + 1;
+    let plan = backend.plan(segment_len)?;
+    let mut spectrum = vec![Complex64::default(); half];
+    let mut density = vec![0_f64; half];
+    let mut segments = 0;
+    for start in (0..=x.len() - segment_len).step_by(hop) {
+        let segment: Vec<f64> = x[start..start + segment_len]
+            .iter()
+            .zip(window.iter())
+            .map(|(s, w)| s * w)
+            .collect();
+        plan.process_real(&segment, &mut spectrum)?;
+        for (d, c) in density.iter_mut().zip(spectrum.iter()) {
+            *d += c.norm_sqr();
+        }
+        segments += 1;
+    }
+
+    let scale = 1_f64 / (sample_rate * window_power * segments as f64);
+    for (k, d) in density.iter_mut().enumerate() {
+        // Fold the negative frequencies onto the positive ones.
+        let fold = if k == 0 || 2 * k == segment_len {
+            1_f64
+        } else {
+            2_f64
+        };
+        *d *= fold * scale;
+    }
+    Ok(Psd {
+        frequencies: FrequencyAxis::new(segment_len, sample_rate)?
+            .positive()
+            .collect(),
+        density,
+    })
+}
+
+// Band is a named frequency range in Hz, inclusive at both ends.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Band {
+    pub name: String,
+    pub low: f64,
+    pub high: f64,
+}
+
+impl Band {
+    pub fn new(name: &str, low: f64, high: f64) -> Self {
+        Band {
+            name: name.to_string(),
+            low,
+            high,
+        }
+    }
+}
+
+// eeg_bands returns the conventional EEG rhythm bands: delta (0.5-4 Hz),
+// theta (4-8 Hz), alpha (8-13 Hz), beta (13-30 Hz) and gamma (30-45 Hz)
+pub fn eeg_bands() -> Vec<Band> {
+    vec![
+        Band::new("delta", 0.5, 4.0),
+        Band::new("theta", 4.0, 8.0),
+        Band::new("alpha", 8.0, 13.0),
+        Band::new("beta", 13.0, 30.0),
+        Band::new("gamma", 30.0, 45.0),
+    ]
+}
+
+// BandPower is the integrated power of a band, absolute and relative to the
+// power of the whole spectrum.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BandPower {
+    pub name: String,
+    pub power: f64,
+    pub relative: f64,
+}
+
+// integrate applies the trapezoidal rule to the points of psd whose frequency
+// lies in [low, high]
+fn integrate(psd: &[f64], freqs: &[f64], low: f64, high: f64) -> f64 {
+    let points: Vec<(f64, f64)> = freqs
+        .iter()
+        .zip(psd.iter())
+        .filter(|(&f, _)| f >= low && f <= high)
+        .map(|(&f, &p)| (f, p))
+        .collect();
+    // fold from +0 rather than sum, whose empty value is -0
+    points.windows(2).fold(0_f64, |acc, w| {
+        acc + (w[1].0 - w[0].0) * (w[0].1 + w[1].1) / 2_f64
+    })
+}
+
+// band_power integrates a power spectral density (with frequencies in
+// increasing order) over every band. Integration uses the trapezoidal rule
+// on the spectrum points inside the band, so bands narrower than the
+// frequency resolution report zero power.
+pub fn band_power(psd: &[f64], freqs: &[f64], bands: &[Band]) -> Result<Vec<BandPower>, FftError> {
+    if psd.len() != freqs.len() {
+        return Err(FftError::LengthMismatch(freqs.len(), psd.len()));
+    }
+    if psd.is_empty() {
+        return Err(FftError::InvalidParameter("PSD must not be empty"));
+    }
+    if bands
+        .iter()
+        .any(|b| b.low > b.high || b.low.is_nan() || b.high.is_nan())
+    {
+        return Err(FftError::InvalidParameter(
+            "band edges must satisfy low <= high",
+        ));
+    }
+    let total = integrate(psd, freqs, f64::NEG_INFINITY, f64::INFINITY);
+    Ok(bands
+        .iter()
+        .map(|b| {
+            let power = integrate(psd, freqs, b.low, b.high);
+            BandPower {
+                name: b.name.clone(),
+                power,
+                relative: if total > 0_f64 { power / total } else { 0_f64 },
+            }
+        })
+        .collect())
+}
+
+// band_ratio returns the power ratio between two named bands, e.g. the
+// theta/beta ratio, or None if either band is missing or the denominator is
+// zero
+pub fn band_ratio(powers: &[BandPower], numerator: &str, denominator: &str) -> Option<f64> {
+    let find = |name: &str| powers.iter().find(|p| p.name == name).map(|p| p.power);
+    let (num, den) = (find(numerator)?, find(denominator)?);
+    if den > 0_f64 {
+        Some(num / den)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+    use std::f64::consts::PI;
+
+    #[test]
+    fn test_welch_integrates_to_variance() {
+        let mut rng = StdRng::seed_from_u64(4);
+        // Uniform noise in [-1, 1) has variance 1/3.
+        let x: Vec<f64> = (0..16384)
+            .map(|_| 2_f64 * rng.random::<f64>() - 1_f64)
+            .collect();
+        let psd = welch(&x, 100.0, 256, 128).unwrap();
+        assert_eq!(psd.density.len(), 129);
+        assert!((psd.frequencies[128] - 50.0).abs() < 1e-12);
+        let total = integrate(&psd.density, &psd.frequencies, 0.0, 50.0);
+        assert!((total - 1.0 / 3.0).abs() < 0.02, "total {}", total);
+
+        // A coarser grid of the same estimate integrates to the same power.
+        let coarse: Vec<f64> = (0..=25).map(|i| 2.0 * i as f64).collect();
+        let regridded = psd.regrid(&coarse, Regrid::BandAverage).unwrap();
+        let coarse_total = integrate(&regridded.density, &coarse, 0.0, 50.0);
+        assert!((coarse_total - total).abs() < 0.01 * total);
+    }
+
+    #[test]
+    fn test_eeg_band_power() {
+        let fs = 256_f64;
+        // A 10 Hz alpha rhythm with a weaker 20 Hz beta component.
+        let x: Vec<f64> = (0..8192)
+            .map(|i| {
+                let t = i as f64 / fs;
+                (2_f64 * PI * 10_f64 * t).sin() + 0.5 * (2_f64 * PI * 20_f64 * t).sin()
+            })
+            .collect();
+        let psd = welch(&x, fs, 512, 256).unwrap();
+        let powers = band_power(&psd.density, &psd.frequencies, &eeg_bands()).unwrap();
+        assert_eq!(powers.len(), 5);
+
+        let alpha = powers.iter().find(|p| p.name == "alpha").unwrap();
+        let beta = powers.iter().find(|p| p.name == "beta").unwrap();
+        // Sine power is amplitude^2 / 2.
+        assert!((alpha.power - 0.5).abs() < 0.02);
+        assert!((beta.power - 0.125).abs() < 0.01);
+        assert!(alpha.relative > 0.75 && alpha.relative < 0.85);
+
+        let ratio = band_ratio(&powers, "alpha", "beta").unwrap();
+        assert!((ratio - 4.0).abs() < 0.2);
+        assert!(band_ratio(&powers, "alpha", "kappa").is_none());
+    }
+
+    #[test]
+    fn test_band_power_invalid_input() {
+        assert!(matches!(
+            band_power(&[1.0; 3], &[0.0, 1.0], &eeg_bands()),
+            Err(FftError::LengthMismatch(2, 3))
+        ));
+        assert!(matches!(
+            band_power(&[1.0; 2], &[0.0, 1.0], &[Band::new("bad", 2.0, 1.0)]),
+            Err(FftError::InvalidParameter(_))
+        ));
+        assert!(matches!(
+            band_power(&[], &[], &eeg_bands()),
+            Err(FftError::InvalidParameter(_))
+        ));
+        let empty = band_power(&[1.0; 2], &[0.0, 1.0], &[Band::new("none", 5.0, 6.0)]).unwrap();
+        assert!(empty[0].power == 0.0 && empty[0].power.is_sign_positive());
+    }
+}
@@ -0,0 +1,263 @@
+use num::complex::Complex64;
+use std::ops::{Add, Mul, Sub};
+
+use crate::error::FftError;
+use crate::planner::{FftPlan, FftPlanner};
+
+// Quaternion is w + x i + y j + z k with Hamilton's product.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Quaternion {
+    pub w: f64,
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
+
+impl Quaternion {
+    pub fn new(w: f64, x: f64, y: f64, z: f64) -> Self {
+        Quaternion { w, x, y, z }
+    }
+
+    // pure returns the quaternion x i + y j + z k, e.g. an RGB pixel
+    pub fn pure(x: f64, y: f64, z: f64) -> Self {
+        Quaternion::new(0_f64, x, y, z)
+    }
+
+    pub fn conj(&self) -> Self {
+        Quaternion::new(self.w, -self.x, -self.y, -self.z)
+    }
+
+    pub fn norm_sqr(&self) -> f64 {
+        self.w * self.w + self.x * self.x + self.y * self.y + self.z * self.z
+    }
+
+    pub fn norm(&self) -> f64 {
+        self.norm_sqr().sqrt()
+    }
+
+    pub fn scale(&self, s: f64) -> Self {
+        Quaternion::new(self.w * s, self.x * s, self.y * s, self.z * s)
+    }
+
+    // dot returns the inner product of the vector parts
+    fn dot(&self, other: &Quaternion) -> f64 {
+        self.x * other.x + self.y * other.y + self.z * other.z
+    }
+}
+
+impl Add for Quaternion {
+    type Output = Quaternion;
+
+    fn add(self, o: Quaternion) -> Quaternion {
+        Quaternion::new(self.w + o.w, self.x + o.x, self.y + o.y, self.z + o.z)
+    }
+}
+
+impl Sub for Quaternion {
+    type Output = Quaternion;
+
+    fn sub(self, o: Quaternion) -> Quaternion {
+        Quaternion::new(self.w - o.w, self.x - o.x, self.y - o.y, self.z - o.z)
+    }
+}
+
+impl Mul for Quaternion {
+    type Output = Quaternion;
+
+    fn mul(self, o: Quaternion) -> Quaternion {
+        Quaternion::new(
+            self.w * o.w - self.x * o.x - self.y * o.y - self.z * o.z,
+            self.w * o.x + self.x * o.w + self.y * o.z - self.z * o.y,
+            self.w * o.y - self.x * o.z + self.y * o.w + self.z * o.x,
+            self.w * o.z + self.x * o.y - self.y * o.x + self.z * o.w,
+        )
+    }
+}
+
+// QuaternionFft is a left-sided quaternion Fourier transform of a fixed
+// length around a unit pure quaternion axis mu,
+// F[k] = sum_n exp(mu 2 pi k n / len) q[n], using the same exp(+) kernel as
+// fft. With nu a unit pure quaternion orthogonal to mu and xi = mu nu, every
+// sample splits as q = a + b nu with a and b in the complex plane spanned by
+// 1 and mu. Left multiplication by exp(mu theta) keeps that split, so the
+// transform reduces to two complex FFTs of a and b, computed with one shared
+// plan.
+#[derive(Debug, Clone)]
+pub struct QuaternionFft {
+    plan: FftPlan,
+    mu: Quaternion,
+    nu: Quaternion,
+    xi: Quaternion,
+}
+
+impl QuaternionFft {
+    // new plans a transform of length len around axis, which is normalized
+    // and must be a non-zero pure quaternion; the colour-image literature
+    // commonly uses the grey axis (i + j + k) / sqrt(3)
+    pub fn new(len: usize, axis: Quaternion) -> Result<Self, FftError> {
+        let norm = axis.norm();
+        if axis.w != 0_f64 || norm == 0_f64 || !norm.is_finite() {
+            return Err(FftError::InvalidParameter(
+                "axis must be a non-zero pure quaternion",
+            ));
+        }
+        let mu = axis.scale(1_f64 / norm);
+        // Any vector not parallel to mu gives an orthogonal direction.
+        let helper = if mu.x.abs() < 0.9 {
+            Quaternion::pure(1_f64, 0_f64, 0_f64)
+        } else {
+            Quaternion::pure(0_f64, 1_f64, 0_f64)
+        };
+        let nu = helper - mu.scale(helper.dot(&mu));
+        let nu = nu.scale(1_f64 / nu.norm());
+        let xi = mu * nu;
+        Ok(QuaternionFft {
+            plan: FftPlanner::new().plan(len)?,
+            mu,
+            nu,
+            xi,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.plan.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.plan.is_empty()
+    }
+
+    pub fn axis(&self) -> Quaternion {
+        self.mu
+    }
+
+    fn split(&self, q: &[Quaternion]) -> (Vec<Complex64>, Vec<Complex64>) {
+        q.iter()
+            .map(|s| {
+                (
+                    Complex64::new(s.w, s.dot(&self.mu)),
+                    Complex64::new(s.dot(&self.nu), s.dot(&self.xi)),
+                )
+            })
+            .unzip()
+    }
+
+    fn join(&self, a: &Complex64, b: &Complex64) -> Quaternion {
+        Quaternion::new(a.re, 0_f64, 0_f64, 0_f64)
+            + self.mu.scale(a.im)
+            + self.nu.scale(b.re)
+            + self.xi.scale(b.im)
+    }
+
+    // process computes the forward transform of buffer in place
+    pub fn process(&self, buffer: &mut [Quaternion]) -> Result<(), FftError> {
+        self.transform(buffer, false)
+    }
+
+    // process_inverse computes the inverse transform of buffer in place,
+    // including the 1 / len scaling
+    pub fn process_inverse(&self, buffer: &mut [Quaternion]) -> Result<(), FftError> {
+        self.transform(buffer, true)
+    }
+
+    fn transform(&self, buffer: &mut [Quaternion], inverse: bool) -> Result<(), FftError> {
+        if buffer.len() != self.len() {
+            return Err(FftError::LengthMismatch(self.len(), buffer.len()));
+        }
+        let (mut a, mut b) = self.split(buffer);
+        for part in [&mut a, &mut b] {
+            if inverse {
+                part.iter_mut().for_each(|c| *c = c.conj());
+            }
+            self.plan.process(part)?;
+            if inverse {
+                let scale = 1_f64 / self.len() as f64;
+                part.iter_mut().for_each(|c| *c = c.conj() * scale);
+            }
+        }
+        for (q, (a, b)) in buffer.iter_mut().zip(a.iter().zip(b.iter())) {
+            *q = self.join(a, b);
+        }
+        Ok(())
+    }
+}
+
+// qfft returns the left-sided quaternion Fourier transform of x around axis
+pub fn qfft(x: &[Quaternion], axis: Quaternion) -> Result<Vec<Quaternion>, FftError> {
+    let mut out = x.to_vec();
+    QuaternionFft::new(x.len(), axis)?.process(&mut out)?;
+    Ok(out)
+}
+
+// iqfft inverts qfft
+pub fn iqfft(x: &[Quaternion], axis: Quaternion) -> Result<Vec<Quaternion>, FftError> {
+    let mut out = x.to_vec();
+    QuaternionFft::new(x.len(), axis)?.process_inverse(&mut out)?;
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::PI;
+
+    fn signal(len: usize) -> Vec<Quaternion> {
+        let v = crate::utils::generate_random_values();
+        (0..len)
+            .map(|i| Quaternion::new(v[4 * i], v[4 * i + 1], v[4 * i + 2], v[4 * i + 3]))
+            .collect()
+    }
+
+    fn grey() -> Quaternion {
+        Quaternion::pure(1.0, 1.0, 1.0)
+    }
+
+    #[test]
+    fn test_qfft_matches_definition() {
+        for len in [16, 12] {
+            let x = signal(len);
+            let mu = grey().scale(1_f64 / 3_f64.sqrt());
+            let y = qfft(&x, grey()).unwrap();
+            for (k, yk) in y.iter().enumerate() {
+                let expected = x
+                    .iter()
+                    .enumerate()
+                    .fold(Quaternion::default(), |acc, (n, q)| {
+                        let theta = 2_f64 * PI * (k * n) as f64 / len as f64;
+                        let e = Quaternion::new(theta.cos(), 0.0, 0.0, 0.0) + mu.scale(theta.sin());
+                        acc + e * *q
+                    });
+                assert!((*yk - expected).norm() < 1e-9, "length {} bin {}", len, k);
+            }
+        }
+    }
+
+    #[test]
+    fn test_qfft_round_trip_and_complex_case() {
+        let x = signal(64);
+        let y = iqfft(&qfft(&x, grey()).unwrap(), grey()).unwrap();
+        for (a, b) in x.iter().zip(y.iter()) {
+            assert!((*a - *b).norm() < 1e-9);
+        }
+
+        // Around the i axis, the 1 and i parts transform like a complex FFT.
+        let y = qfft(&x, Quaternion::pure(1.0, 0.0, 0.0)).unwrap();
+        let z: Vec<Complex64> = x.iter().map(|q| Complex64::new(q.w, q.x)).collect();
+        let expected = crate::fft::fft_complex(&z).unwrap();
+        for (q, c) in y.iter().zip(expected.iter()) {
+            assert!((q.w - c.re).abs() < 1e-9 && (q.x - c.im).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_qfft_invalid_input() {
+        assert!(matches!(
+            QuaternionFft::new(8, Quaternion::new(1.0, 0.0, 0.0, 0.0)),
+            Err(FftError::InvalidParameter(_))
+        ));
+        assert!(matches!(
+            qfft(&[], grey()),
+            Err(FftError::InvalidParameter(_))
+        ));
+    }
+}
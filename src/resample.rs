@@ -0,0 +1,321 @@
+use std::f64::consts::PI;
+
+use crate::error::FftError;
+use crate::fft::{fft, ifft};
+use crate::window::{kaiser_from_spec, kaiser_value};
+
+// Width of the transition band as a fraction of the lower of the two Nyquist
+// frequencies; the passband ends TRANSITION / 2 below it and the stopband
+// starts TRANSITION / 2 above it.
+const TRANSITION: f64 = 0.1;
+
+// Number of kernel samples per input sample in the polyphase table. Kernel
+// values between two phases are linearly interpolated, so the rate ratio need
+// not be rational.
+const PHASES: usize = 256;
+
+// Resampler converts a stream of samples by an arbitrary rate ratio
+// (output rate / input rate) with a Kaiser-windowed sinc kernel. The kernel is
+// tabulated once at PHASES points per input sample; for every output sample
+// the taps are read from the table at the fractional position of that sample,
+// which makes the same resampler serve rational and irrational ratios alike.
+// Output sample j lies at input time j / ratio, independently of how the
+// input is split into blocks.
+#[derive(Debug, Clone)]
+pub struct Resampler {
+    ratio: f64,
+    half_width: usize,
+    table: Vec<f64>,
+    buffer: Vec<f64>,
+    // Input index of buffer[0]; negative while the initial zeros are held.
+    offset: isize,
+    consumed: usize,
+    produced: usize,
+}
+
+impl Resampler {
+    // new returns a resampler for the given ratio whose stopband is attenuated
+    // by at least attenuation_db dB
+    pub fn new(ratio: f64, attenuation_db: f64) -> Result<Self, FftError> {
+        if !(ratio > 0_f64 && ratio.is_finite()) {
+            return Err(FftError::InvalidParameter(
+                "ratio must be positive and finite",
+            ));
+        }
+        if !(attenuation_db > 0_f64 && attenuation_db.is_finite()) {
+            return Err(FftError::InvalidParameter(
+                "stopband attenuation must be positive",
+            ));
+        }
+
+        let band = ratio.min(1_f64);
+        // Cutoff relative to the input Nyquist frequency.
+        let cutoff = band * (1_f64 - TRANSITION / 2_f64);
+        // The transition is TRANSITION * band / 2 cycles per input sample.
+        let spec = kaiser_from_spec(attenuation_db, TRANSITION * band / 2_f64)?;
+        let half_width = (spec.len / 2).max(1);
+        let beta = spec.beta;
+        let table = (0..=half_width * PHASES)
+            .map(|i| {
+                let x = i as f64 / PHASES as f64;
+                let u = cutoff * x;
+                let sinc = if u == 0_f64 {
+                    1_f64
+                } else {
+                    (PI * u).sin() / (PI * u)
+                };
+                cutoff * sinc * kaiser_value(x / half_width as f64, beta)
+            })
+            .collect();
+
+        Ok(Resampler {
+            ratio,
+            half_width,
+            table,
+            buffer: vec![0_f64; half_width],
+            offset: -(half_width as isize),
+            consumed: 0,
+            produced: 0,
+        })
+    }
+
+    pub fn ratio(&self) -> f64 {
+        self.ratio
+    }
+
+    // latency returns the number of input samples the resampler must look
+    // ahead before it can emit an output sample
+    pub fn latency(&self) -> usize {
+        self.half_width
+    }
+
+    // kernel returns the interpolated kernel value at a distance of x input
+    // samples
+    fn kernel(&self, x: f64) -> f64 {
+        let pos = x.abs() * PHASES as f64;
+        let i = pos as usize;
+        if i + 1 >= self.table.len() {
+            return 0_f64;
+        }
+        let frac = pos - i as f64;
+        self.table[i] + frac * (self.table[i + 1] - self.table[i])
+    }
+
+    // emit produces output samples while the buffered input covers their
+    // kernel support, stopping at input time limit
+    fn emit(&mut self, limit: f64, out: &mut Vec<f64>) {
+        let hw = self.half_width as isize;
+        loop {
+            let t = self.produced as f64 / self.ratio;
+            let centre = t.floor() as isize;
+            if t >= limit || centre + hw >= self.offset + self.buffer.len() as isize {
+                break;
+            }
+            let value: f64 = (centre - hw + 1..=centre + hw)
+                .map(|i| self.buffer[(i - self.offset) as usize] * self.kernel(t - i as f64))
+                .sum();
+            out.push(value);
+            self.produced += 1;
+        }
+        // Drop the input no later output sample can reach.
+        let next = (self.produced as f64 / self.ratio).floor() as isize;
+        let drop = (next - hw + 1 - self.offset).clamp(0, self.buffer.len() as isize);
+        self.buffer.drain(..drop as usize);
+        self.offset += drop;
+    }
+
+    // process feeds a block of input and returns the output samples that
+    // became available; the last latency() input samples are held back until
+    // more input arrives or finish is called
+    pub fn process(&mut self, block: &[f64]) -> Vec<f64> {
+        self.buffer.extend_from_slice(block);
+        self.consumed += block.len();
+        let mut out = Vec::with_capacity((block.len() as f64 * self.ratio).ceil() as usize + 1);
+        self.emit(f64::INFINITY, &mut out);
+        out
+    }
+
+    // finish flushes the held-back input, treating the signal as zero after
+    // its end, and returns the remaining output samples up to the end of the
+    // input
+    pub fn finish(&mut self) -> Vec<f64> {
+        self.buffer
+            .extend(std::iter::repeat_n(0_f64, self.half_width + 1));
+        let mut out = Vec::new();
+        self.emit(self.consumed as f64, &mut out);
+        out
+    }
+
+    // reset clears the stream state, keeping the kernel
+    pub fn reset(&mut self) {
+        self.buffer = vec![0_f64; self.half_width];
+        self.offset = -(self.half_width as isize);
+        self.consumed = 0;
+        self.produced = 0;
+    }
+}
+
+// resample converts the whole of x by the rate ratio (output rate / input
+// rate) and returns ceil(x.len() * ratio) samples
+pub fn resample(x: &[f64], ratio: f64, attenuation_db: f64) -> Result<Vec<f64>, FftError> {
+    let mut resampler = Resampler::new(ratio, attenuation_db)?;
+    let mut out = resampler.process(x);
+    out.extend(resampler.finish());
+    Ok(out)
+}
+
+// Rolloff is the shape of the anti-aliasing low-pass used by decimate_with.
+// The filter always reaches zero at the output Nyquist frequency; BrickWall
+// jumps there from one, RaisedCosine falls off along a half cosine that
+// starts width (a fraction of the output Nyquist frequency) below it. A
+// gentler rolloff rings less in time at the cost of passband width.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Rolloff {
+    BrickWall,
+    RaisedCosine { width: f64 },
+}
+
+impl Default for Rolloff {
+    fn default() -> Self {
+        Rolloff::RaisedCosine { width: 0.1 }
+    }
+}
+
+// decimate keeps every factor-th sample of x after removing, in the
+// frequency domain, everything above the output Nyquist frequency, using the
+// default raised-cosine rolloff
+pub fn decimate(x: &[f64], factor: usize) -> Result<Vec<f64>, FftError> {
+    decimate_with(x, factor, Rolloff::default())
+}
+
+// decimate_with decimates x by factor with the given rolloff. x is zero
+// padded to at least twice its length before filtering so the filter
+// response does not wrap around from one end of the signal to the other.
+pub fn decimate_with(x: &[f64], factor: usize, rolloff: Rolloff) -> Result<Vec<f64>, FftError> {
+    if factor == 0 {
+        return Err(FftError::InvalidParameter(
+            "decimation factor must be greater than zero",
+        ));
+    }
+    if let Rolloff::RaisedCosine { width } = rolloff {
+        if !(width > 0_f64 && width <= 1_f64) {
+            return Err(FftError::InvalidParameter(
+                "rolloff width must lie in (0, 1]",
+            ));
+        }
+    }
+    if factor == 1 || x.is_empty() {
+        return Ok(x.to_vec());
+    }
+
+    let n = (2 * x.len()).next_power_of_two();
+    let mut padded = vec![0_f64; n];
+    padded[..x.len()].copy_from_slice(x);
+    let mut spectrum = fft(&padded)?;
+
+    let nyquist = 0.5 / factor as f64;
+    for (k, c) in spectrum.iter_mut().enumerate() {
+        let f = k.min(n - k) as f64 / n as f64;
+        let gain = match rolloff {
+            _ if f >= nyquist => 0_f64,
+            Rolloff::BrickWall => 1_f64,
+            Rolloff::RaisedCosine { width } => {
+                let start = nyquist * (1_f64 - width);
+                if f <= start {
+                    1_f64
+                } else {
+                    0.5 * (1_f64 + (PI * (f - start) / (nyquist * width)).cos())
+                }
+            }
+        };
+        *c *= gain;
+    }
+
+    let filtered = ifft(&spectrum)?;
+    Ok(filtered[..x.len()]
+        .iter()
+        .step_by(factor)
+        .cloned()
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tone(len: usize, f: f64, fs: f64) -> Vec<f64> {
+        (0..len)
+            .map(|i| (2_f64 * PI * f * i as f64 / fs).sin())
+            .collect()
+    }
+
+    #[test]
+    fn test_resample_tone_arbitrary_ratios() {
+        let x = tone(4000, 1000.0, 44100.0);
+        for ratio in [48000.0 / 44100.0, 0.5, PI / 3.0] {
+            let y = resample(&x, ratio, 80.0).unwrap();
+            assert_eq!(y.len(), (4000_f64 * ratio).ceil() as usize);
+            // Away from the edges the output is the tone at the new rate.
+            let fs = 44100.0 * ratio;
+            let skip = 200;
+            for (j, v) in y.iter().enumerate().take(y.len() - skip).skip(skip) {
+                let expected = (2_f64 * PI * 1000.0 * j as f64 / fs).sin();
+                assert!((v - expected).abs() < 1e-3, "ratio {} at {}", ratio, j);
+            }
+        }
+    }
+
+    #[test]
+    fn test_streaming_matches_one_shot() {
+        let x = crate::utils::generate_random_values();
+        let expected = resample(&x, 0.73, 60.0).unwrap();
+        let mut r = Resampler::new(0.73, 60.0).unwrap();
+        let mut y = Vec::new();
+        for block in x.chunks(100) {
+            y.extend(r.process(block));
+        }
+        y.extend(r.finish());
+        assert_eq!(y.len(), expected.len());
+        for (a, b) in y.iter().zip(expected.iter()) {
+            assert!((a - b).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_decimate_keeps_passband_and_removes_aliases() {
+        let fs = 8000_f64;
+        let low = tone(4096, 300.0, fs);
+        let high = tone(4096, 3000.0, fs);
+        let x: Vec<f64> = low.iter().zip(high.iter()).map(|(a, b)| a + b).collect();
+        for rolloff in [Rolloff::BrickWall, Rolloff::default()] {
+            let y = decimate_with(&x, 4, rolloff).unwrap();
+            assert_eq!(y.len(), 1024);
+            // 3 kHz would alias to 1 kHz at the 2 kHz output rate.
+            for (j, v) in y.iter().enumerate().take(900).skip(124) {
+                assert!((v - low[4 * j]).abs() < 0.02, "{:?} at {}", rolloff, j);
+            }
+        }
+        assert_eq!(decimate(&x, 1).unwrap(), x);
+        assert!(matches!(
+            decimate(&x, 0),
+            Err(FftError::InvalidParameter(_))
+        ));
+        assert!(matches!(
+            decimate_with(&x, 2, Rolloff::RaisedCosine { width: 0.0 }),
+            Err(FftError::InvalidParameter(_))
+        ));
+    }
+
+    #[test]
+    fn test_downsampling_rejects_aliases() {
+        // 15 kHz lies above the 8 kHz Nyquist frequency of the output.
+        let x = tone(8000, 15000.0, 32000.0);
+        let y = resample(&x, 0.5, 80.0).unwrap();
+        let rms = (y[500..3500].iter().map(|v| v * v).sum::<f64>() / 3000.0).sqrt();
+        assert!(rms < 1e-3, "rms {}", rms);
+        assert!(matches!(
+            Resampler::new(0.0, 80.0),
+            Err(FftError::InvalidParameter(_))
+        ));
+    }
+}
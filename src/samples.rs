@@ -0,0 +1,344 @@
+use alloc::borrow::Cow;
+use alloc::vec::Vec;
+use num::complex::Complex64;
+
+// IntoSamples converts real samples into a slice of T, f64 unless a
+// transform runs in another precision. References to anything that is
+// AsRef<[T]> (slices, Vec, arrays, boxed slices) are borrowed; a Vec passed
+// by value and iterators, e.g. x.iter().map(|v| v * 2.0), are collected.
+// K only tells these impls apart, which would otherwise overlap, and is
+// always inferred.
+pub trait IntoSamples<'a, T: Clone + 'a, K> {
+    fn into_samples(self) -> Cow<'a, [T]>;
+}
+
+// The kinds of IntoSamples impls.
+pub mod kind {
+    #[derive(Debug)]
+    pub enum Borrowed {}
+
+    #[derive(Debug)]
+    pub enum Owned {}
+
+    #[derive(Debug)]
+    pub enum Iterated {}
+}
+
+// IntoComplexSamples converts the usual containers of complex samples into a
+// slice of Complex64. Pairs (re, im) and arrays [re, im] are accepted as well
+// and copied; Complex64 slices are borrowed.
+pub trait IntoComplexSamples<'a> {
+    fn into_complex_samples(self) -> Cow<'a, [Complex64]>;
+}
+
+// Samples collects anything iterable, e.g. a VecDeque, of f64 or Complex64
+// values at an API boundary taking IntoSamples or IntoComplexSamples.
+#[derive(Debug, Clone)]
+pub struct Samples<I>(pub I);
+
+impl<'a, T: Clone + 'a, A: AsRef<[T]> + ?Sized> IntoSamples<'a, T, kind::Borrowed> for &'a A {
+    fn into_samples(self) -> Cow<'a, [T]> {
+        Cow::Borrowed(self.as_ref())
+    }
+}
+
+impl<'a, T: Clone + 'a> IntoSamples<'a, T, kind::Owned> for Vec<T> {
+    fn into_samples(self) -> Cow<'a, [T]> {
+        Cow::Owned(self)
+    }
+}
+
+impl<'a, T: Clone + 'a, I: Iterator<Item = T>> IntoSamples<'a, T, kind::Iterated> for I {
+    fn into_samples(self) -> Cow<'a, [T]> {
+        Cow::Owned(self.collect())
+    }
+}
+
+impl<'a, T: Clone + 'a, I: IntoIterator<Item = T>> IntoSamples<'a, T, kind::Owned> for Samples<I> {
+    fn into_samples(self) -> Cow<'a, [T]> {
+        Cow::Owned(self.0.into_iter().collect())
+    }
+}
+
+impl<'a> IntoComplexSamples<'a> for &'a [Complex64] {
+    fn into_complex_samples(self) -> Cow<'a, [Complex64]> {
+        Cow::Borrowed(self)
+    }
+}
+
+impl<'a> IntoComplexSamples<'a> for &'a Vec<Complex64> {
+    fn into_complex_samples(self) -> Cow<'a, [Complex64]> {
+        Cow::Borrowed(self)
+    }
+}
+
+impl<'a, const N: usize> IntoComplexSamples<'a> for &'a [Complex64; N] {
+    fn into_complex_samples(self) -> Cow<'a, [Complex64]> {
+        Cow::Borrowed(self)
+    }
+}
+
+impl<'a> IntoComplexSamples<'a> for Vec<Complex64> {
+    fn into_complex_samples(self) -> Cow<'a, [Complex64]> {
+        Cow::Owned(self)
+    }
+}
+
+impl<'a> IntoComplexSamples<'a> for &'a [(f64, f64)] {
+    fn into_complex_samples(self) -> Cow<'a, [Complex64]> {
+        Cow::Owned(
+            self.iter()
+                .map(|&(re, im)| Complex64::new(re, im))
+                .collect(),
+        )
+    }
+}
+
+impl<'a> IntoComplexSamples<'a> for &'a Vec<(f64, f64)> {
+    fn into_complex_samples(self) -> Cow<'a, [Complex64]> {
+        self.as_slice().into_complex_samples()
+    }
+}
+
+impl<'a> IntoComplexSamples<'a> for &'a [[f64; 2]] {
+    fn into_complex_samples(self) -> Cow<'a, [Complex64]> {
+        Cow::Owned(
+            self.iter()
+                .map(|&[re, im]| Complex64::new(re, im))
+                .collect(),
+        )
+    }
+}
+
+impl<'a> IntoComplexSamples<'a> for &'a Vec<[f64; 2]> {
+    fn into_complex_samples(self) -> Cow<'a, [Complex64]> {
+        self.as_slice().into_complex_samples()
+    }
+}
+
+impl<'a, I: IntoIterator<Item = Complex64>> IntoComplexSamples<'a> for Samples<I> {
+    fn into_complex_samples(self) -> Cow<'a, [Complex64]> {
+        Cow::Owned(self.0.into_iter().collect())
+    }
+}
+
+// Sample is a PCM sample format convertible to f64 in [-1, 1), following the
+// conventions of the Rust audio ecosystem (dasp, cpal, hound): signed
+// integers are divided by 2^(bits - 1), unsigned ones are offset by their
+// midpoint first, and floats are taken as they are.
+pub trait Sample: Copy {
+    fn to_f64(self) -> f64;
+}
+
+macro_rules! signed_sample {
+    ($($t:ty),*) => {$(
+        impl Sample for $t {
+            fn to_f64(self) -> f64 {
+                self as f64 / -(<$t>::MIN as f64)
+            }
+        }
+    )*};
+}
+
+macro_rules! unsigned_sample {
+    ($($t:ty),*) => {$(
+        impl Sample for $t {
+            fn to_f64(self) -> f64 {
+                let mid = (<$t>::MAX / 2 + 1) as f64;
+                (self as f64 - mid) / mid
+            }
+        }
+    )*};
+}
+
+signed_sample!(i8, i16, i32, i64);
+unsigned_sample!(u8, u16, u32);
+
+impl Sample for f32 {
+    fn to_f64(self) -> f64 {
+        self as f64
+    }
+}
+
+impl Sample for f64 {
+    fn to_f64(self) -> f64 {
+        self
+    }
+}
+
+// Frame is the set of simultaneous samples of all channels; a lone sample is
+// a mono frame and [S; N] an N-channel one.
+pub trait Frame {
+    fn channels(&self) -> usize;
+
+    // channel returns channel c as f64
+    fn channel(&self, c: usize) -> Option<f64>;
+
+    // mono returns the average of all channels
+    fn mono(&self) -> f64 {
+        let n = self.channels();
+        (0..n).filter_map(|c| self.channel(c)).sum::<f64>() / n as f64
+    }
+}
+
+macro_rules! mono_frame {
+    ($($t:ty),*) => {$(
+        impl Frame for $t {
+            fn channels(&self) -> usize {
+                1
+            }
+
+            fn channel(&self, c: usize) -> Option<f64> {
+                (c == 0).then(|| self.to_f64())
+            }
+        }
+    )*};
+}
+
+mono_frame!(i8, i16, i32, i64, u8, u16, u32, f32, f64);
+
+impl<S: Sample, const N: usize> Frame for [S; N] {
+    fn channels(&self) -> usize {
+        N
+    }
+
+    fn channel(&self, c: usize) -> Option<f64> {
+        self.get(c).map(|s| s.to_f64())
+    }
+}
+
+impl<F: Frame> Frame for &F {
+    fn channels(&self) -> usize {
+        (*self).channels()
+    }
+
+    fn channel(&self, c: usize) -> Option<f64> {
+        (*self).channel(c)
+    }
+}
+
+// Pcm converts PCM frames of any sample format, e.g. a &[i16] buffer, a
+// &[[f32; 2]] stereo buffer or an iterator of frames from an audio signal,
+// into f64 samples, mixing multi-channel frames down to mono.
+#[derive(Debug, Clone)]
+pub struct Pcm<I>(pub I);
+
+impl<'a, I> IntoSamples<'a, f64, kind::Owned> for Pcm<I>
+where
+    I: IntoIterator,
+    I::Item: Frame,
+{
+    fn into_samples(self) -> Cow<'a, [f64]> {
+        Cow::Owned(self.0.into_iter().map(|f| f.mono()).collect())
+    }
+}
+
+// Dasp converts dasp frames into f64 samples with dasp's own sample
+// conversions, mixing multi-channel frames down to mono. It takes iterators
+// of frames, such as buffer.iter().copied() over a &[[i16; 2]] buffer or
+// signal.take(n) over a dasp_signal::Signal.
+#[cfg(feature = "dasp")]
+#[derive(Debug, Clone)]
+pub struct Dasp<I>(pub I);
+
+#[cfg(feature = "dasp")]
+impl<'a, I> IntoSamples<'a, f64, kind::Owned> for Dasp<I>
+where
+    I: IntoIterator,
+    I::Item: dasp_frame::Frame,
+    <I::Item as dasp_frame::Frame>::Sample: dasp_sample::ToSample<f64>,
+{
+    fn into_samples(self) -> Cow<'a, [f64]> {
+        use dasp_frame::Frame;
+        use dasp_sample::Sample;
+
+        let channels = <I::Item as Frame>::CHANNELS as f64;
+        Cow::Owned(
+            self.0
+                .into_iter()
+                .map(|f| f.channels().map(|s| s.to_sample::<f64>()).sum::<f64>() / channels)
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sum<'a, K>(x: impl IntoSamples<'a, f64, K>) -> f64 {
+        x.into_samples().iter().sum()
+    }
+
+    fn sum_complex<'a>(x: impl IntoComplexSamples<'a>) -> Complex64 {
+        x.into_complex_samples().iter().sum()
+    }
+
+    #[test]
+    fn test_into_samples() {
+        let v = vec![1.0, 2.0, 3.0];
+        assert_eq!(sum(&v), 6.0);
+        assert_eq!(sum(&v[1..]), 5.0);
+        assert_eq!(sum(&[1.0, 1.0]), 2.0);
+        assert_eq!(sum(v.iter().map(|x| x * 2.0)), 12.0);
+        assert_eq!(sum((1..4).map(f64::from)), 6.0);
+        let boxed: alloc::boxed::Box<[f64]> = v.clone().into_boxed_slice();
+        assert_eq!(sum(&boxed), 6.0);
+        let deque: std::collections::VecDeque<f64> = v.iter().copied().collect();
+        assert_eq!(sum(Samples(deque)), 6.0);
+        assert!(matches!(
+            IntoSamples::<f64, _>::into_samples(&v),
+            Cow::Borrowed(_)
+        ));
+        assert_eq!(sum(v), 6.0);
+    }
+
+    #[test]
+    fn test_pcm_formats() {
+        assert_eq!(sum(Pcm(&[i16::MIN, 16384, 0])), -0.5);
+        assert_eq!(sum(Pcm(&[0_u8, 128, 192])), -0.5);
+        assert_eq!(i32::MIN.to_f64(), -1.0);
+        assert_eq!(0.25_f32.to_f64(), 0.25);
+        let stereo = [[i16::MAX, i16::MIN], [16384, 16384]];
+        let mono = Pcm(&stereo).into_samples();
+        assert!(mono[0].abs() < 1e-4);
+        assert_eq!(mono[1], 0.5);
+        assert_eq!(stereo[0].channel(1), Some(-1.0));
+        assert_eq!(stereo[0].channel(2), None);
+    }
+
+    #[cfg(feature = "dasp")]
+    #[test]
+    fn test_dasp_frames_and_signals() {
+        use dasp_signal::Signal;
+
+        let stereo = [[i16::MAX, i16::MIN], [16384, 16384], [-8192, 0]];
+        let dasp = Dasp(stereo.iter().copied()).into_samples();
+        let pcm = Pcm(&stereo).into_samples();
+        assert!(dasp
+            .iter()
+            .zip(pcm.iter())
+            .all(|(a, b)| (a - b).abs() < 1e-4));
+        assert_eq!(sum(Dasp([0_u8, 128, 192])), -0.5);
+
+        // An eight-sample period of a sine lands in bin 1.
+        let sine = dasp_signal::rate(8.0).const_hz(1.0).sine();
+        let spectrum = crate::fft::fft(Dasp(sine.take(8))).unwrap();
+        assert!((spectrum[1].norm() - 4.0).abs() < 1e-9);
+        assert!(spectrum[2].norm() < 1e-9);
+    }
+
+    #[test]
+    fn test_into_complex_samples() {
+        let expected = Complex64::new(4.0, 6.0);
+        assert_eq!(sum_complex(&[(1.0, 2.0), (3.0, 4.0)][..]), expected);
+        assert_eq!(sum_complex(&vec![[1.0, 2.0], [3.0, 4.0]]), expected);
+        let c = vec![Complex64::new(1.0, 2.0), Complex64::new(3.0, 4.0)];
+        assert_eq!(sum_complex(&c), expected);
+        assert_eq!(sum_complex(Samples(c.iter().cloned())), expected);
+        assert!(matches!(
+            c.as_slice().into_complex_samples(),
+            Cow::Borrowed(_)
+        ));
+    }
+}
@@ -0,0 +1,139 @@
+use num::complex::Complex64;
+
+use crate::error::FftError;
+use crate::planner::{FftPlan, FftPlanner};
+
+// Segments yields the spectra of consecutive segments of a sample iterator,
+// one at a time: segment_len samples starting every hop samples, multiplied
+// by the window if one is given. Only one segment of samples is held, so the
+// signal can be far longer than memory, e.g. read from a file or a device on
+// demand. When the input ends, samples not yet covered by a segment are
+// zero-padded into one last segment.
+#[derive(Debug)]
+pub struct Segments<I> {
+    samples: I,
+    window: Option<Vec<f64>>,
+    segment_len: usize,
+    hop: usize,
+    plan: FftPlan,
+    buffer: Vec<f64>,
+    // samples at the start of buffer that an emitted segment already covered
+    covered: usize,
+    done: bool,
+}
+
+// segmented returns the lazy iterator over the segment spectra of samples
+pub fn segmented<I: IntoIterator<Item = f64>>(
+    samples: I,
+    segment_len: usize,
+    hop: usize,
+    window: Option<Vec<f64>>,
+) -> Result<Segments<I::IntoIter>, FftError> {
+    if hop == 0 || hop > segment_len {
+        return Err(FftError::InvalidParameter(
+            "hop must be between 1 and the segment length",
+        ));
+    }
+    if let Some(w) = &window {
+        if w.len() != segment_len {
+            return Err(FftError::LengthMismatch(segment_len, w.len()));
+        }
+    }
+    Ok(Segments {
+        samples: samples.into_iter(),
+        window,
+        segment_len,
+        hop,
+        plan: FftPlanner::new().plan(segment_len)?,
+        buffer: Vec::with_capacity(segment_len),
+        covered: 0,
+        done: false,
+    })
+}
+
+impl<I> Segments<I> {
+    fn spectrum(&self) -> Vec<Complex64> {
+        let mut frame: Vec<Complex64> = (0..self.segment_len)
+            .map(|i| {
+                let s = self.buffer.get(i).copied().unwrap_or(0_f64);
+                let w = self.window.as_ref().map_or(1_f64, |w| w[i]);
+                Complex64::new(s * w, 0_f64)
+            })
+            .collect();
+        // The frame has the plan's length, so process cannot fail.
+        let _ = self.plan.process(&mut frame);
+        frame
+    }
+}
+
+impl<I: Iterator<Item = f64>> Iterator for Segments<I> {
+    type Item = Vec<Complex64>;
+
+    fn next(&mut self) -> Option<Vec<Complex64>> {
+        if self.done {
+            return None;
+        }
+        while self.buffer.len() < self.segment_len {
+            match self.samples.next() {
+                Some(s) => self.buffer.push(s),
+                None => {
+                    self.done = true;
+                    if self.buffer.len() > self.covered {
+                        return Some(self.spectrum());
+                    }
+                    return None;
+                }
+            }
+        }
+        let spectrum = self.spectrum();
+        self.buffer.drain(..self.hop);
+        self.covered = self.segment_len - self.hop;
+        Some(spectrum)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "window")]
+    use crate::fft::fft;
+
+    #[cfg(feature = "window")]
+    #[test]
+    fn test_segments_match_framewise_fft() {
+        let x = crate::utils::generate_random_values();
+        let window = crate::window::hann(128);
+        let spectra: Vec<Vec<Complex64>> =
+            segmented(x.iter().copied(), 128, 64, Some(window.clone()))
+                .unwrap()
+                .collect();
+        // 1024 samples give 15 full segments and nothing left over.
+        assert_eq!(spectra.len(), 15);
+        for (i, s) in spectra.iter().enumerate() {
+            let frame: Vec<f64> = x[64 * i..64 * i + 128]
+                .iter()
+                .zip(&window)
+                .map(|(a, w)| a * w)
+                .collect();
+            for (a, b) in s.iter().zip(fft(&frame).unwrap().iter()) {
+                assert!((a - b).norm() < 1e-9);
+            }
+        }
+    }
+
+    #[test]
+    fn test_segments_of_an_unbounded_signal() {
+        // An endless source is consumed only as far as the segments taken.
+        let ones = std::iter::repeat(1_f64);
+        let first: Vec<Vec<Complex64>> = segmented(ones, 8, 8, None).unwrap().take(3).collect();
+        assert!(first.iter().all(|s| s[0] == Complex64::new(8.0, 0.0)));
+
+        let tail: Vec<Vec<Complex64>> = segmented(vec![1_f64; 10], 8, 8, None).unwrap().collect();
+        assert_eq!(tail.len(), 2);
+        assert_eq!(tail[1][0], Complex64::new(2.0, 0.0));
+        assert!(matches!(
+            segmented(vec![0_f64; 8], 8, 9, None),
+            Err(FftError::InvalidParameter(_))
+        ));
+    }
+}
@@ -0,0 +1,225 @@
+use num::complex::Complex64;
+use std::ops::RangeInclusive;
+
+use crate::backend::{FftBackend, ScalarBackend};
+use crate::error::FftError;
+
+// ConvolveMode selects the part of the linear convolution of a (length N)
+// and b (length M) that convolve returns, as in scipy.signal.convolve:
+// Full is all N + M - 1 samples, Same the N samples centred on it, and Valid
+// the max(N, M) - min(N, M) + 1 samples to which both inputs contribute fully.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConvolveMode {
+    Full,
+    Same,
+    Valid,
+}
+
+// spectral_product returns the real signal whose n-point spectrum is the
+// product of those of a and b zero-padded to n, b's conjugated for a
+// correlation. One plan of the backend serves both directions, the inverse
+// being conj(P(conj(Z))) / n.
+fn spectral_product(
+    backend: &dyn FftBackend,
+    a: &[f64],
+    b: &[f64],
+    n: usize,
+    correlate: bool,
+) -> Result<Vec<f64>, FftError> {
+    let plan = backend.plan(n)?;
+    let spectrum = |x: &[f64]| -> Result<Vec<Complex64>, FftError> {
+        let mut buffer = vec![Complex64::default(); n];
+        for (slot, &v) in buffer.iter_mut().zip(x) {
+            slot.re = v;
+        }
+        plan.process(&mut buffer)?;
+        Ok(buffer)
+    };
+    let (fa, fb) = (spectrum(a)?, spectrum(b)?);
+    let mut product: Vec<Complex64> = fa
+        .iter()
+        .zip(&fb)
+        .map(|(x, y)| if correlate { x * y.conj() } else { x * y }.conj())
+        .collect();
+    plan.process(&mut product)?;
+    Ok(product.iter().map(|c| c.re / n as f64).collect())
+}
+
+// convolve returns the linear convolution of a and b, computed through the
+// FFT with enough zero-padding to avoid wrap-around
+pub fn convolve(a: &[f64], b: &[f64], mode: ConvolveMode) -> Result<Vec<f64>, FftError> {
+    convolve_with(&ScalarBackend::default(), a, b, mode)
+}
+
+// convolve_with computes convolve with the transforms of the given backend
+pub fn convolve_with(
+    backend: &dyn FftBackend,
+    a: &[f64],
+    b: &[f64],
+    mode: ConvolveMode,
+) -> Result<Vec<f64>, FftError> {
+    if a.is_empty() || b.is_empty() {
+        return Ok(Vec::new());
+    }
+    let (n, m) = (a.len(), b.len());
+    let full_len = n + m - 1;
+    let full = spectral_product(backend, a, b, full_len.next_power_of_two(), false)?;
+    let (start, len) = match mode {
+        ConvolveMode::Full => (0, full_len),
+        ConvolveMode::Same => ((m - 1) / 2, n),
+        ConvolveMode::Valid => (n.min(m) - 1, n.max(m) - n.min(m) + 1),
+    };
+    Ok(full[start..start + len].to_vec())
+}
+
+// convolve_circular returns the circular convolution
+// y[k] = sum_i a[i] b[(k - i) mod N] of two signals of equal length N, the
+// product of their spectra without padding
+pub fn convolve_circular(a: &[f64], b: &[f64]) -> Result<Vec<f64>, FftError> {
+    if a.len() != b.len() {
+        return Err(FftError::LengthMismatch(a.len(), b.len()));
+    }
+    if a.is_empty() {
+        return Ok(Vec::new());
+    }
+    spectral_product(&ScalarBackend::default(), a, b, a.len(), false)
+}
+
+// correlate_circular returns sum_n a[n + l] b[n] for every lag l, modulo a
+// power-of-two length above a.len() + b.len() - 1 so that negative lags l
+// land at n + l without overlapping the positive ones
+fn correlate_circular(
+    backend: &dyn FftBackend,
+    a: &[f64],
+    b: &[f64],
+) -> Result<Vec<f64>, FftError> {
+    let n = (a.len() + b.len() - 1).next_power_of_two();
+    spectral_product(backend, a, b, n, true)
+}
+
+// xcorr returns the cross-correlation r[l] = sum_n a[n + l] b[n] for the lags
+// l = -(b.len() - 1) ..= a.len() - 1, index i holding lag
+// xcorr_lag(i, b.len()), as numpy.correlate(a, b, "full") lays them out. The
+// peak lag is the delay of a relative to b.
+pub fn xcorr(a: &[f64], b: &[f64]) -> Result<Vec<f64>, FftError> {
+    xcorr_with(&ScalarBackend::default(), a, b)
+}
+
+// xcorr_with computes xcorr with the transforms of the given backend
+pub fn xcorr_with(backend: &dyn FftBackend, a: &[f64], b: &[f64]) -> Result<Vec<f64>, FftError> {
+    if a.is_empty() || b.is_empty() {
+        return Ok(Vec::new());
+    }
+    let r = correlate_circular(backend, a, b)?;
+    let n = r.len();
+    Ok(xcorr_lags(a.len(), b.len())
+        .map(|lag| r[lag.rem_euclid(n as isize) as usize])
+        .collect())
+}
+
+// autocorr returns the autocorrelation r[l] = sum_n x[n + l] x[n] for the
+// lags 0..x.len(); negative lags mirror them, r[-l] = r[l]
+pub fn autocorr(x: &[f64]) -> Result<Vec<f64>, FftError> {
+    if x.is_empty() {
+        return Ok(Vec::new());
+    }
+    let mut r = correlate_circular(&ScalarBackend::default(), x, x)?;
+    r.truncate(x.len());
+    Ok(r)
+}
+
+// xcorr_lags returns the lags of the output of xcorr for inputs of the given
+// lengths, in order
+pub fn xcorr_lags(a_len: usize, b_len: usize) -> RangeInclusive<isize> {
+    -(b_len as isize - 1)..=a_len as isize - 1
+}
+
+// xcorr_lag returns the lag at index i of the output of xcorr
+pub fn xcorr_lag(i: usize, b_len: usize) -> isize {
+    i as isize - (b_len as isize - 1)
+}
+
+// peak_lag returns the lag at which the output of xcorr is largest, the time
+// delay estimate of a relative to b, or None for an empty correlation
+pub fn peak_lag(r: &[f64], b_len: usize) -> Option<isize> {
+    r.iter()
+        .enumerate()
+        .max_by(|x, y| x.1.total_cmp(y.1))
+        .map(|(i, _)| xcorr_lag(i, b_len))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: &[f64], b: &[f64]) -> bool {
+        a.len() == b.len() && a.iter().zip(b).all(|(a, b)| (a - b).abs() < 1e-9)
+    }
+
+    #[test]
+    fn test_convolve_modes() {
+        let a = [1_f64, 2_f64, 3_f64, 4_f64, 5_f64];
+        let b = [1_f64, 0_f64, -1_f64];
+        let full = [1_f64, 2_f64, 2_f64, 2_f64, 2_f64, -4_f64, -5_f64];
+        assert!(close(&convolve(&a, &b, ConvolveMode::Full).unwrap(), &full));
+        assert!(close(
+            &convolve(&a, &b, ConvolveMode::Same).unwrap(),
+            &full[1..6]
+        ));
+        assert!(close(
+            &convolve(&a, &b, ConvolveMode::Valid).unwrap(),
+            &full[2..5]
+        ));
+        // Valid does not depend on which input is longer.
+        assert!(close(
+            &convolve(&b, &a, ConvolveMode::Valid).unwrap(),
+            &full[2..5]
+        ));
+        assert!(convolve(&a, &[], ConvolveMode::Same).unwrap().is_empty());
+        let backend = ScalarBackend::default();
+        assert!(close(
+            &convolve_with(&backend, &a, &b, ConvolveMode::Full).unwrap(),
+            &full
+        ));
+        assert!(close(
+            &xcorr_with(&backend, &a, &b).unwrap(),
+            &xcorr(&a, &b).unwrap()
+        ));
+    }
+
+    #[test]
+    fn test_xcorr_and_autocorr() {
+        let a = [1_f64, 2_f64, 3_f64];
+        let b = [0_f64, 1_f64, 0.5];
+        // numpy.correlate(a, b, "full")
+        let expected = [0.5, 2_f64, 3.5, 3_f64, 0_f64];
+        let r = xcorr(&a, &b).unwrap();
+        assert!(close(&r, &expected));
+        assert_eq!(xcorr_lags(3, 3).collect::<Vec<_>>(), vec![-2, -1, 0, 1, 2]);
+        assert_eq!(xcorr_lag(0, 3), -2);
+
+        // A copy of b delayed by 7 samples peaks at lag 7.
+        let b: Vec<f64> = (0..40).map(|i| ((i * 37) % 11) as f64 - 5_f64).collect();
+        let mut a = vec![0_f64; 7];
+        a.extend(&b);
+        let r = xcorr(&a, &b).unwrap();
+        assert_eq!(peak_lag(&r, b.len()), Some(7));
+
+        let x = [1_f64, 2_f64, 3_f64];
+        assert!(close(&autocorr(&x).unwrap(), &[14_f64, 8_f64, 3_f64]));
+        assert!(xcorr(&[], &x).unwrap().is_empty());
+        assert_eq!(peak_lag(&[], 3), None);
+    }
+
+    #[test]
+    fn test_convolve_circular() {
+        let a = [1_f64, 2_f64, 3_f64, 4_f64, 5_f64];
+        let shift = [0_f64, 0_f64, 1_f64, 0_f64, 0_f64];
+        let y = convolve_circular(&a, &shift).unwrap();
+        assert!(close(&y, &[4_f64, 5_f64, 1_f64, 2_f64, 3_f64]));
+        assert!(matches!(
+            convolve_circular(&a, &shift[..4]),
+            Err(FftError::LengthMismatch(5, 4))
+        ));
+    }
+}
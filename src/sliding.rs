@@ -0,0 +1,138 @@
+use alloc::{vec, vec::Vec};
+use num::complex::Complex64;
+
+use crate::error::FftError;
+use crate::float::twiddle;
+use crate::goertzel::goertzel;
+
+// SlidingDft tracks selected bins of the DFT of the last window_len samples
+// of a stream. Every push updates each bin in O(1) with
+//   X[k] <- (X[k] - x_oldest + x_new) exp(-2j pi k / N),
+// which is exact in exact arithmetic; to keep rounding errors from
+// accumulating, the bins are recomputed with goertzel once per window_len
+// pushes, O(1) per sample on average. Before window_len samples have been
+// pushed, the window is padded with zeros at the front. The values follow
+// fft: value k is fft(window)[bins[k]], the window oldest sample first.
+#[derive(Debug, Clone)]
+pub struct SlidingDft {
+    bins: Vec<usize>,
+    rotations: Vec<Complex64>,
+    values: Vec<Complex64>,
+    samples: Vec<f64>,
+    // position of the oldest sample in samples
+    head: usize,
+    since_refresh: usize,
+}
+
+impl SlidingDft {
+    pub fn new(window_len: usize, bins: &[usize]) -> Result<Self, FftError> {
+        if window_len == 0 {
+            return Err(FftError::InvalidParameter("window length must be positive"));
+        }
+        if bins.iter().any(|&k| k >= window_len) {
+            return Err(FftError::InvalidParameter(
+                "bins must be below the window length",
+            ));
+        }
+        Ok(SlidingDft {
+            bins: bins.to_vec(),
+            rotations: bins
+                .iter()
+                .map(|&k| twiddle(k, window_len).conj())
+                .collect(),
+            values: vec![Complex64::default(); bins.len()],
+            samples: vec![0_f64; window_len],
+            head: 0,
+            since_refresh: 0,
+        })
+    }
+
+    // push slides the window by one sample
+    pub fn push(&mut self, sample: f64) {
+        let oldest = self.samples[self.head];
+        self.samples[self.head] = sample;
+        self.head = (self.head + 1) % self.samples.len();
+        let delta = sample - oldest;
+        for (v, r) in self.values.iter_mut().zip(&self.rotations) {
+            *v = (*v + delta) * r;
+        }
+        self.since_refresh += 1;
+        if self.since_refresh == self.samples.len() {
+            self.refresh();
+        }
+    }
+
+    // extend pushes every sample of x in order
+    pub fn extend(&mut self, x: &[f64]) {
+        x.iter().for_each(|&v| self.push(v));
+    }
+
+    // values returns the current value of every tracked bin, in the order of
+    // bins
+    pub fn values(&self) -> &[Complex64] {
+        &self.values
+    }
+
+    pub fn bins(&self) -> &[usize] {
+        &self.bins
+    }
+
+    pub fn window_len(&self) -> usize {
+        self.samples.len()
+    }
+
+    // window returns the samples under the window, oldest first
+    pub fn window(&self) -> Vec<f64> {
+        let (newer, older) = self.samples.split_at(self.head);
+        older.iter().chain(newer).copied().collect()
+    }
+
+    // reset empties the window
+    pub fn reset(&mut self) {
+        self.samples.iter_mut().for_each(|v| *v = 0_f64);
+        self.values
+            .iter_mut()
+            .for_each(|v| *v = Complex64::default());
+        self.head = 0;
+        self.since_refresh = 0;
+    }
+
+    fn refresh(&mut self) {
+        let window = self.window();
+        for (v, &k) in self.values.iter_mut().zip(&self.bins) {
+            // k < window_len was checked by new
+            *v = goertzel(&window, k).unwrap_or_default();
+        }
+        self.since_refresh = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fft::fft;
+
+    #[test]
+    fn test_sliding_dft_tracks_fft() {
+        let x: Vec<f64> = (0..300).map(|i| ((i * 37) % 11) as f64 - 5_f64).collect();
+        let bins = [0, 3, 17];
+        let mut sdft = SlidingDft::new(32, &bins).unwrap();
+        for (i, &v) in x.iter().enumerate() {
+            sdft.push(v);
+            if i >= 31 && i % 7 == 0 {
+                let window = &x[i - 31..=i];
+                assert_eq!(sdft.window(), window);
+                let spectrum = fft(window).unwrap();
+                for (value, &k) in sdft.values().iter().zip(&bins) {
+                    assert!((value - spectrum[k]).norm() < 1e-9, "{} {}", i, k);
+                }
+            }
+        }
+        sdft.reset();
+        sdft.extend(&[1_f64]);
+        // 1 is the newest sample of an otherwise empty window.
+        assert!((sdft.values()[1] - twiddle(3 * 31, 32)).norm() < 1e-12);
+        assert!(SlidingDft::new(32, &[32]).is_err());
+        assert!(SlidingDft::new(0, &[]).is_err());
+    }
+}
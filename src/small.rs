@@ -0,0 +1,135 @@
+use core::f64::consts::FRAC_1_SQRT_2;
+use num::complex::Complex64;
+
+use crate::error::FftError;
+
+// Transforms of up to MAX_LEN points that need no heap at all: the twiddle
+// factors W^j = exp(2j pi j / 64) are a constant table, lengths below 64 use
+// every (64 / len)-th entry, and the butterflies work inside the caller's
+// array. They can run in interrupt handlers and other places where
+// allocating is not allowed, and the planner uses them for tiny sizes.
+pub const MAX_LEN: usize = 64;
+
+pub(crate) const TWIDDLES: [Complex64; MAX_LEN / 2] = [
+    Complex64::new(1.0, 0.0),
+    Complex64::new(0.9951847266721969, 0.0980171403295606),
+    Complex64::new(0.9807852804032304, 0.19509032201612825),
+    Complex64::new(0.9569403357322088, 0.29028467725446233),
+    Complex64::new(0.9238795325112867, 0.3826834323650898),
+    Complex64::new(0.881921264348355, 0.47139673682599764),
+    Complex64::new(0.8314696123025452, 0.5555702330196022),
+    Complex64::new(0.773010453362737, 0.6343932841636455),
+    Complex64::new(FRAC_1_SQRT_2, FRAC_1_SQRT_2),
+    Complex64::new(0.6343932841636455, 0.773010453362737),
+    Complex64::new(0.5555702330196023, 0.8314696123025452),
+    Complex64::new(0.4713967368259978, 0.8819212643483549),
+    Complex64::new(0.38268343236508984, 0.9238795325112867),
+    Complex64::new(0.29028467725446233, 0.9569403357322089),
+    Complex64::new(0.19509032201612833, 0.9807852804032304),
+    Complex64::new(0.09801714032956077, 0.9951847266721968),
+    Complex64::new(0.0, 1.0),
+    Complex64::new(-0.09801714032956065, 0.9951847266721969),
+    Complex64::new(-0.1950903220161282, 0.9807852804032304),
+    Complex64::new(-0.29028467725446216, 0.9569403357322089),
+    Complex64::new(-0.3826834323650897, 0.9238795325112867),
+    Complex64::new(-0.4713967368259977, 0.881921264348355),
+    Complex64::new(-0.555570233019602, 0.8314696123025455),
+    Complex64::new(-0.6343932841636454, 0.7730104533627371),
+    Complex64::new(-FRAC_1_SQRT_2, FRAC_1_SQRT_2),
+    Complex64::new(-0.773010453362737, 0.6343932841636455),
+    Complex64::new(-0.8314696123025453, 0.5555702330196022),
+    Complex64::new(-0.8819212643483549, 0.47139673682599786),
+    Complex64::new(-0.9238795325112867, 0.3826834323650899),
+    Complex64::new(-0.9569403357322088, 0.2902846772544624),
+    Complex64::new(-0.9807852804032304, 0.1950903220161286),
+    Complex64::new(-0.9951847266721968, 0.09801714032956083),
+];
+
+// fft_array transforms an array of a power-of-two length up to MAX_LEN in
+// place; other lengths are rejected at compile time
+pub fn fft_array<const N: usize>(x: &mut [Complex64; N]) {
+    const {
+        assert!(
+            N.is_power_of_two() && N <= MAX_LEN,
+            "length must be a power of two up to 64"
+        )
+    };
+    transform(x);
+}
+
+// fft_in_place transforms a slice of a power-of-two length up to MAX_LEN in
+// place, failing with SizeTooLarge above MAX_LEN
+pub fn fft_in_place(x: &mut [Complex64]) -> Result<(), FftError> {
+    if !x.len().is_power_of_two() {
+        return Err(FftError::NotAPowerOfTwo(x.len()));
+    }
+    if x.len() > MAX_LEN {
+        return Err(FftError::SizeTooLarge(x.len()));
+    }
+    transform(x);
+    Ok(())
+}
+
+// transform runs the radix-2 decimation-in-time passes on x, whose length
+// is a power of two up to MAX_LEN
+pub(crate) fn transform(x: &mut [Complex64]) {
+    let n = x.len();
+    crate::fft::bit_reverse(x);
+    let mut size = 2;
+    while size <= n {
+        let half = size / 2;
+        let step = MAX_LEN / size;
+        for block in x.chunks_exact_mut(size) {
+            let (lo, hi) = block.split_at_mut(half);
+            for j in 0..half {
+                let v = hi[j] * TWIDDLES[j * step];
+                hi[j] = lo[j] - v;
+                lo[j] += v;
+            }
+        }
+        size *= 2;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fft::fft_complex;
+
+    #[test]
+    fn test_small_sizes_match_fft() {
+        let values = crate::utils::generate_random_values();
+        for len in [1, 2, 4, 8, 16, 32, 64] {
+            let x: Vec<Complex64> = (0..len)
+                .map(|i| Complex64::new(values[2 * i], values[2 * i + 1]))
+                .collect();
+            let mut y = x.clone();
+            fft_in_place(&mut y).unwrap();
+            let expected = fft_complex(&x).unwrap();
+            for (a, b) in y.iter().zip(expected.iter()) {
+                assert!((a - b).norm() < 1e-12, "len {}", len);
+            }
+        }
+    }
+
+    #[test]
+    fn test_fft_array() {
+        let mut x = [Complex64::new(1.0, 0.0); 8];
+        fft_array(&mut x);
+        assert_eq!(x[0], Complex64::new(8.0, 0.0));
+        assert!(x[1..].iter().all(|c| c.norm() < 1e-15));
+    }
+
+    #[test]
+    fn test_small_invalid_lengths() {
+        let mut x = vec![Complex64::default(); 128];
+        assert!(matches!(
+            fft_in_place(&mut x),
+            Err(FftError::SizeTooLarge(128))
+        ));
+        assert!(matches!(
+            fft_in_place(&mut x[..12]),
+            Err(FftError::NotAPowerOfTwo(12))
+        ));
+    }
+}
@@ -0,0 +1,429 @@
+use num::complex::Complex64;
+use std::ops::{Add, Div, Mul, Range, Sub};
+
+use crate::axis::FrequencyAxis;
+use crate::error::FftError;
+use crate::fft::{fft, fft_complex, ifft, ifft_complex};
+use crate::samples::{IntoComplexSamples, IntoSamples};
+#[cfg(feature = "window")]
+use crate::window::{coherent_gain, enbw};
+
+// WindowInfo records the correction factors of the window a spectrum was
+// computed with (see window::coherent_gain and window::enbw).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WindowInfo {
+    pub coherent_gain: f64,
+    pub enbw: f64,
+}
+
+impl WindowInfo {
+    #[cfg(feature = "window")]
+    pub fn of(window: &[f64]) -> Result<Self, FftError> {
+        Ok(WindowInfo {
+            coherent_gain: coherent_gain(window)?,
+            enbw: enbw(window)?,
+        })
+    }
+
+    pub fn rectangular() -> Self {
+        WindowInfo {
+            coherent_gain: 1_f64,
+            enbw: 1_f64,
+        }
+    }
+}
+
+impl Default for WindowInfo {
+    fn default() -> Self {
+        WindowInfo::rectangular()
+    }
+}
+
+// Normalization is the scaling already applied to the bins: none, as
+// computed by fft, 1 / n, or the unitary 1 / sqrt(n).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Normalization {
+    #[default]
+    None,
+    Length,
+    Orthonormal,
+}
+
+// Spectrum is a full n-bin complex spectrum as produced by fft, together with
+// the sample rate of the signal, the window it was computed with and the
+// scaling of its bins; without a sample rate, frequencies are in cycles per
+// sample. Products are circular convolutions of the underlying signals and
+// quotients undo them; binary operators check the lengths and sample rates
+// of their operands and return a Result, so frequency-domain expressions
+// read as (&a * &b)?. Results keep the metadata of the left operand.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Spectrum {
+    bins: Vec<Complex64>,
+    sample_rate: f64,
+    window: WindowInfo,
+    normalization: Normalization,
+}
+
+impl Default for Spectrum {
+    fn default() -> Self {
+        Spectrum::new(Vec::new())
+    }
+}
+
+impl Spectrum {
+    pub fn new(bins: Vec<Complex64>) -> Self {
+        Spectrum {
+            bins,
+            sample_rate: 1_f64,
+            window: WindowInfo::rectangular(),
+            normalization: Normalization::None,
+        }
+    }
+
+    // from_windowed_signal returns the spectrum of a real signal sampled at
+    // sample_rate Hz after multiplying it by window
+    #[cfg(feature = "window")]
+    pub fn from_windowed_signal(
+        x: &[f64],
+        window: &[f64],
+        sample_rate: f64,
+    ) -> Result<Self, FftError> {
+        if x.len() != window.len() {
+            return Err(FftError::LengthMismatch(window.len(), x.len()));
+        }
+        let windowed: Vec<f64> = x.iter().zip(window).map(|(s, w)| s * w).collect();
+        Ok(Spectrum::from_signal(windowed)?
+            .with_sample_rate(sample_rate)?
+            .with_window(WindowInfo::of(window)?))
+    }
+
+    pub fn with_sample_rate(mut self, sample_rate: f64) -> Result<Self, FftError> {
+        if !(sample_rate > 0_f64 && sample_rate.is_finite()) {
+            return Err(FftError::InvalidParameter("sample rate must be positive"));
+        }
+        self.sample_rate = sample_rate;
+        Ok(self)
+    }
+
+    pub fn with_window(mut self, window: WindowInfo) -> Self {
+        self.window = window;
+        self
+    }
+
+    pub fn with_normalization(mut self, normalization: Normalization) -> Self {
+        self.normalization = normalization;
+        self
+    }
+
+    pub fn sample_rate(&self) -> f64 {
+        self.sample_rate
+    }
+
+    pub fn window(&self) -> WindowInfo {
+        self.window
+    }
+
+    pub fn normalization(&self) -> Normalization {
+        self.normalization
+    }
+
+    // axis returns the frequency axis of the bins
+    pub fn axis(&self) -> Result<FrequencyAxis, FftError> {
+        FrequencyAxis::new(self.len(), self.sample_rate)
+    }
+
+    // at_hz returns the one-sided bin nearest to f Hz, see
+    // FrequencyAxis::hz_to_bin
+    pub fn at_hz(&self, f: f64) -> Option<Complex64> {
+        let k = self.axis().ok()?.hz_to_bin(f)?;
+        Some(self.bins[k])
+    }
+
+    // band returns the one-sided bins with frequencies in range, none if a
+    // bound is NaN
+    pub fn band(&self, range: Range<f64>) -> &[Complex64] {
+        let Ok(axis) = self.axis() else {
+            return &[];
+        };
+        if range.start.is_nan() || range.end.is_nan() {
+            return &[];
+        }
+        let resolution = axis.resolution();
+        let bin = |f: f64| ((f / resolution).ceil().max(0_f64) as usize).min(axis.one_sided_len());
+        let (lo, hi) = (bin(range.start), bin(range.end));
+        if lo >= hi {
+            return &[];
+        }
+        &self.bins[lo..hi]
+    }
+
+    // amplitude returns, for the one-sided bins 0..=n/2, the amplitude of a
+    // sinusoid centred on each bin, undoing the normalization and the
+    // coherent gain of the window
+    pub fn amplitude(&self) -> Vec<f64> {
+        let n = self.len();
+        let scale = match self.normalization {
+            Normalization::None => 1_f64 / n as f64,
+            Normalization::Length => 1_f64,
+            Normalization::Orthonormal => 1_f64 / (n as f64).sqrt(),
+        } / self.window.coherent_gain;
+        self.bins[..(n / 2 + 1).min(n)]
+            .iter()
+            .enumerate()
+            .map(|(k, c)| {
+                let fold = if k == 0 || 2 * k == n { 1_f64 } else { 2_f64 };
+                fold * scale * c.norm()
+            })
+            .collect()
+    }
+
+    // magnitude_db returns amplitude in dB (20 log10)
+    pub fn magnitude_db(&self) -> Vec<f64> {
+        self.amplitude()
+            .iter()
+            .map(|a| 20_f64 * a.log10())
+            .collect()
+    }
+
+    // with_bins returns a spectrum of bins with the metadata of self
+    fn with_bins(&self, bins: Vec<Complex64>) -> Spectrum {
+        Spectrum {
+            bins,
+            sample_rate: self.sample_rate,
+            window: self.window,
+            normalization: self.normalization,
+        }
+    }
+
+    // from_signal returns the spectrum of a real signal
+    pub fn from_signal<'a, K>(x: impl IntoSamples<'a, f64, K>) -> Result<Self, FftError> {
+        Ok(Spectrum::new(fft(x)?))
+    }
+
+    // from_complex_signal returns the spectrum of a complex signal
+    pub fn from_complex_signal<'a>(x: impl IntoComplexSamples<'a>) -> Result<Self, FftError> {
+        Ok(Spectrum::new(fft_complex(&x.into_complex_samples())?))
+    }
+
+    // to_signal returns the real signal of the spectrum, dropping any
+    // imaginary part left by a non-Hermitian spectrum
+    pub fn to_signal(&self) -> Result<Vec<f64>, FftError> {
+        ifft(&self.bins)
+    }
+
+    pub fn to_complex_signal(&self) -> Result<Vec<Complex64>, FftError> {
+        ifft_complex(&self.bins)
+    }
+
+    pub fn bins(&self) -> &[Complex64] {
+        &self.bins
+    }
+
+    pub fn bins_mut(&mut self) -> &mut [Complex64] {
+        &mut self.bins
+    }
+
+    pub fn into_bins(self) -> Vec<Complex64> {
+        self.bins
+    }
+
+    pub fn len(&self) -> usize {
+        self.bins.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bins.is_empty()
+    }
+
+    pub fn conj(&self) -> Spectrum {
+        self.with_bins(self.bins.iter().map(|c| c.conj()).collect())
+    }
+
+    // div_regularized deconvolves self by other with Tikhonov regularization,
+    // self * conj(other) / (|other|^2 + epsilon), which stays bounded where
+    // other is close to zero; epsilon must be positive and finite, plain
+    // division being the / operator
+    pub fn div_regularized(&self, other: &Spectrum, epsilon: f64) -> Result<Spectrum, FftError> {
+        if !(epsilon > 0_f64 && epsilon.is_finite()) {
+            return Err(FftError::InvalidParameter(
+                "regularization must be positive",
+            ));
+        }
+        self.zip_with(other, |a, b| a * b.conj() / (b.norm_sqr() + epsilon))
+    }
+
+    fn zip_with(
+        &self,
+        other: &Spectrum,
+        f: impl Fn(Complex64, Complex64) -> Complex64,
+    ) -> Result<Spectrum, FftError> {
+        if self.len() != other.len() {
+            return Err(FftError::LengthMismatch(self.len(), other.len()));
+        }
+        if self.sample_rate != other.sample_rate {
+            return Err(FftError::InvalidParameter(
+                "spectra have different sample rates",
+            ));
+        }
+        Ok(self.with_bins(
+            self.bins
+                .iter()
+                .zip(other.bins.iter())
+                .map(|(&a, &b)| f(a, b))
+                .collect(),
+        ))
+    }
+}
+
+impl From<Vec<Complex64>> for Spectrum {
+    fn from(bins: Vec<Complex64>) -> Self {
+        Spectrum::new(bins)
+    }
+}
+
+impl AsRef<[Complex64]> for Spectrum {
+    fn as_ref(&self) -> &[Complex64] {
+        &self.bins
+    }
+}
+
+impl Add for &Spectrum {
+    type Output = Result<Spectrum, FftError>;
+
+    fn add(self, other: &Spectrum) -> Self::Output {
+        self.zip_with(other, |a, b| a + b)
+    }
+}
+
+impl Sub for &Spectrum {
+    type Output = Result<Spectrum, FftError>;
+
+    fn sub(self, other: &Spectrum) -> Self::Output {
+        self.zip_with(other, |a, b| a - b)
+    }
+}
+
+// Multiplying two spectra convolves their signals circularly.
+impl Mul for &Spectrum {
+    type Output = Result<Spectrum, FftError>;
+
+    fn mul(self, other: &Spectrum) -> Self::Output {
+        self.zip_with(other, |a, b| a * b)
+    }
+}
+
+// Dividing two spectra deconvolves their signals; see div_regularized for a
+// version that tolerates near-zero bins.
+impl Div for &Spectrum {
+    type Output = Result<Spectrum, FftError>;
+
+    fn div(self, other: &Spectrum) -> Self::Output {
+        self.zip_with(other, |a, b| a / b)
+    }
+}
+
+impl Mul<f64> for &Spectrum {
+    type Output = Spectrum;
+
+    fn mul(self, s: f64) -> Spectrum {
+        self.with_bins(self.bins.iter().map(|c| c * s).collect())
+    }
+}
+
+impl Mul<Complex64> for &Spectrum {
+    type Output = Spectrum;
+
+    fn mul(self, s: Complex64) -> Spectrum {
+        self.with_bins(self.bins.iter().map(|c| c * s).collect())
+    }
+}
+
+impl Div<f64> for &Spectrum {
+    type Output = Spectrum;
+
+    fn div(self, s: f64) -> Spectrum {
+        self.with_bins(self.bins.iter().map(|c| c / s).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spectrum_product_is_circular_convolution() {
+        let x = [1.0, 2.0, 3.0, 4.0, 0.0, 0.0, 0.0, 0.0];
+        let h = [1.0, -1.0, 0.5, 0.0, 0.0, 0.0, 0.0, 0.0];
+        let y = (&Spectrum::from_signal(&x).unwrap() * &Spectrum::from_signal(&h).unwrap())
+            .unwrap()
+            .to_signal()
+            .unwrap();
+        let expected = [1.0, 1.0, 1.5, 2.0, -2.5, 2.0, 0.0, 0.0];
+        for (a, b) in y.iter().zip(expected.iter()) {
+            assert!((a - b).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_spectrum_deconvolution() {
+        let x = crate::utils::generate_random_values();
+        let mut h = vec![0_f64; 1024];
+        h[..3].copy_from_slice(&[1.0, 0.5, 0.25]);
+        let sx = Spectrum::from_signal(&x).unwrap();
+        let sh = Spectrum::from_signal(&h).unwrap();
+        let y = (&sx * &sh).unwrap();
+
+        let plain = (&y / &sh).unwrap().to_signal().unwrap();
+        let regularized = y.div_regularized(&sh, 1e-9).unwrap().to_signal().unwrap();
+        for i in 0..1024 {
+            assert!((plain[i] - x[i]).abs() < 1e-9);
+            assert!((regularized[i] - x[i]).abs() < 1e-6);
+        }
+        for epsilon in [-1.0, 0.0, f64::NAN, f64::INFINITY] {
+            assert!(matches!(
+                y.div_regularized(&sh, epsilon),
+                Err(FftError::InvalidParameter(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_spectrum_linear_ops() {
+        let a = Spectrum::from_signal(&[1.0, 2.0, 3.0, 4.0]).unwrap();
+        let b = Spectrum::from_signal(&[4.0, 3.0, 2.0, 1.0]).unwrap();
+        let sum = (&a + &b).unwrap().to_signal().unwrap();
+        assert!(sum.iter().all(|v| (v - 5.0).abs() < 1e-12));
+        let diff = (&(&a * 2.0) - &(&a / 0.5)).unwrap();
+        assert!(diff.bins().iter().all(|c| c.norm() < 1e-12));
+
+        let short = Spectrum::new(vec![Complex64::default(); 2]);
+        assert!(matches!(&a + &short, Err(FftError::LengthMismatch(4, 2))));
+    }
+    #[cfg(feature = "window")]
+    #[test]
+    fn test_spectrum_metadata() {
+        let fs = 1024_f64;
+        let n = 1024;
+        let x: Vec<f64> = (0..n)
+            .map(|i| 2.0 * (2_f64 * std::f64::consts::PI * 50.0 * i as f64 / fs).cos())
+            .collect();
+        let window = crate::window::hann(n);
+        let s = Spectrum::from_windowed_signal(&x, &window, fs).unwrap();
+        assert_eq!(s.sample_rate(), fs);
+        assert!((s.window().coherent_gain - 0.5).abs() < 1e-12);
+        assert!((s.amplitude()[50] - 2.0).abs() < 1e-9);
+        assert!((s.magnitude_db()[50] - 20.0 * 2_f64.log10()).abs() < 1e-9);
+        assert_eq!(s.at_hz(50.2), Some(s.bins()[50]));
+        assert_eq!(s.at_hz(-50.0), None);
+        assert_eq!(s.at_hz(f64::NAN), None);
+        assert!(s.band(f64::NAN..60.0).is_empty());
+        assert_eq!(s.band(40.0..60.0), &s.bins()[40..60]);
+        assert!(s.band(600.0..700.0).is_empty());
+
+        let scaled = (&s * 2.0).with_normalization(Normalization::Length);
+        assert_eq!(scaled.sample_rate(), fs);
+        assert!((scaled.amplitude()[50] - 2.0 * n as f64 * 2.0).abs() < 1e-6);
+        let other = Spectrum::new(s.bins().to_vec());
+        assert!(matches!(&s + &other, Err(FftError::InvalidParameter(_))));
+    }
+}
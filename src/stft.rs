@@ -0,0 +1,200 @@
+use num::complex::Complex64;
+
+use crate::backend::{FftBackend, ScalarBackend};
+use crate::cancel::CancellationToken;
+use crate::error::FftError;
+use crate::fft::ifft;
+use crate::frames::{frame_count, frames, PadMode};
+
+fn check_params(frame_len: usize, hop: usize, window: &[f64]) -> Result<(), FftError> {
+    if frame_len == 0 {
+        return Err(FftError::InvalidParameter(
+            "frame length must be greater than zero",
+        ));
+    }
+    if hop == 0 || hop > frame_len {
+        return Err(FftError::InvalidParameter(
+            "hop must be between 1 and the frame length",
+        ));
+    }
+    if window.len() != frame_len {
+        return Err(FftError::LengthMismatch(frame_len, window.len()));
+    }
+    Ok(())
+}
+
+// stft computes the Short-Time Fourier Transform of x. The frames are laid
+// out by frames with PadMode::Zero, so that every sample lies under the
+// centre of some frame; each frame is multiplied by window and transformed,
+// giving one full complex spectrum per frame.
+pub fn stft(
+    x: &[f64],
+    frame_len: usize,
+    hop: usize,
+    window: &[f64],
+) -> Result<Vec<Vec<Complex64>>, FftError> {
+    stft_with(&ScalarBackend::default(), x, frame_len, hop, window)
+}
+
+// stft_with computes stft with the transforms of the given backend, planned
+// once for all frames
+pub fn stft_with(
+    backend: &dyn FftBackend,
+    x: &[f64],
+    frame_len: usize,
+    hop: usize,
+    window: &[f64],
+) -> Result<Vec<Vec<Complex64>>, FftError> {
+    stft_cancellable(
+        backend,
+        x,
+        frame_len,
+        hop,
+        window,
+        &CancellationToken::new(),
+    )
+}
+
+// stft_cancellable is stft_with checking token before every frame, so a
+// long spectrogram job can be abandoned with FftError::Cancelled
+pub fn stft_cancellable(
+    backend: &dyn FftBackend,
+    x: &[f64],
+    frame_len: usize,
+    hop: usize,
+    window: &[f64],
+    token: &CancellationToken,
+) -> Result<Vec<Vec<Complex64>>, FftError> {
+    check_params(frame_len, hop, window)?;
+    let plan = backend.plan(frame_len)?;
+
+    frames(x, frame_len, hop, PadMode::Zero)?
+        .map(|frame| {
+            token.check()?;
+            let mut frame: Vec<Complex64> = frame
+                .iter()
+                .zip(window.iter())
+                .map(|(s, w)| Complex64::new(s * w, 0_f64))
+                .collect();
+            plan.process(&mut frame)?;
+            Ok(frame)
+        })
+        .collect()
+}
+
+// istft inverts stft by weighted overlap-add: every frame is inverse
+// transformed, multiplied by window again and summed, and the result is
+// normalized by the overlapped sum of squared windows. len is the length of
+// the original signal.
+pub fn istft(
+    frames: &[Vec<Complex64>],
+    hop: usize,
+    window: &[f64],
+    len: usize,
+) -> Result<Vec<f64>, FftError> {
+    let frame_len = window.len();
+    check_params(frame_len, hop, window)?;
+
+    let pad = frame_len / 2;
+    let total = (frames.len().max(1) - 1) * hop + frame_len;
+    if pad + len > total {
+        return Err(FftError::LengthMismatch(
+            frame_count(len, frame_len, hop, PadMode::Zero),
+            frames.len(),
+        ));
+    }
+
+    let mut out = vec![0_f64; total];
+    let mut norm = vec![0_f64; total];
+    for (f, frame) in frames.iter().enumerate() {
+        if frame.len() != frame_len {
+            return Err(FftError::LengthMismatch(frame_len, frame.len()));
+        }
+        let samples = ifft(frame)?;
+        let start = f * hop;
+        for i in 0..frame_len {
+            out[start + i] += samples[i] * window[i];
+            norm[start + i] += window[i] * window[i];
+        }
+    }
+
+    Ok((pad..pad + len)
+        .map(|i| {
+            if norm[i] > 1e-10 {
+                out[i] / norm[i]
+            } else {
+                0_f64
+            }
+        })
+        .collect())
+}
+
+#[cfg(all(test, feature = "window"))]
+mod tests {
+    use super::*;
+    use crate::window::hann;
+
+    #[test]
+    fn test_stft_frame_layout() {
+        let x = vec![1_f64; 100];
+        let frames = stft(&x, 16, 4, &hann(16)).unwrap();
+        assert_eq!(frames.len(), frame_count(100, 16, 4, PadMode::Zero));
+        assert!(frames.iter().all(|f| f.len() == 16));
+    }
+
+    #[test]
+    fn test_stft_istft_round_trip() {
+        let values = crate::utils::generate_random_values();
+        let window = hann(64);
+        let frames = stft(&values, 64, 16, &window).unwrap();
+        let o = istft(&frames, 16, &window, values.len()).unwrap();
+        assert_eq!(values.len(), o.len());
+        for i in 0..values.len() {
+            let diff = (values[i] - o[i]).abs();
+            assert!(
+                diff < 1e-9,
+                "Mismatch at index {}: {} vs {}",
+                i,
+                values[i],
+                o[i]
+            );
+        }
+    }
+
+    #[test]
+    fn test_stft_any_frame_length() {
+        let x: Vec<f64> = (0..50).map(|i| (i as f64 * 0.3).sin()).collect();
+        let window = hann(12);
+        let frames = stft(&x, 12, 4, &window).unwrap();
+        assert_eq!(frames.len(), frame_count(50, 12, 4, PadMode::Zero));
+        let padded: Vec<f64> = (0..12)
+            .map(|i| if i < 6 { 0_f64 } else { x[i - 6] * window[i] })
+            .collect();
+        let expected = crate::fft::fft(&padded).unwrap();
+        assert!(frames[0]
+            .iter()
+            .zip(&expected)
+            .all(|(a, b)| (a - b).norm() < 1e-9));
+        let back = istft(&frames, 4, &window, x.len()).unwrap();
+        assert!(back.iter().zip(&x).all(|(a, b)| (a - b).abs() < 1e-9));
+    }
+
+    #[test]
+    fn test_stft_invalid_params() {
+        let x = vec![0_f64; 32];
+        assert!(matches!(
+            stft(&x, 16, 0, &hann(16)),
+            Err(FftError::InvalidParameter(_))
+        ));
+        assert!(matches!(
+            stft(&x, 16, 4, &hann(8)),
+            Err(FftError::LengthMismatch(16, 8))
+        ));
+        let token = CancellationToken::new();
+        token.cancel();
+        assert!(matches!(
+            stft_cancellable(&ScalarBackend::default(), &x, 16, 4, &hann(16), &token),
+            Err(FftError::Cancelled)
+        ));
+    }
+}
@@ -0,0 +1,210 @@
+use futures_core::Stream;
+use num::complex::Complex64;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use crate::error::FftError;
+use crate::planner::{FftPlan, FftPlanner};
+
+// Adapters over futures::Stream for async ingestion: F32LeSamples decodes a
+// byte stream into samples and SpectrumFrames turns a sample stream into a
+// stream of spectra.
+
+// F32LeSamples decodes a stream of byte chunks (Vec<u8>, Bytes or anything
+// else that is AsRef<[u8]>) holding little-endian f32 samples, as read from a
+// socket or a sound card, into chunks of f64 samples. Samples may be split
+// across chunks.
+#[derive(Debug)]
+pub struct F32LeSamples<S> {
+    inner: S,
+    pending: Vec<u8>,
+}
+
+impl<S> F32LeSamples<S> {
+    pub fn new(inner: S) -> Self {
+        F32LeSamples {
+            inner,
+            pending: Vec::new(),
+        }
+    }
+}
+
+impl<S> Stream for F32LeSamples<S>
+where
+    S: Stream + Unpin,
+    S::Item: AsRef<[u8]>,
+{
+    type Item = Vec<f64>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Vec<f64>>> {
+        let this = &mut *self;
+        match Pin::new(&mut this.inner).poll_next(cx) {
+            Poll::Ready(Some(bytes)) => {
+                this.pending.extend_from_slice(bytes.as_ref());
+                let whole = this.pending.len() / 4 * 4;
+                let samples = this.pending[..whole]
+                    .chunks_exact(4)
+                    .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f64)
+                    .collect();
+                this.pending.drain(..whole);
+                Poll::Ready(Some(samples))
+            }
+            other => other.map(|_| None),
+        }
+    }
+}
+
+// SpectrumFrames turns a stream of sample chunks of any size, such as
+// Vec<f64> or the output of F32LeSamples, into a stream
+// of spectra: every frame_len samples, advancing by hop, are multiplied by
+// window and transformed. When the input ends, samples not yet covered by a
+// frame are zero-padded into one last frame.
+#[derive(Debug)]
+pub struct SpectrumFrames<S> {
+    inner: S,
+    window: Vec<f64>,
+    hop: usize,
+    plan: FftPlan,
+    buffer: Vec<f64>,
+    // samples at the start of buffer that an emitted frame already covered
+    covered: usize,
+    done: bool,
+}
+
+impl<S> SpectrumFrames<S> {
+    // new builds the adapter; the frame length is the length of window
+    pub fn new(inner: S, window: Vec<f64>, hop: usize) -> Result<Self, FftError> {
+        if hop == 0 || hop > window.len() {
+            return Err(FftError::InvalidParameter(
+                "hop must be between 1 and the frame length",
+            ));
+        }
+        Ok(SpectrumFrames {
+            plan: FftPlanner::new().plan(window.len())?,
+            inner,
+            window,
+            hop,
+            buffer: Vec::new(),
+            covered: 0,
+            done: false,
+        })
+    }
+
+    fn spectrum(&self, samples: &[f64]) -> Vec<Complex64> {
+        let mut frame: Vec<Complex64> = self
+            .window
+            .iter()
+            .enumerate()
+            .map(|(i, w)| Complex64::new(samples.get(i).map_or(0_f64, |s| s * w), 0_f64))
+            .collect();
+        // The frame has the plan's length, so process cannot fail.
+        let _ = self.plan.process(&mut frame);
+        frame
+    }
+}
+
+impl<S> Stream for SpectrumFrames<S>
+where
+    S: Stream + Unpin,
+    S::Item: AsRef<[f64]>,
+{
+    type Item = Vec<Complex64>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Vec<Complex64>>> {
+        let this = &mut *self;
+        let frame_len = this.window.len();
+        loop {
+            if this.buffer.len() >= frame_len {
+                let spectrum = this.spectrum(&this.buffer[..frame_len]);
+                this.buffer.drain(..this.hop);
+                this.covered = frame_len - this.hop;
+                return Poll::Ready(Some(spectrum));
+            }
+            if this.done {
+                return Poll::Ready(None);
+            }
+            match Pin::new(&mut this.inner).poll_next(cx) {
+                Poll::Ready(Some(chunk)) => this.buffer.extend_from_slice(chunk.as_ref()),
+                Poll::Ready(None) => {
+                    this.done = true;
+                    if this.buffer.len() > this.covered {
+                        let spectrum = this.spectrum(&this.buffer);
+                        this.buffer.clear();
+                        return Poll::Ready(Some(spectrum));
+                    }
+                }
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_util::stream::iter;
+    use std::task::Waker;
+
+    // collect drains a stream that never returns Pending
+    fn collect<S: Stream + Unpin>(mut stream: S) -> Vec<S::Item> {
+        let mut cx = Context::from_waker(Waker::noop());
+        let mut out = Vec::new();
+        while let Poll::Ready(Some(item)) = Pin::new(&mut stream).poll_next(&mut cx) {
+            out.push(item);
+        }
+        out
+    }
+
+    #[cfg(feature = "window")]
+    #[test]
+    fn test_spectrum_frames_ignore_chunking() {
+        let x = crate::utils::generate_random_values();
+        let window = crate::window::hann(64);
+        let whole = collect(SpectrumFrames::new(iter([x.clone()]), window.clone(), 32).unwrap());
+        let chunks: Vec<Vec<f64>> = x.chunks(13).map(|c| c.to_vec()).collect();
+        let chunked = collect(SpectrumFrames::new(iter(chunks), window.clone(), 32).unwrap());
+        assert_eq!(whole, chunked);
+        // 1024 samples give 31 full frames and nothing left over.
+        assert_eq!(whole.len(), 31);
+        let windowed: Vec<f64> = x[32..96].iter().zip(&window).map(|(s, w)| s * w).collect();
+        let expected = crate::fft::fft(&windowed).unwrap();
+        for (a, b) in whole[1].iter().zip(expected.iter()) {
+            assert!((a - b).norm() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_spectrum_frames_flush_tail() {
+        let frames =
+            collect(SpectrumFrames::new(iter([vec![1_f64; 10]]), vec![1_f64; 8], 8).unwrap());
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[1][0], Complex64::new(2.0, 0.0));
+        assert!(matches!(
+            SpectrumFrames::new(iter([vec![0_f64]]), vec![1_f64; 8], 0),
+            Err(FftError::InvalidParameter(_))
+        ));
+    }
+
+    #[test]
+    fn test_f32_le_samples_across_chunks() {
+        let bytes: Vec<u8> = [1.5_f32, -2.0, 0.25]
+            .iter()
+            .flat_map(|v| v.to_le_bytes())
+            .collect();
+        let chunks = vec![
+            bytes[..3].to_vec(),
+            bytes[3..9].to_vec(),
+            bytes[9..].to_vec(),
+        ];
+        let samples: Vec<f64> = collect(F32LeSamples::new(iter(chunks.clone()))).concat();
+        assert_eq!(samples, [1.5, -2.0, 0.25]);
+
+        // Byte chunks decode straight into spectra.
+        let slices = chunks.iter().map(|c| c.as_slice());
+        let spectra = collect(
+            SpectrumFrames::new(F32LeSamples::new(iter(slices)), vec![1_f64; 4], 4).unwrap(),
+        );
+        assert_eq!(spectra.len(), 1);
+        assert_eq!(spectra[0][0], Complex64::new(-0.25, 0.0));
+    }
+}
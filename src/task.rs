@@ -0,0 +1,180 @@
+use num::complex::Complex64;
+use std::future::Future;
+use std::panic::{self, AssertUnwindSafe};
+use std::pin::Pin;
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::task::{Context, Poll, Waker};
+use std::thread;
+
+use crate::error::FftError;
+
+// Heavy transforms run on a small pool of worker threads, one per core,
+// started on first use, and are awaited through a Task. Task only relies on
+// std::future, so it can be awaited from tokio, async-std or any other
+// executor without blocking its reactor threads. A job that panics does not
+// take its worker down: the panic is caught and resumed in the task awaiting
+// it.
+type Job = Box<dyn FnOnce() + Send>;
+
+fn pool() -> &'static Mutex<Sender<Job>> {
+    static POOL: OnceLock<Mutex<Sender<Job>>> = OnceLock::new();
+    POOL.get_or_init(|| {
+        let (sender, receiver) = channel::<Job>();
+        let receiver = Arc::new(Mutex::new(receiver));
+        let workers = thread::available_parallelism().map_or(1, |n| n.get());
+        for i in 0..workers {
+            let receiver: Arc<Mutex<Receiver<Job>>> = Arc::clone(&receiver);
+            thread::Builder::new()
+                .name(format!("fft-worker-{}", i))
+                .spawn(move || loop {
+                    let job = match receiver.lock() {
+                        Ok(receiver) => receiver.recv(),
+                        Err(_) => return,
+                    };
+                    match job {
+                        Ok(job) => job(),
+                        Err(_) => return,
+                    }
+                })
+                .expect("failed to start an fft worker thread");
+        }
+        Mutex::new(sender)
+    })
+}
+
+struct State<T> {
+    result: Option<thread::Result<T>>,
+    waker: Option<Waker>,
+}
+
+// Task is the future of a job running on the worker pool.
+pub struct Task<T> {
+    state: Arc<Mutex<State<T>>>,
+}
+
+impl<T> Future for Task<T> {
+    type Output = T;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<T> {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        match state.result.take() {
+            Some(Ok(result)) => Poll::Ready(result),
+            Some(Err(payload)) => {
+                drop(state);
+                panic::resume_unwind(payload)
+            }
+            None => {
+                state.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+// spawn_blocking runs f on the worker pool and returns its future. Should
+// the pool have stopped, f runs on the calling thread instead.
+pub fn spawn_blocking<T: Send + 'static>(f: impl FnOnce() -> T + Send + 'static) -> Task<T> {
+    let state = Arc::new(Mutex::new(State {
+        result: None,
+        waker: None,
+    }));
+    let shared = Arc::clone(&state);
+    let job: Job = Box::new(move || {
+        let result = panic::catch_unwind(AssertUnwindSafe(f));
+        let mut state = shared.lock().unwrap_or_else(|e| e.into_inner());
+        state.result = Some(result);
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+    });
+    let sent = pool().lock().unwrap_or_else(|e| e.into_inner()).send(job);
+    if let Err(unsent) = sent {
+        (unsent.0)();
+    }
+    Task { state }
+}
+
+// fft_async computes fft of x on the worker pool
+pub fn fft_async(x: Vec<f64>) -> Task<Result<Vec<Complex64>, FftError>> {
+    spawn_blocking(move || crate::fft::fft(x))
+}
+
+// spectrogram_async computes stft of x on the worker pool
+pub fn spectrogram_async(
+    x: Vec<f64>,
+    frame_len: usize,
+    hop: usize,
+    window: Vec<f64>,
+) -> Task<Result<Vec<Vec<Complex64>>, FftError>> {
+    spawn_blocking(move || crate::stft::stft(&x, frame_len, hop, &window))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::task::Wake;
+
+    struct Unpark(thread::Thread);
+
+    impl Wake for Unpark {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    // block_on is a minimal executor polling future on the current thread
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let mut future = std::pin::pin!(future);
+        let waker = Waker::from(Arc::new(Unpark(thread::current())));
+        let mut cx = Context::from_waker(&waker);
+        loop {
+            match future.as_mut().poll(&mut cx) {
+                Poll::Ready(output) => return output,
+                Poll::Pending => thread::park(),
+            }
+        }
+    }
+
+    #[test]
+    fn test_fft_async_matches_fft() {
+        let x = crate::utils::generate_random_values();
+        let expected = crate::fft::fft(&x).unwrap();
+        assert_eq!(block_on(fft_async(x)).unwrap(), expected);
+        let twelve: Vec<f64> = (0..12).map(|i| i as f64).collect();
+        let expected = crate::fft::fft(&twelve).unwrap();
+        assert_eq!(block_on(fft_async(twelve)).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_panicking_job_resumes_in_task() {
+        // More panicking jobs than workers; the pool must survive them all.
+        let workers = thread::available_parallelism().map_or(1, |n| n.get());
+        let tasks: Vec<Task<()>> = (0..2 * workers)
+            .map(|i| spawn_blocking(move || panic!("job {} failed", i)))
+            .collect();
+        for task in tasks {
+            let caught = panic::catch_unwind(AssertUnwindSafe(|| block_on(task)));
+            let payload = caught.unwrap_err();
+            assert!(payload
+                .downcast_ref::<String>()
+                .unwrap()
+                .ends_with("failed"));
+        }
+        assert_eq!(block_on(spawn_blocking(|| 6 * 7)), 42);
+    }
+
+    #[cfg(feature = "window")]
+    #[test]
+    fn test_spectrogram_async_runs_concurrently() {
+        let x = crate::utils::generate_random_values();
+        let window = crate::window::hann(64);
+        let expected = crate::stft::stft(&x, 64, 16, &window).unwrap();
+        let tasks: Vec<_> = (0..8)
+            .map(|_| spectrogram_async(x.clone(), 64, 16, window.clone()))
+            .collect();
+        for task in tasks {
+            assert_eq!(block_on(task).unwrap(), expected);
+        }
+    }
+}
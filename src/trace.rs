@@ -0,0 +1,99 @@
+// Spans around planning, execution, pipeline stages and the free transforms.
+// With the tracing feature enabled, span! opens a TRACE-level span of the
+// tracing crate, carrying the transform size and algorithm as fields, for the
+// current subscriber to time and report; without the feature it compiles to
+// nothing.
+
+// span! enters a span named name with the given fields until the end of the
+// enclosing block. The fields are only evaluated with the tracing feature.
+macro_rules! span {
+    ($name:literal, $($fields:tt)*) => {
+        #[cfg(feature = "tracing")]
+        let _span = ::tracing::trace_span!($name, $($fields)*).entered();
+    };
+}
+
+pub(crate) use span;
+
+#[cfg(all(test, feature = "tracing", feature = "window"))]
+mod tests {
+    use std::fmt::{Debug, Write};
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::{Arc, Mutex};
+    use tracing::field::{Field, Visit};
+    use tracing::span::{Attributes, Id, Record};
+    use tracing::{Event, Metadata, Subscriber};
+
+    use crate::planner::FftPlanner;
+
+    // Recorder keeps the name and fields of every span created
+    #[derive(Default)]
+    struct Recorder {
+        spans: Arc<Mutex<Vec<String>>>,
+        next_id: AtomicU64,
+    }
+
+    struct Fields<'a>(&'a mut String);
+
+    impl Visit for Fields<'_> {
+        fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+            let _ = write!(self.0, " {}={:?}", field.name(), value);
+        }
+    }
+
+    impl Subscriber for Recorder {
+        fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, span: &Attributes<'_>) -> Id {
+            let mut line = span.metadata().name().to_string();
+            span.record(&mut Fields(&mut line));
+            self.spans.lock().unwrap().push(line);
+            Id::from_u64(self.next_id.fetch_add(1, Ordering::Relaxed) + 1)
+        }
+
+        fn record(&self, _span: &Id, _values: &Record<'_>) {}
+
+        fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+        fn event(&self, _event: &Event<'_>) {}
+
+        fn enter(&self, _span: &Id) {}
+
+        fn exit(&self, _span: &Id) {}
+    }
+
+    #[test]
+    fn test_spans_are_reported() {
+        let recorder = Recorder::default();
+        let spans = recorder.spans.clone();
+        tracing::subscriber::with_default(recorder, || {
+            let plan = FftPlanner::new().plan(1 << 15).unwrap();
+            let mut buffer = vec![num::complex::Complex64::default(); 1 << 15];
+            plan.process(&mut buffer).unwrap();
+            let mut pipeline = crate::pipeline::Pipeline::builder(1 << 15).build().unwrap();
+            pipeline.process(&vec![0_f64; 1 << 15]).unwrap();
+            let spectrum = crate::fft::fft(&[1_f64, 2_f64, 3_f64]).unwrap();
+            crate::fft::ifft(&spectrum).unwrap();
+            crate::fft::rfft(&[0_f32; 64]).unwrap();
+        });
+
+        let spans = spans.lock().unwrap();
+        for expected in [
+            "plan len=32768 algorithm=\"stockham\"",
+            "execute len=32768 algorithm=\"stockham\"",
+            "pipeline len=32768",
+            "fft len=3",
+            "transform len=3 algorithm=\"mixed_radix\"",
+            "ifft len=3",
+            "rfft len=64",
+            "transform len=32 algorithm=\"radix4\"",
+        ] {
+            assert!(
+                spans.iter().any(|s| s == expected),
+                "{expected} in {spans:?}"
+            );
+        }
+    }
+}
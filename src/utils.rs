@@ -1,29 +1,149 @@
-use num::complex::Complex64;
+use alloc::vec::Vec;
+use core::iter::Sum;
+use core::ops::{Add, Div, Mul, Sub};
+use num::complex::{Complex, Complex64};
+use num::traits::Float;
 
-use rand::prelude::*;
+use crate::error::FftError;
+use crate::float::FftNum;
 
-use itertools::Itertools;
+// Vector and matrix helpers for DSP code. The element-wise functions work on
+// f64, f32, Complex64 and Complex32 slices alike and return
+// FftError::LengthMismatch when their operands differ in length instead of
+// silently truncating.
 
-// mul_mv multiplies a Matrix by a Vector
-pub fn mul_mv(m: &[Vec<Complex64>], v: &[Complex64]) -> Vec<Complex64> {
-    assert_eq!(m[0].len(), m.len());
-    assert_eq!(m.len(), v.len());
-
-    (0..m.len())
-        .map(|i| (0..m.len()).map(|j| m[i][j] * v[j]).sum())
+// mul_mv multiplies a matrix, given as rows of v.len() values, by a vector
+pub fn mul_mv<T>(m: &[Vec<T>], v: &[T]) -> Result<Vec<T>, FftError>
+where
+    T: Copy + Mul<Output = T> + Sum,
+{
+    m.iter()
+        .map(|row| {
+            check_len(v, row)?;
+            Ok(row.iter().zip(v).map(|(&a, &b)| a * b).sum())
+        })
         .collect()
 }
 
-pub fn add_vv(a: &[Complex64], b: &[Complex64]) -> Vec<Complex64> {
-    a.iter().zip_eq(b.iter()).map(|(x, y)| x + y).collect()
+fn check_len<T>(a: &[T], b: &[T]) -> Result<(), FftError> {
+    if a.len() != b.len() {
+        return Err(FftError::LengthMismatch(a.len(), b.len()));
+    }
+    Ok(())
+}
+
+fn zip_with<T: Copy>(a: &[T], b: &[T], f: impl Fn(T, T) -> T) -> Result<Vec<T>, FftError> {
+    check_len(a, b)?;
+    Ok(a.iter().zip(b).map(|(&x, &y)| f(x, y)).collect())
+}
+
+pub fn add_vv<T: Copy + Add<Output = T>>(a: &[T], b: &[T]) -> Result<Vec<T>, FftError> {
+    zip_with(a, b, |x, y| x + y)
+}
+
+pub fn sub_vv<T: Copy + Sub<Output = T>>(a: &[T], b: &[T]) -> Result<Vec<T>, FftError> {
+    zip_with(a, b, |x, y| x - y)
 }
 
 // mul_vv_el multiplies elements of one vector by the elements of another vector
-pub fn mul_vv_el(a: &[Complex64], b: &[Complex64]) -> Vec<Complex64> {
-    a.iter().zip_eq(b.iter()).map(|(x, y)| x * y).collect()
+pub fn mul_vv_el<T: Copy + Mul<Output = T>>(a: &[T], b: &[T]) -> Result<Vec<T>, FftError> {
+    zip_with(a, b, |x, y| x * y)
+}
+
+// mul_vv_complex is mul_vv_el for Complex64 vectors, vectorized with AVX
+// when the simd feature is enabled and the CPU supports it
+pub fn mul_vv_complex(a: &[Complex64], b: &[Complex64]) -> Result<Vec<Complex64>, FftError> {
+    check_len(a, b)?;
+    let mut out = a.to_vec();
+    #[cfg(feature = "simd")]
+    if crate::simd::mul_assign(&mut out, b) {
+        return Ok(out);
+    }
+    out.iter_mut().zip(b).for_each(|(x, y)| *x *= y);
+    Ok(out)
+}
+
+// div_vv_el divides elements of one vector by the elements of another vector
+pub fn div_vv_el<T: Copy + Div<Output = T>>(a: &[T], b: &[T]) -> Result<Vec<T>, FftError> {
+    zip_with(a, b, |x, y| x / y)
+}
+
+// scale_v multiplies every element by s
+pub fn scale_v<T: Copy + Mul<Output = T>>(a: &[T], s: T) -> Vec<T> {
+    a.iter().map(|&x| x * s).collect()
+}
+
+pub fn conj_v<T: FftNum>(a: &[Complex<T>]) -> Vec<Complex<T>> {
+    a.iter().map(|c| c.conj()).collect()
+}
+
+// dot returns sum a[i] * b[i], without conjugation; the Hermitian inner
+// product of complex vectors is dot(a, &conj_v(b))
+pub fn dot<T: Copy + Mul<Output = T> + Sum>(a: &[T], b: &[T]) -> Result<T, FftError> {
+    check_len(a, b)?;
+    Ok(a.iter().zip(b).map(|(&x, &y)| x * y).sum())
+}
+
+// Magnitude is implemented by the element types norm accepts.
+pub trait Magnitude {
+    fn magnitude_sqr(&self) -> f64;
+}
+
+impl Magnitude for f64 {
+    fn magnitude_sqr(&self) -> f64 {
+        self * self
+    }
 }
 
+impl Magnitude for f32 {
+    fn magnitude_sqr(&self) -> f64 {
+        let v = *self as f64;
+        v * v
+    }
+}
+
+impl<T: FftNum + Magnitude> Magnitude for Complex<T> {
+    fn magnitude_sqr(&self) -> f64 {
+        self.re.magnitude_sqr() + self.im.magnitude_sqr()
+    }
+}
+
+// norm returns the Euclidean norm sqrt(sum |a[i]|^2)
+pub fn norm<T: Magnitude>(a: &[T]) -> f64 {
+    Float::sqrt(a.iter().map(|x| x.magnitude_sqr()).sum::<f64>())
+}
+
+// parabolic_peak fits a parabola through three equally spaced samples around a
+// local maximum and returns the offset of its vertex from the middle sample
+// (in samples, within [-0.5, 0.5]) together with the interpolated peak value
+#[cfg(any(feature = "std", test))]
+pub(crate) fn parabolic_peak(a: f64, b: f64, c: f64) -> (f64, f64) {
+    let denom = a - 2_f64 * b + c;
+    if denom.abs() < f64::EPSILON {
+        return (0_f64, b);
+    }
+    let offset = (0.5 * (a - c) / denom).clamp(-0.5, 0.5);
+    (offset, b - 0.25 * (a - c) * offset)
+}
+
+// checked_len returns the number of elements of an array of the given shape
+// (batch x rows x columns, say), or FftError::SizeOverflow naming the shape
+// when the product does not fit in usize
+pub fn checked_len(shape: &[usize]) -> Result<usize, FftError> {
+    shape
+        .iter()
+        .try_fold(1_usize, |acc, &d| acc.checked_mul(d))
+        .ok_or_else(|| FftError::SizeOverflow {
+            shape: shape.to_vec(),
+        })
+}
+
+// generate_random_values needs the rand feature; the crate's own tests always
+// have it
+#[cfg(any(test, feature = "rand"))]
 pub fn generate_random_values() -> Vec<f64> {
+    use rand::Rng;
+
     let mut rng = rand::rng();
 
     // Generate 1024 random f64 values (uniformly distributed in [0, 1))
@@ -35,6 +155,16 @@ mod tests {
     use super::*;
     use num::complex::Complex64;
 
+    #[test]
+    fn test_checked_len() {
+        assert_eq!(checked_len(&[4, 8, 16]).unwrap(), 512);
+        assert_eq!(checked_len(&[]).unwrap(), 1);
+        match checked_len(&[1 << 40, 1 << 30]) {
+            Err(FftError::SizeOverflow { shape }) => assert_eq!(shape, [1 << 40, 1 << 30]),
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
     #[test]
     fn test_mul_mv() {
         // 2x2 matrix times a 2-element vector
@@ -49,7 +179,7 @@ mod tests {
         // row 1: (3*1) + (4*1) = 7
         let expected = vec![Complex64::new(3.0, 0.0), Complex64::new(7.0, 0.0)];
 
-        let result = mul_mv(&m, &v);
+        let result = mul_mv(&m, &v).unwrap();
         assert_eq!(result, expected);
     }
 
@@ -64,7 +194,7 @@ mod tests {
         // (3+4i) + (7+8i) = (10 + 12i)
         let expected = vec![Complex64::new(6.0, 8.0), Complex64::new(10.0, 12.0)];
 
-        let result = add_vv(&a, &b);
+        let result = add_vv(&a, &b).unwrap();
         assert_eq!(result, expected);
     }
 
@@ -79,10 +209,19 @@ mod tests {
         // (2+0i)*(4+1i) = 8+2i
         let expected = vec![Complex64::new(1.0, 5.0), Complex64::new(8.0, 2.0)];
 
-        let result = mul_vv_el(&a, &b);
+        let result = mul_vv_el(&a, &b).unwrap();
         assert_eq!(result, expected);
     }
 
+    #[test]
+    fn test_parabolic_peak() {
+        // Samples of -(x - 0.25)^2 at x = -1, 0, 1.
+        let f = |x: f64| -(x - 0.25) * (x - 0.25);
+        let (offset, value) = parabolic_peak(f(-1.0), f(0.0), f(1.0));
+        assert!((offset - 0.25).abs() < 1e-12);
+        assert!(value.abs() < 1e-12);
+    }
+
     #[test]
     fn test_generate_random_values() {
         let random_values = generate_random_values();
@@ -91,7 +230,32 @@ mod tests {
 
         // Check that each value is within [0, 1)
         for &val in &random_values {
-            assert!(val >= 0.0 && val < 1.0);
+            assert!((0.0..1.0).contains(&val));
         }
     }
+    #[test]
+    fn test_vector_helpers() {
+        let a = [1.0, 2.0, 3.0];
+        let b = [4.0, 5.0, 6.0];
+        assert_eq!(sub_vv(&b, &a).unwrap(), [3.0, 3.0, 3.0]);
+        assert_eq!(div_vv_el(&b, &[2.0, 5.0, 3.0]).unwrap(), [2.0, 1.0, 2.0]);
+        assert_eq!(scale_v(&a, 2.0), [2.0, 4.0, 6.0]);
+        assert_eq!(dot(&a, &b).unwrap(), 32.0);
+        assert_eq!(norm(&[3.0, 4.0]), 5.0);
+        assert!(matches!(
+            add_vv(&a, &b[..2]),
+            Err(FftError::LengthMismatch(3, 2))
+        ));
+
+        let c = [Complex64::new(1.0, 1.0), Complex64::new(0.0, 2.0)];
+        assert_eq!(
+            conj_v(&c),
+            [Complex64::new(1.0, -1.0), Complex64::new(0.0, -2.0)]
+        );
+        assert_eq!(dot(&c, &conj_v(&c)).unwrap(), Complex64::new(6.0, 0.0));
+        assert!((norm(&c) - 6_f64.sqrt()).abs() < 1e-15);
+        let m = vec![vec![1.0, 0.0, 2.0]];
+        assert_eq!(mul_mv(&m, &a).unwrap(), [7.0]);
+        assert!(mul_mv(&m, &a[..2]).is_err());
+    }
 }
@@ -0,0 +1,138 @@
+use alloc::borrow::Cow;
+use core::sync::atomic::{AtomicU8, Ordering};
+use num::complex::Complex;
+
+use crate::error::FftError;
+use crate::float::FftNum;
+
+// Validation decides what the transforms do with NaN and infinite input
+// values. A single NaN spreads into every output bin, so Error reports the
+// index of the first offending value instead, and SanitizeToZero replaces
+// such values with zero. Off skips the scan and is the default.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Validation {
+    #[default]
+    Off,
+    Error,
+    SanitizeToZero,
+}
+
+static DEFAULT: AtomicU8 = AtomicU8::new(0);
+
+// set_default_validation sets the mode of the free transform functions and
+// of planners created afterwards. The _with variants of the transforms,
+// FftPlanner::validation and FftPlan::with_validation override it for one
+// call or plan.
+pub fn set_default_validation(mode: Validation) {
+    DEFAULT.store(mode as u8, Ordering::Relaxed);
+}
+
+// default_validation returns the mode set by set_default_validation
+pub fn default_validation() -> Validation {
+    match DEFAULT.load(Ordering::Relaxed) {
+        1 => Validation::Error,
+        2 => Validation::SanitizeToZero,
+        _ => Validation::Off,
+    }
+}
+
+impl Validation {
+    // apply validates x in place
+    pub fn apply<T: FftNum>(self, x: &mut [Complex<T>]) -> Result<(), FftError> {
+        match self {
+            Validation::Off => Ok(()),
+            Validation::Error => match first_non_finite(x) {
+                Some(i) => Err(FftError::NonFinite(i)),
+                None => Ok(()),
+            },
+            Validation::SanitizeToZero => {
+                x.iter_mut()
+                    .filter(|c| !is_finite(c))
+                    .for_each(|c| *c = Complex::default());
+                Ok(())
+            }
+        }
+    }
+
+    // check validates x, copying it only if values have to be replaced
+    pub fn check<T: FftNum>(self, x: &[Complex<T>]) -> Result<Cow<'_, [Complex<T>]>, FftError> {
+        if self == Validation::Off || first_non_finite(x).is_none() {
+            return Ok(Cow::Borrowed(x));
+        }
+        let mut owned = x.to_vec();
+        self.apply(&mut owned)?;
+        Ok(Cow::Owned(owned))
+    }
+}
+
+fn first_non_finite<T: FftNum>(x: &[Complex<T>]) -> Option<usize> {
+    x.iter().position(|c| !is_finite(c))
+}
+
+fn is_finite<T: FftNum>(c: &Complex<T>) -> bool {
+    c.re.is_finite() && c.im.is_finite()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num::complex::Complex64;
+
+    #[test]
+    fn test_validation_modes() {
+        let x = [
+            Complex64::new(1_f64, 0_f64),
+            Complex64::new(f64::NAN, 0_f64),
+            Complex64::new(0_f64, f64::INFINITY),
+        ];
+        assert!(matches!(Validation::Off.check(&x), Ok(Cow::Borrowed(_))));
+        assert!(matches!(
+            Validation::Error.check(&x),
+            Err(FftError::NonFinite(1))
+        ));
+        let clean = Validation::SanitizeToZero.check(&x).unwrap();
+        assert_eq!(clean[0], x[0]);
+        assert_eq!(clean[1], Complex64::default());
+        assert_eq!(clean[2], Complex64::default());
+        assert!(matches!(
+            Validation::Error.check(&clean),
+            Ok(Cow::Borrowed(_))
+        ));
+    }
+
+    #[test]
+    fn test_default_and_per_call_validation() {
+        use crate::fft::{fft, fft_complex_with, fft_with, ifft, ifft_with};
+
+        let x = [1_f64, f64::NAN, 3_f64, 4_f64];
+        let spectrum = [Complex64::new(f64::INFINITY, 0_f64); 4];
+        set_default_validation(Validation::Error);
+        let from_default = (fft(&x), ifft(&spectrum));
+        #[cfg(feature = "std")]
+        let planner = crate::planner::FftPlanner::new();
+        set_default_validation(Validation::Off);
+
+        assert_eq!(default_validation(), Validation::Off);
+        assert!(matches!(from_default.0, Err(FftError::NonFinite(1))));
+        assert!(matches!(from_default.1, Err(FftError::NonFinite(0))));
+        #[cfg(feature = "std")]
+        assert_eq!(planner.plan(4).unwrap().validation(), Validation::Error);
+        // With the default Off, NaN spreads unless a call asks otherwise.
+        assert!(fft(&x).unwrap().iter().all(|c| c.is_nan()));
+        assert!(matches!(
+            fft_with(&x, Validation::Error),
+            Err(FftError::NonFinite(1))
+        ));
+        let clean = fft_with(&x, Validation::SanitizeToZero).unwrap();
+        assert_eq!(clean[0], Complex64::new(8_f64, 0_f64));
+        let z: [Complex64; 4] = core::array::from_fn(|i| Complex64::new(x[i], 0_f64));
+        assert_eq!(
+            fft_complex_with(&z, Validation::SanitizeToZero).unwrap(),
+            clean
+        );
+        assert_eq!(
+            ifft_with(&spectrum, Validation::SanitizeToZero).unwrap(),
+            [0_f64; 4]
+        );
+    }
+}
@@ -0,0 +1,160 @@
+use num::complex::Complex64;
+
+use crate::error::FftError;
+
+// vDSP works on split complex data, the real and imaginary parts in two
+// separate arrays, while this crate interleaves them in Complex64. split and
+// join convert between the two layouts.
+
+// split returns the real and imaginary parts of x
+pub fn split(x: &[Complex64]) -> (Vec<f64>, Vec<f64>) {
+    x.iter().map(|c| (c.re, c.im)).unzip()
+}
+
+// join interleaves real and imaginary parts into out
+pub fn join(re: &[f64], im: &[f64], out: &mut [Complex64]) -> Result<(), FftError> {
+    if re.len() != im.len() {
+        return Err(FftError::LengthMismatch(re.len(), im.len()));
+    }
+    if out.len() != re.len() {
+        return Err(FftError::LengthMismatch(re.len(), out.len()));
+    }
+    for (c, (&r, &i)) in out.iter_mut().zip(re.iter().zip(im.iter())) {
+        *c = Complex64::new(r, i);
+    }
+    Ok(())
+}
+
+#[cfg(target_vendor = "apple")]
+pub use self::apple::VdspFftPlan;
+
+#[cfg(target_vendor = "apple")]
+mod apple {
+    use num::complex::Complex64;
+    use std::ffi::{c_int, c_long, c_ulong, c_void};
+
+    use super::{join, split};
+    use crate::error::FftError;
+
+    // Bindings to the double-precision FFT of vDSP, declared from vDSP.h of
+    // the Accelerate framework as shipped since macOS 10.4.
+    #[repr(C)]
+    struct DSPDoubleSplitComplex {
+        realp: *mut f64,
+        imagp: *mut f64,
+    }
+
+    type FFTSetupD = *mut c_void;
+
+    const FFT_RADIX2: c_int = 0;
+    // vDSP's inverse direction uses the exp(+) kernel of fft, without scaling.
+    const FFT_INVERSE: c_int = -1;
+
+    #[link(name = "Accelerate", kind = "framework")]
+    extern "C" {
+        fn vDSP_create_fftsetupD(log2n: c_ulong, radix: c_int) -> FFTSetupD;
+        fn vDSP_destroy_fftsetupD(setup: FFTSetupD);
+        fn vDSP_fft_zipD(
+            setup: FFTSetupD,
+            c: *const DSPDoubleSplitComplex,
+            stride: c_long,
+            log2n: c_ulong,
+            direction: c_int,
+        );
+    }
+
+    // VdspFftPlan is a forward transform of a fixed length computed by the
+    // Accelerate framework, used like FftPlan. A vDSP setup is only read while
+    // transforming, so one plan may be shared between threads.
+    #[derive(Debug)]
+    pub struct VdspFftPlan {
+        len: usize,
+        log2n: c_ulong,
+        setup: FFTSetupD,
+    }
+
+    unsafe impl Send for VdspFftPlan {}
+    unsafe impl Sync for VdspFftPlan {}
+
+    impl VdspFftPlan {
+        pub fn new(len: usize) -> Result<Self, FftError> {
+            if !len.is_power_of_two() {
+                return Err(FftError::NotAPowerOfTwo(len));
+            }
+            let log2n = len.trailing_zeros() as c_ulong;
+            let setup = unsafe { vDSP_create_fftsetupD(log2n, FFT_RADIX2) };
+            if setup.is_null() {
+                return Err(FftError::Backend("vDSP_create_fftsetupD failed"));
+            }
+            Ok(VdspFftPlan { len, log2n, setup })
+        }
+
+        pub fn len(&self) -> usize {
+            self.len
+        }
+
+        pub fn is_empty(&self) -> bool {
+            self.len == 0
+        }
+
+        // process transforms buffer in place
+        pub fn process(&self, buffer: &mut [Complex64]) -> Result<(), FftError> {
+            if buffer.len() != self.len {
+                return Err(FftError::LengthMismatch(self.len, buffer.len()));
+            }
+            let (mut re, mut im) = split(buffer);
+            let data = DSPDoubleSplitComplex {
+                realp: re.as_mut_ptr(),
+                imagp: im.as_mut_ptr(),
+            };
+            // SAFETY: both arrays hold len values, the size of the setup.
+            unsafe { vDSP_fft_zipD(self.setup, &data, 1, self.log2n, FFT_INVERSE) };
+            join(&re, &im, buffer)
+        }
+    }
+
+    impl Drop for VdspFftPlan {
+        fn drop(&mut self) {
+            unsafe { vDSP_destroy_fftsetupD(self.setup) };
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn test_vdsp_matches_fft() {
+            let x: Vec<Complex64> = crate::utils::generate_random_values()
+                .chunks(2)
+                .map(|c| Complex64::new(c[0], c[1]))
+                .collect();
+            let mut y = x.clone();
+            VdspFftPlan::new(512).unwrap().process(&mut y).unwrap();
+            let expected = crate::fft::fft_complex(&x).unwrap();
+            for (a, b) in y.iter().zip(expected.iter()) {
+                assert!((a - b).norm() < 1e-9);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_join_round_trip() {
+        let x = vec![Complex64::new(1.0, -1.0), Complex64::new(2.5, 0.5)];
+        let (re, im) = split(&x);
+        assert_eq!(re, [1.0, 2.5]);
+        assert_eq!(im, [-1.0, 0.5]);
+        let mut y = vec![Complex64::default(); 2];
+        join(&re, &im, &mut y).unwrap();
+        assert_eq!(y, x);
+        assert!(matches!(
+            join(&re, &im[..1], &mut y),
+            Err(FftError::LengthMismatch(2, 1))
+        ));
+    }
+}
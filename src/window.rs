@@ -0,0 +1,393 @@
+use std::f64::consts::PI;
+
+use crate::error::FftError;
+
+// hann returns a periodic Hann window of the given length. The periodic form
+// (denominator len instead of len - 1) overlap-adds to a constant at hops of
+// len / 2 and len / 4, which is what the STFT expects.
+pub fn hann(len: usize) -> Vec<f64> {
+    if len == 1 {
+        return vec![1_f64];
+    }
+    (0..len)
+        .map(|i| 0.5 - 0.5 * (2_f64 * PI * i as f64 / len as f64).cos())
+        .collect()
+}
+
+// bessel_i0 evaluates the zeroth-order modified Bessel function of the first
+// kind by its power series
+pub(crate) fn bessel_i0(x: f64) -> f64 {
+    let mut sum = 1_f64;
+    let mut term = 1_f64;
+    let half = x / 2_f64;
+    for k in 1..64 {
+        term *= half / k as f64;
+        let t = term * term;
+        sum += t;
+        if t < sum * 1e-17 {
+            break;
+        }
+    }
+    sum
+}
+
+// kaiser_beta returns the Kaiser window shape parameter giving a stopband
+// attenuation of attenuation_db dB (Kaiser's empirical formula)
+pub fn kaiser_beta(attenuation_db: f64) -> f64 {
+    if attenuation_db > 50_f64 {
+        0.1102 * (attenuation_db - 8.7)
+    } else if attenuation_db > 21_f64 {
+        0.5842 * (attenuation_db - 21_f64).powf(0.4) + 0.07886 * (attenuation_db - 21_f64)
+    } else {
+        0_f64
+    }
+}
+
+// KaiserSpec is a Kaiser window design: the window of len taps and shape
+// beta that meets a stopband attenuation and transition width.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct KaiserSpec {
+    pub beta: f64,
+    pub len: usize,
+}
+
+impl KaiserSpec {
+    // window returns the designed window
+    pub fn window(&self) -> Vec<f64> {
+        kaiser(self.len, self.beta)
+    }
+}
+
+// kaiser_from_spec designs the Kaiser window for a windowed-sinc FIR filter
+// with the given stopband attenuation in dB and transition width in cycles
+// per sample (a fraction of the sample rate, between 0 and 0.5), using
+// Kaiser's formulas for beta and for the length
+// (A - 8) / (2.285 * 2 pi * width) + 1. The length is rounded up to an odd
+// number so that the filter has an integer delay.
+pub fn kaiser_from_spec(stopband_db: f64, transition_width: f64) -> Result<KaiserSpec, FftError> {
+    if !(stopband_db > 0_f64 && stopband_db.is_finite()) {
+        return Err(FftError::InvalidParameter(
+            "stopband attenuation must be positive",
+        ));
+    }
+    if !(transition_width > 0_f64 && transition_width <= 0.5) {
+        return Err(FftError::InvalidParameter(
+            "transition width must be between 0 and 0.5 cycles per sample",
+        ));
+    }
+    let taps = (stopband_db - 8_f64).max(0_f64) / (2.285 * 2_f64 * PI * transition_width);
+    let len = taps.ceil() as usize + 1;
+    Ok(KaiserSpec {
+        beta: kaiser_beta(stopband_db),
+        len: len | 1,
+    })
+}
+
+// kaiser_value returns the Kaiser window with shape beta at x, where x runs
+// from -1 to 1 across the window
+pub(crate) fn kaiser_value(x: f64, beta: f64) -> f64 {
+    if x.abs() > 1_f64 {
+        return 0_f64;
+    }
+    bessel_i0(beta * (1_f64 - x * x).sqrt()) / bessel_i0(beta)
+}
+
+// kaiser returns a symmetric Kaiser window of the given length and shape
+// parameter beta; beta = 0 is rectangular and larger values trade main-lobe
+// width for lower side lobes
+pub fn kaiser(len: usize, beta: f64) -> Vec<f64> {
+    if len == 1 {
+        return vec![1_f64];
+    }
+    let half = (len - 1) as f64 / 2_f64;
+    (0..len)
+        .map(|i| kaiser_value((i as f64 - half) / half, beta))
+        .collect()
+}
+
+// tukey returns a symmetric Tukey (tapered cosine) window: a flat top with
+// cosine tapers over the fraction alpha of the length, split between both
+// ends. alpha = 0 is rectangular and alpha = 1 is a Hann window; small
+// values keep most of the signal at full weight, which suits transients.
+pub fn tukey(len: usize, alpha: f64) -> Vec<f64> {
+    if len == 1 {
+        return vec![1_f64];
+    }
+    let alpha = alpha.clamp(0_f64, 1_f64);
+    let last = (len - 1) as f64;
+    let taper = alpha * last / 2_f64;
+    (0..len)
+        .map(|i| {
+            let d = (i as f64).min(last - i as f64);
+            if d >= taper {
+                1_f64
+            } else {
+                0.5 - 0.5 * (PI * d / taper).cos()
+            }
+        })
+        .collect()
+}
+
+// gaussian returns a symmetric Gaussian window whose standard deviation is
+// sigma times half the length. Its transform is again Gaussian, without
+// side lobes in the untruncated limit; sigma around 0.4 balances main-lobe
+// width against the truncation steps at the ends. sigma must be finite and
+// positive.
+pub fn gaussian(len: usize, sigma: f64) -> Result<Vec<f64>, FftError> {
+    if !(sigma.is_finite() && sigma > 0_f64) {
+        return Err(FftError::InvalidParameter(
+            "sigma must be finite and positive",
+        ));
+    }
+    if len == 1 {
+        return Ok(vec![1_f64]);
+    }
+    let half = (len - 1) as f64 / 2_f64;
+    Ok((0..len)
+        .map(|i| {
+            let x = (i as f64 - half) / (sigma * half);
+            (-0.5 * x * x).exp()
+        })
+        .collect())
+}
+
+// flat_top returns a periodic flat-top window (five-term cosine sum). Its
+// main lobe is flat to within 0.02 dB over a bin, so a sinusoid between bins
+// is still measured at its true amplitude once divided by coherent_gain, at
+// the price of a wide main lobe (poor frequency resolution).
+pub fn flat_top(len: usize) -> Vec<f64> {
+    const A: [f64; 5] = [
+        0.21557895,
+        0.41663158,
+        0.277263158,
+        0.083578947,
+        0.006947368,
+    ];
+    if len == 1 {
+        return vec![1_f64];
+    }
+    (0..len)
+        .map(|i| {
+            let x = 2_f64 * PI * i as f64 / len as f64;
+            A.iter()
+                .enumerate()
+                .map(|(k, a)| if k % 2 == 0 { 1_f64 } else { -1_f64 } * a * (k as f64 * x).cos())
+                .sum()
+        })
+        .collect()
+}
+
+// chebyshev returns a symmetric Dolph-Chebyshev window, normalized to a peak
+// of 1, whose side lobes all lie sidelobe_db dB below the main lobe. For a
+// given side-lobe level it has the narrowest main lobe of any window; the
+// equal-height side lobes do not decay, so distant strong signals leak as
+// much as nearby ones.
+pub fn chebyshev(len: usize, sidelobe_db: f64) -> Vec<f64> {
+    if len <= 1 {
+        return vec![1_f64; len];
+    }
+    let n = len as f64;
+    let order = n - 1_f64;
+    let x0 = ((10_f64.powf(sidelobe_db.abs() / 20_f64)).acosh() / order).cosh();
+    // The window is the inverse transform of the Chebyshev polynomial
+    // T_order sampled around the unit circle, centred on the middle sample.
+    let spectrum: Vec<f64> = (0..len)
+        .map(|k| {
+            let x = x0 * (PI * k as f64 / n).cos();
+            if x > 1_f64 {
+                (order * x.acosh()).cosh()
+            } else if x < -1_f64 {
+                let sign = if len % 2 == 1 { 1_f64 } else { -1_f64 };
+                sign * (order * (-x).acosh()).cosh()
+            } else {
+                (order * x.acos()).cos()
+            }
+        })
+        .collect();
+    let mut w: Vec<f64> = (0..len)
+        .map(|i| {
+            let t = i as f64 - order / 2_f64;
+            spectrum
+                .iter()
+                .enumerate()
+                .map(|(k, p)| p * (2_f64 * PI * k as f64 * t / n).cos())
+                .sum()
+        })
+        .collect();
+    let peak = w.iter().cloned().fold(f64::MIN, f64::max);
+    w.iter_mut().for_each(|v| *v /= peak);
+    w
+}
+
+// planck_taper returns a symmetric Planck-taper window: flat except for
+// the fraction epsilon of the length at each end, where it rises from 0 to 1
+// along a smooth bump function. Being infinitely differentiable, it has side
+// lobes falling faster than any power of frequency, useful for
+// gravitational-wave and other high-dynamic-range work. epsilon is clamped
+// to [0, 0.5]; 0 gives the rectangular window.
+pub fn planck_taper(len: usize, epsilon: f64) -> Vec<f64> {
+    let epsilon = epsilon.clamp(0_f64, 0.5);
+    // with no taper, or no sample between the two zero end points, keep the
+    // flat top
+    if len <= 2 || epsilon.is_nan() || epsilon == 0_f64 {
+        return vec![1_f64; len];
+    }
+    let last = (len - 1) as f64;
+    let taper = epsilon * last;
+    (0..len)
+        .map(|i| {
+            let d = (i as f64).min(last - i as f64);
+            if d == 0_f64 {
+                0_f64
+            } else if d >= taper {
+                1_f64
+            } else {
+                1_f64 / ((taper / d - taper / (taper - d)).exp() + 1_f64)
+            }
+        })
+        .collect()
+}
+
+// coherent_gain returns the mean of the window, the factor by which it
+// scales the amplitude of a sinusoid centred on a bin; divide spectrum
+// magnitudes by it to read amplitudes
+pub fn coherent_gain(w: &[f64]) -> Result<f64, FftError> {
+    if w.is_empty() {
+        return Err(FftError::InvalidParameter("window must not be empty"));
+    }
+    Ok(w.iter().sum::<f64>() / w.len() as f64)
+}
+
+// enbw returns the equivalent noise bandwidth of the window in bins: the
+// factor by which it widens each bin for broadband noise, i.e. the
+// correction for power spectral densities (1 for rectangular, 1.5 for Hann,
+// about 3.8 for flat-top). A window summing to zero has no finite ENBW.
+pub fn enbw(w: &[f64]) -> Result<f64, FftError> {
+    if w.is_empty() {
+        return Err(FftError::InvalidParameter("window must not be empty"));
+    }
+    let sum: f64 = w.iter().sum();
+    if sum == 0_f64 {
+        return Err(FftError::InvalidParameter("window must not sum to zero"));
+    }
+    Ok(w.len() as f64 * w.iter().map(|v| v * v).sum::<f64>() / (sum * sum))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hann_values() {
+        let w = hann(4);
+        assert_eq!(w.len(), 4);
+        assert!((w[0] - 0.0).abs() < 1e-12);
+        assert!((w[1] - 0.5).abs() < 1e-12);
+        assert!((w[2] - 1.0).abs() < 1e-12);
+        assert!((w[3] - 0.5).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_kaiser_values() {
+        assert!((bessel_i0(1.0) - 1.2660658777520082).abs() < 1e-12);
+        let w = kaiser(9, 8.6);
+        assert!((w[4] - 1.0).abs() < 1e-12);
+        assert!((w[0] - w[8]).abs() < 1e-12);
+        assert!((w[0] - 1.0 / bessel_i0(8.6)).abs() < 1e-12);
+        assert_eq!(kaiser(5, 0.0), vec![1.0; 5]);
+        assert!((kaiser_beta(60.0) - 5.65326).abs() < 1e-5);
+
+        let spec = kaiser_from_spec(60.0, 0.05).unwrap();
+        assert_eq!(spec.len, 75);
+        assert_eq!(spec.beta, kaiser_beta(60.0));
+        assert_eq!(spec.window(), kaiser(75, spec.beta));
+        assert!(matches!(
+            kaiser_from_spec(60.0, 0.0),
+            Err(FftError::InvalidParameter(_))
+        ));
+    }
+
+    #[test]
+    fn test_hann_overlap_add_is_constant() {
+        let len = 16;
+        let w = hann(len);
+        // Shifted copies at a hop of len / 2 must sum to 1.
+        for i in 0..len / 2 {
+            assert!((w[i] + w[i + len / 2] - 1.0).abs() < 1e-12);
+        }
+    }
+    #[test]
+    fn test_window_shapes() {
+        assert_eq!(tukey(8, 0.0), vec![1.0; 8]);
+        for (a, b) in tukey(9, 1.0).iter().zip(symmetric_hann(9).iter()) {
+            assert!((a - b).abs() < 1e-12);
+        }
+        let g = gaussian(9, 0.5).unwrap();
+        assert!((g[4] - 1.0).abs() < 1e-12 && (g[0] - (-2_f64).exp()).abs() < 1e-12);
+        for sigma in [0.0, -0.5, f64::NAN, f64::INFINITY] {
+            assert!(matches!(
+                gaussian(5, sigma),
+                Err(FftError::InvalidParameter(_))
+            ));
+        }
+        let p = planck_taper(101, 0.1);
+        assert_eq!((p[0], p[50], p[100]), (0.0, 1.0, 0.0));
+        assert!(p[5] > 0.0 && p[5] < 1.0 && (p[5] - p[95]).abs() < 1e-12);
+        assert_eq!(planck_taper(2, 0.1), vec![1.0; 2]);
+        assert_eq!(planck_taper(8, 0.0), vec![1.0; 8]);
+        assert_eq!(planck_taper(8, -1.0), vec![1.0; 8]);
+        // A single sample is the rectangular window, as in scipy.
+        for w in [hann(1), flat_top(1), tukey(1, 0.5), planck_taper(1, 0.1)] {
+            assert_eq!(w, vec![1.0]);
+        }
+    }
+
+    // symmetric_hann is the symmetric Hann window
+    fn symmetric_hann(len: usize) -> Vec<f64> {
+        (0..len)
+            .map(|i| 0.5 - 0.5 * (2_f64 * PI * i as f64 / (len - 1) as f64).cos())
+            .collect()
+    }
+
+    #[test]
+    fn test_chebyshev_side_lobes() {
+        let w = chebyshev(31, 60.0);
+        assert!((w[15] - 1.0).abs() < 1e-12 && (w[0] - w[30]).abs() < 1e-12);
+        // Sample the response beyond the main lobe, whose first null is near
+        // 0.08 cycles per sample, and check the highest side lobe.
+        let response = |f: f64| {
+            let (re, im) = w
+                .iter()
+                .enumerate()
+                .fold((0_f64, 0_f64), |(re, im), (i, v)| {
+                    let phase = 2_f64 * PI * f * i as f64;
+                    (re + v * phase.cos(), im + v * phase.sin())
+                });
+            (re * re + im * im).sqrt()
+        };
+        let peak = response(0.0);
+        let side = (900..5000)
+            .map(|i| response(i as f64 / 10000.0))
+            .fold(0_f64, f64::max);
+        assert!((20.0 * (side / peak).log10() + 60.0).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_correction_factors() {
+        assert!((coherent_gain(&hann(64)).unwrap() - 0.5).abs() < 1e-12);
+        assert!((enbw(&hann(64)).unwrap() - 1.5).abs() < 1e-12);
+        assert!((enbw(&[1.0; 16]).unwrap() - 1.0).abs() < 1e-12);
+        assert!(coherent_gain(&[]).is_err() && enbw(&[]).is_err());
+        assert!(enbw(&[1.0, -1.0]).is_err());
+        // A sinusoid half a bin off is still read at its amplitude.
+        let len = 256;
+        let w = flat_top(len);
+        let x: Vec<f64> = (0..len)
+            .map(|i| 3.0 * (2_f64 * PI * 20.5 * i as f64 / len as f64).cos() * w[i])
+            .collect();
+        let spectrum = crate::fft::fft(&x).unwrap();
+        let peak = spectrum.iter().map(|c| c.norm()).fold(0_f64, f64::max);
+        let amplitude = 2.0 * peak / len as f64 / coherent_gain(&w).unwrap();
+        assert!((amplitude - 3.0).abs() < 3.0 * 0.01);
+    }
+}
@@ -0,0 +1,165 @@
+use num::complex::Complex64;
+
+use crate::butterflies::butterfly;
+use crate::error::FftError;
+
+// Small prime transforms for the leaves of the prime-factor algorithm, where
+// coprime factors leave lengths that the radix-2 code cannot split. They
+// follow Winograd's approach of folding the input into the sums and
+// differences x[k] +/- x[p - k] before multiplying, so that every constant
+// is real: 3 needs 2 real multiplications by a constant per component, 5
+// uses Winograd's 5-multiplication form and 7, 11 and 13 take ((p - 1) / 2)^2
+// multiplications each for the cosine and sine halves instead of the p^2
+// complex ones of a DFT.
+
+// has_winograd tells whether winograd handles length n
+pub const fn has_winograd(n: usize) -> bool {
+    matches!(n, 3 | 5 | 7 | 11 | 13)
+}
+
+// winograd transforms x in place if its length is one of the small primes
+pub fn winograd(x: &mut [Complex64]) -> Result<(), FftError> {
+    match x.len() {
+        3 | 7 => butterfly(x)?,
+        5 => winograd5(x),
+        11 => symmetric::<11, 5>(x, &COS_11, &SIN_11),
+        13 => symmetric::<13, 6>(x, &COS_13, &SIN_13),
+        _ => {
+            return Err(FftError::InvalidParameter(
+                "no Winograd kernel for this length",
+            ))
+        }
+    }
+    Ok(())
+}
+
+// i_times returns i * c
+fn i_times(c: Complex64) -> Complex64 {
+    Complex64::new(-c.im, c.re)
+}
+
+// With u = 2 pi / 5: (cos u + cos 2u) / 2 - 1, (cos u - cos 2u) / 2,
+// sin u, sin u + sin 2u and sin u - sin 2u
+const W5: [f64; 5] = [
+    -1.25,
+    0.5590169943749475,
+    0.9510565162951535,
+    1.5388417685876268,
+    0.3632712640026803,
+];
+
+fn winograd5(x: &mut [Complex64]) {
+    let t1 = x[1] + x[4];
+    let t2 = x[2] + x[3];
+    let t3 = x[1] - x[4];
+    let t4 = x[3] - x[2];
+    let t5 = t1 + t2;
+    let x0 = x[0] + t5;
+    let m1 = t5 * W5[0];
+    let m2 = (t1 - t2) * W5[1];
+    let m3 = i_times(t3 + t4) * W5[2];
+    let m4 = i_times(t4) * W5[3];
+    let m5 = i_times(t3) * W5[4];
+    let s1 = x0 + m1;
+    let s2 = s1 + m2;
+    let s3 = m3 - m4;
+    let s4 = s1 - m2;
+    let s5 = m3 - m5;
+    x[0] = x0;
+    x[1] = s2 + s3;
+    x[4] = s2 - s3;
+    x[2] = s4 + s5;
+    x[3] = s4 - s5;
+}
+
+// cos(2 pi m / p) and sin(2 pi m / p) for m in 1..=(p - 1) / 2
+const COS_11: [f64; 5] = [
+    0.8412535328311812,
+    0.41541501300188644,
+    -0.142314838273285,
+    -0.654860733945285,
+    -0.9594929736144974,
+];
+const SIN_11: [f64; 5] = [
+    0.5406408174555976,
+    0.9096319953545183,
+    0.9898214418809328,
+    0.7557495743542583,
+    0.28173255684142967,
+];
+const COS_13: [f64; 6] = [
+    0.8854560256532099,
+    0.5680647467311559,
+    0.120536680255323,
+    -0.35460488704253545,
+    -0.7485107481711012,
+    -0.970941817426052,
+];
+const SIN_13: [f64; 6] = [
+    0.4647231720437685,
+    0.8229838658936564,
+    0.992708874098054,
+    0.9350162426854148,
+    0.6631226582407952,
+    0.23931566428755768,
+];
+
+// symmetric computes a length P = 2 H + 1 transform from the sums t[k] and
+// differences d[k] of x[k] and x[P - k]: output j is
+// x0 + sum t[k] cos(2 pi j k / P) +/- i sum d[k] sin(2 pi j k / P)
+fn symmetric<const P: usize, const H: usize>(x: &mut [Complex64], cos: &[f64; H], sin: &[f64; H]) {
+    let x0 = x[0];
+    let mut t = [Complex64::default(); H];
+    let mut d = [Complex64::default(); H];
+    for k in 0..H {
+        t[k] = x[k + 1] + x[P - 1 - k];
+        d[k] = x[k + 1] - x[P - 1 - k];
+    }
+    x[0] = t.iter().fold(x0, |acc, v| acc + v);
+    for j in 1..=H {
+        let mut m = x0;
+        let mut s = Complex64::default();
+        for k in 1..=H {
+            // Fold j k mod P into the first half period.
+            let r = j * k % P;
+            let (c, sign, i) = if r <= H {
+                (cos[r - 1], 1_f64, r - 1)
+            } else {
+                (cos[P - r - 1], -1_f64, P - r - 1)
+            };
+            m += t[k - 1] * c;
+            s += d[k - 1] * (sign * sin[i]);
+        }
+        let s = i_times(s);
+        x[j] = m + s;
+        x[P - j] = m - s;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dft::dft_complex;
+
+    #[test]
+    fn test_winograd_matches_dft() {
+        let values = crate::utils::generate_random_values();
+        for n in [3, 5, 7, 11, 13] {
+            assert!(has_winograd(n));
+            let x: Vec<Complex64> = (0..n)
+                .map(|i| Complex64::new(values[2 * i], values[2 * i + 1]))
+                .collect();
+            let mut y = x.clone();
+            winograd(&mut y).unwrap();
+            let expected = dft_complex(&x).unwrap();
+            for (k, (a, b)) in y.iter().zip(expected.iter()).enumerate() {
+                assert!((a - b).norm() < 1e-12, "size {} bin {}", n, k);
+            }
+        }
+        let mut x = vec![Complex64::default(); 9];
+        assert!(matches!(
+            winograd(&mut x),
+            Err(FftError::InvalidParameter(_))
+        ));
+    }
+}